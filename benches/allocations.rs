@@ -0,0 +1,119 @@
+//! Allocation counts for the buffer-reusing scan methods.
+//!
+//! Kept out of `patterns.rs` because the counting allocator is global to the
+//! binary and would skew every timing there. Run with
+//! `cargo bench --bench allocations`; it prints one line per method, e.g.
+//!
+//! ```text
+//! scan_into: 1 allocations per 10k-bar scan (scan: 17)
+//! ```
+//!
+//! and fails if a warmed `*_into` call allocates as much as its allocating
+//! counterpart.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use yacpd::prelude::*;
+
+/// System allocator that counts allocations
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Allocations performed by `f`
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TestBar {
+    o: f64,
+    h: f64,
+    l: f64,
+    c: f64,
+}
+
+impl OHLCV for TestBar {
+    fn open(&self) -> f64 {
+        self.o
+    }
+
+    fn high(&self) -> f64 {
+        self.h
+    }
+
+    fn low(&self) -> f64 {
+        self.l
+    }
+
+    fn close(&self) -> f64 {
+        self.c
+    }
+
+    fn volume(&self) -> f64 {
+        1000.0
+    }
+}
+
+/// Same deterministic series as `patterns.rs`
+fn generate_bars(n: usize) -> Vec<TestBar> {
+    let mut bars = Vec::with_capacity(n);
+    let mut price = 100.0;
+
+    for i in 0..n {
+        let change = ((i * 7 + 13) % 100) as f64 / 50.0 - 1.0;
+        let volatility = 2.0 + ((i * 3) % 10) as f64 / 5.0;
+
+        let o = price;
+        let c = price + change;
+        let h = o.max(c) + volatility * 0.5;
+        let l = o.min(c) - volatility * 0.5;
+
+        bars.push(TestBar { o, h, l, c });
+        price = c;
+    }
+
+    bars
+}
+
+fn main() {
+    let bars = generate_bars(10_000);
+    let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+    let mut flat = Vec::new();
+    let mut grouped = Vec::new();
+    // Warm the buffers so the counts show steady-state reuse
+    engine.scan_into(&bars, &mut flat).unwrap();
+    engine.scan_grouped_into(&bars, &mut grouped).unwrap();
+
+    let scan = count_allocations(|| drop(engine.scan(&bars)));
+    let scan_into = count_allocations(|| engine.scan_into(&bars, &mut flat).unwrap());
+    println!("scan_into: {scan_into} allocations per 10k-bar scan (scan: {scan})");
+    assert!(scan_into < scan, "scan_into does not reuse its buffer");
+
+    let grouped_scan = count_allocations(|| drop(engine.scan_grouped(&bars)));
+    let grouped_into = count_allocations(|| engine.scan_grouped_into(&bars, &mut grouped).unwrap());
+    println!(
+        "scan_grouped_into: {grouped_into} allocations per 10k-bar scan (scan_grouped: {grouped_scan})"
+    );
+    assert!(
+        grouped_into < grouped_scan,
+        "scan_grouped_into does not reuse its buffers"
+    );
+}
@@ -1,7 +1,8 @@
 //! Benchmarks for candlestick pattern detection.
 
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use yacpd::prelude::*;
+use yacpd::DefaultContextProvider;
 
 /// Simple test bar structure
 #[derive(Debug, Clone, Copy)]
@@ -100,6 +101,22 @@ fn bench_scaling(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_all_patterns_1m(c: &mut Criterion) {
+    let bars = generate_bars(1_000_000);
+
+    let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+
+    let mut group = c.benchmark_group("throughput");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(bars.len() as u64));
+    group.bench_function("scan_all_patterns_1m_bars", |b| {
+        b.iter(|| {
+            let _ = black_box(engine.scan(black_box(&bars)));
+        })
+    });
+    group.finish();
+}
+
 fn bench_parallel_scan(c: &mut Criterion) {
     let bars1 = generate_bars(1000);
     let bars2 = generate_bars(1000);
@@ -137,6 +154,27 @@ fn bench_context_computation(c: &mut Criterion) {
     });
 }
 
+fn bench_context_computation_parallel(c: &mut Criterion) {
+    let bars = generate_bars(1_000_000);
+
+    let provider = DefaultContextProvider::default();
+
+    let mut group = c.benchmark_group("contexts_1m");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(bars.len() as u64));
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            let _ = black_box(provider.compute_all(black_box(&bars)));
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            let _ = black_box(provider.compute_all_parallel(black_box(&bars)));
+        })
+    });
+    group.finish();
+}
+
 fn bench_scan_at(c: &mut Criterion) {
     let bars = generate_bars(1000);
 
@@ -155,14 +193,45 @@ fn bench_scan_at(c: &mut Criterion) {
     });
 }
 
+fn bench_scan_into(c: &mut Criterion) {
+    let bars = generate_bars(10_000);
+
+    let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+    let mut flat = Vec::new();
+    let mut grouped = Vec::new();
+
+    let mut group = c.benchmark_group("buffer_reuse");
+    group.throughput(Throughput::Elements(bars.len() as u64));
+    group.bench_function("scan", |b| {
+        b.iter(|| black_box(engine.scan(black_box(&bars)).unwrap()))
+    });
+    group.bench_function("scan_into", |b| {
+        b.iter(|| engine.scan_into(black_box(&bars), &mut flat).unwrap())
+    });
+    group.bench_function("scan_grouped", |b| {
+        b.iter(|| black_box(engine.scan_grouped(black_box(&bars)).unwrap()))
+    });
+    group.bench_function("scan_grouped_into", |b| {
+        b.iter(|| {
+            engine
+                .scan_grouped_into(black_box(&bars), &mut grouped)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_single_pattern,
     bench_all_patterns,
     bench_scaling,
+    bench_all_patterns_1m,
     bench_parallel_scan,
     bench_context_computation,
+    bench_context_computation_parallel,
     bench_scan_at,
+    bench_scan_into,
 );
 
 criterion_main!(benches);
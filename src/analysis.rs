@@ -0,0 +1,702 @@
+//! Post-scan analysis utilities
+//!
+//! Helpers that operate on the output of a scan (`PatternMatch` values together
+//! with the bars they were detected on), e.g. filtering match lists or checking
+//! whether a window was later filled.
+//!
+//! # Example
+//!
+//! ```rust
+//! use yacpd::analysis::gap_filled;
+//! use yacpd::prelude::*;
+//!
+//! struct Bar { o: f64, h: f64, l: f64, c: f64 }
+//!
+//! impl OHLCV for Bar {
+//!     fn open(&self) -> f64 { self.o }
+//!     fn high(&self) -> f64 { self.h }
+//!     fn low(&self) -> f64 { self.l }
+//!     fn close(&self) -> f64 { self.c }
+//!     fn volume(&self) -> f64 { 0.0 }
+//! }
+//!
+//! let engine = EngineBuilder::new()
+//!     .add(BuiltinDetector::RisingWindow(RisingWindowDetector))
+//!     .build()
+//!     .unwrap();
+//!
+//! let bars = vec![
+//!     Bar { o: 100.0, h: 101.0, l: 99.0, c: 100.5 },
+//!     Bar { o: 102.5, h: 104.0, l: 102.0, c: 103.5 },
+//!     Bar { o: 103.0, h: 103.5, l: 100.5, c: 101.0 },
+//! ];
+//!
+//! for m in engine.scan(&bars).unwrap() {
+//!     assert_eq!(gap_filled(&m, &bars, 1), Some(true));
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{Direction, PatternId, PatternMatch, OHLCV};
+
+// ============================================================
+// MATCH FILTERING
+// ============================================================
+
+/// Filtering helpers for match lists.
+///
+/// Each helper returns a new `Vec`, which derefs back to a slice, so calls
+/// chain: `matches.bullish().above_strength(0.7)`.
+pub trait MatchSliceExt {
+    /// Matches with `Direction::Bullish`
+    fn bullish(&self) -> Vec<PatternMatch>;
+
+    /// Matches with `Direction::Bearish`
+    fn bearish(&self) -> Vec<PatternMatch>;
+
+    /// Matches of a single pattern
+    fn with_id(&self, id: PatternId) -> Vec<PatternMatch>;
+
+    /// Matches with `strength >= min`
+    fn above_strength(&self, min: f64) -> Vec<PatternMatch>;
+
+    /// Matches ending inside `range` (by `end_index`)
+    fn in_range(&self, range: std::ops::Range<usize>) -> Vec<PatternMatch>;
+}
+
+impl MatchSliceExt for [PatternMatch] {
+    fn bullish(&self) -> Vec<PatternMatch> {
+        self.iter()
+            .filter(|m| m.direction.is_bullish())
+            .copied()
+            .collect()
+    }
+
+    fn bearish(&self) -> Vec<PatternMatch> {
+        self.iter()
+            .filter(|m| m.direction.is_bearish())
+            .copied()
+            .collect()
+    }
+
+    fn with_id(&self, id: PatternId) -> Vec<PatternMatch> {
+        self.iter()
+            .filter(|m| m.pattern_id == id)
+            .copied()
+            .collect()
+    }
+
+    fn above_strength(&self, min: f64) -> Vec<PatternMatch> {
+        self.iter().filter(|m| m.strength >= min).copied().collect()
+    }
+
+    fn in_range(&self, range: std::ops::Range<usize>) -> Vec<PatternMatch> {
+        self.iter()
+            .filter(|m| range.contains(&m.end_index))
+            .copied()
+            .collect()
+    }
+}
+
+// ============================================================
+// GAP FILL
+// ============================================================
+
+/// Gap between two bars as `(lower, upper, is_up)`
+fn gap_bounds<T: OHLCV>(m: &PatternMatch, bars: &[T]) -> Option<(f64, f64, bool)> {
+    let first = bars.get(m.start_index)?;
+    let second = bars.get(m.start_index + 1)?;
+
+    match m.pattern_id.0 {
+        "RISING_WINDOW" | "UPSIDE_TASUKI_GAP" => Some((first.high(), second.low(), true)),
+        "FALLING_WINDOW" | "DOWNSIDE_TASUKI_GAP" => Some((second.high(), first.low(), false)),
+        // TA-Lib tasuki gap is a real-body gap, direction from the match
+        "CDL_TASUKIGAP" => {
+            let first_hi = first.open().max(first.close());
+            let first_lo = first.open().min(first.close());
+            let second_hi = second.open().max(second.close());
+            let second_lo = second.open().min(second.close());
+            match m.direction {
+                Direction::Bullish => Some((first_hi, second_lo, true)),
+                Direction::Bearish => Some((second_hi, first_lo, false)),
+                Direction::Neutral => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Check whether the gap of a window-type pattern was filled afterwards.
+///
+/// Looks at up to `within` bars after `m.end_index` and returns `Some(true)` if
+/// any of them traded back through the whole gap (low at or below the gap
+/// bottom for an upward gap, high at or above the gap top for a downward one).
+///
+/// Returns `None` for patterns that are not gap-based (only windows and tasuki
+/// gaps are supported) or if the pattern bars are not in `bars`.
+pub fn gap_filled<T: OHLCV>(m: &PatternMatch, bars: &[T], within: usize) -> Option<bool> {
+    let (lower, upper, is_up) = gap_bounds(m, bars)?;
+
+    let start = m.end_index + 1;
+    let end = start.saturating_add(within).min(bars.len());
+    if start >= end {
+        return Some(false);
+    }
+
+    let filled = bars[start..end].iter().any(|b| {
+        if is_up {
+            b.low() <= lower
+        } else {
+            b.high() >= upper
+        }
+    });
+    Some(filled)
+}
+
+// ============================================================
+// CONFIRMATION
+// ============================================================
+
+/// Keep only matches confirmed by the bar after the pattern.
+///
+/// A bullish match is kept if the bar after `end_index` closes above the close
+/// of the last pattern bar, a bearish one if it closes below. Neutral matches
+/// have nothing to confirm and are dropped, as are matches ending on the last
+/// bar of the series.
+pub fn confirm<T: OHLCV>(matches: &[PatternMatch], bars: &[T]) -> Vec<PatternMatch> {
+    matches
+        .iter()
+        .filter(|m| {
+            let (Some(last), Some(next)) = (bars.get(m.end_index), bars.get(m.end_index + 1))
+            else {
+                return false;
+            };
+            match m.direction {
+                Direction::Bullish => next.close() > last.close(),
+                Direction::Bearish => next.close() < last.close(),
+                Direction::Neutral => false,
+            }
+        })
+        .copied()
+        .collect()
+}
+
+// ============================================================
+// OVERLAP RESOLUTION
+// ============================================================
+
+/// Collapse overlapping matches of the same pattern into the strongest one.
+///
+/// Per `PatternId`, matches are taken greedily by descending strength (ties go
+/// to the earlier match) and kept only if their `start_index..=end_index` does
+/// not overlap a match already kept. Different patterns never suppress each
+/// other. The result preserves the input order.
+pub fn select_non_overlapping(matches: &[PatternMatch]) -> Vec<PatternMatch> {
+    let mut order: Vec<usize> = (0..matches.len()).collect();
+    order.sort_by(|&a, &b| matches[b].strength.total_cmp(&matches[a].strength));
+
+    let mut kept = vec![false; matches.len()];
+    let mut taken: HashMap<PatternId, Vec<(usize, usize)>> = HashMap::new();
+    for i in order {
+        let m = &matches[i];
+        let spans = taken.entry(m.pattern_id).or_default();
+        if spans
+            .iter()
+            .all(|&(start, end)| m.end_index < start || m.start_index > end)
+        {
+            spans.push((m.start_index, m.end_index));
+            kept[i] = true;
+        }
+    }
+
+    matches
+        .iter()
+        .zip(kept)
+        .filter(|(_, k)| *k)
+        .map(|(m, _)| *m)
+        .collect()
+}
+
+// ============================================================
+// CONTINUATION MERGING
+// ============================================================
+
+/// Merge runs of the same pattern on adjacent bars into one match.
+///
+/// Continuation patterns (rising/falling windows, three methods, tasuki
+/// gaps) firing on consecutive bars describe a single trend leg. A match is
+/// folded into the previous match of the same `PatternId` and direction when
+/// their spans share a bar, as multi-bar patterns ending on consecutive bars
+/// do; the merged match covers the union of the spans with the maximum
+/// strength. Expects matches in scan order (ascending `end_index`); the
+/// result keeps the order of each run's first match.
+pub fn coalesce_continuations(matches: &[PatternMatch]) -> Vec<PatternMatch> {
+    let mut merged: Vec<PatternMatch> = Vec::with_capacity(matches.len());
+    let mut last: HashMap<PatternId, usize> = HashMap::new();
+    for m in matches {
+        if let Some(&k) = last.get(&m.pattern_id) {
+            let run = &mut merged[k];
+            if run.direction == m.direction && m.start_index <= run.end_index {
+                run.start_index = run.start_index.min(m.start_index);
+                run.end_index = run.end_index.max(m.end_index);
+                run.strength = run.strength.max(m.strength);
+                continue;
+            }
+        }
+        last.insert(m.pattern_id, merged.len());
+        merged.push(*m);
+    }
+    merged
+}
+
+// ============================================================
+// TIMESTAMPS
+// ============================================================
+
+/// Pair each match with the timestamp of its last bar.
+///
+/// Yields `None` for bars without a timestamp or matches outside `bars`.
+pub fn with_timestamps<T: OHLCV>(
+    matches: &[PatternMatch],
+    bars: &[T],
+) -> Vec<(PatternMatch, Option<i64>)> {
+    matches
+        .iter()
+        .map(|m| (*m, bars.get(m.end_index).and_then(|b| b.timestamp())))
+        .collect()
+}
+
+// ============================================================
+// DENSITY
+// ============================================================
+
+/// Number of matches ending within a trailing window, per bar.
+///
+/// Element `i` counts the matches whose `end_index` lies in
+/// `i + 1 - window..=i`, so peaks mark clusters of signals. The result has
+/// `total_bars` elements; matches ending at or after `total_bars` are
+/// ignored and a `window` of 0 yields all zeros.
+pub fn match_density(matches: &[PatternMatch], window: usize, total_bars: usize) -> Vec<usize> {
+    let mut per_bar = vec![0usize; total_bars];
+    for m in matches {
+        if let Some(count) = per_bar.get_mut(m.end_index) {
+            *count += 1;
+        }
+    }
+
+    let mut density = Vec::with_capacity(total_bars);
+    let mut running = 0;
+    for i in 0..total_bars {
+        running += per_bar[i];
+        if i >= window {
+            running -= per_bar[i - window];
+        }
+        density.push(running);
+    }
+    density
+}
+
+// ============================================================
+// SENTIMENT
+// ============================================================
+
+/// Recency-weighted sentiment at `current_index`.
+///
+/// Sums `direction.sign() * strength` over the matches, each weighted by
+/// `0.5^((current_index - end_index) / half_life)`, so a match `half_life`
+/// bars old counts half as much as one ending on the current bar. Matches
+/// ending after `current_index` are ignored; positive values lean bullish.
+///
+/// Returns `0.0` when `half_life` is zero, negative or NaN.
+pub fn decayed_sentiment(matches: &[PatternMatch], current_index: usize, half_life: f64) -> f64 {
+    if half_life.is_nan() || half_life <= 0.0 {
+        return 0.0;
+    }
+    matches
+        .iter()
+        .filter(|m| m.end_index <= current_index)
+        .map(|m| {
+            let age = (current_index - m.end_index) as f64;
+            0.5f64.powf(age / half_life) * m.direction.sign() * m.strength
+        })
+        .sum()
+}
+
+// ============================================================
+// CONFLICTS
+// ============================================================
+
+/// Bar indices whose matches include both a bullish and a bearish signal.
+///
+/// `grouped` is per-bar output as from `PatternEngine::scan_grouped`; the
+/// returned indices are positions in it. Neutral matches never conflict.
+pub fn conflicts(grouped: &[Vec<PatternMatch>]) -> Vec<usize> {
+    grouped
+        .iter()
+        .enumerate()
+        .filter(|(_, matches)| {
+            matches.iter().any(|m| m.direction == Direction::Bullish)
+                && matches.iter().any(|m| m.direction == Direction::Bearish)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// ============================================================
+// STOP LEVELS
+// ============================================================
+
+/// Natural invalidation level of a match, for risk sizing.
+///
+/// The lowest low of the pattern span for bullish matches and the highest
+/// high for bearish ones. Returns `None` for neutral matches or if the span
+/// is not in `bars`.
+pub fn suggested_stop<T: OHLCV>(m: &PatternMatch, bars: &[T]) -> Option<f64> {
+    let span = bars.get(m.start_index..=m.end_index)?;
+    match m.direction {
+        Direction::Bullish => span.iter().map(|b| b.low()).reduce(f64::min),
+        Direction::Bearish => span.iter().map(|b| b.high()).reduce(f64::max),
+        Direction::Neutral => None,
+    }
+}
+
+// ============================================================
+// TESTS
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resample::OwnedBar;
+    use crate::{BuiltinDetector, EngineBuilder, HammerDetector, RisingWindowDetector};
+
+    struct Bar {
+        o: f64,
+        h: f64,
+        l: f64,
+        c: f64,
+    }
+
+    impl Bar {
+        fn new(o: f64, h: f64, l: f64, c: f64) -> Self {
+            Self { o, h, l, c }
+        }
+    }
+
+    impl OHLCV for Bar {
+        fn open(&self) -> f64 {
+            self.o
+        }
+
+        fn high(&self) -> f64 {
+            self.h
+        }
+
+        fn low(&self) -> f64 {
+            self.l
+        }
+
+        fn close(&self) -> f64 {
+            self.c
+        }
+
+        fn volume(&self) -> f64 {
+            1000.0
+        }
+    }
+
+    fn sample_matches() -> Vec<PatternMatch> {
+        let m = |id, direction, strength, index| PatternMatch {
+            pattern_id: PatternId(id),
+            direction,
+            strength,
+            start_index: index,
+            end_index: index,
+            metric: None,
+        };
+        vec![
+            m("CDL_HAMMER", Direction::Bullish, 0.8, 2),
+            m("CDL_DOJI", Direction::Neutral, 0.9, 3),
+            m("CDL_SHOOTINGSTAR", Direction::Bearish, 0.6, 5),
+            m("CDL_HAMMER", Direction::Bullish, 0.5, 8),
+        ]
+    }
+
+    fn ends(matches: &[PatternMatch]) -> Vec<usize> {
+        matches.iter().map(|m| m.end_index).collect()
+    }
+
+    #[test]
+    fn test_match_slice_direction_filters() {
+        let matches = sample_matches();
+        assert_eq!(ends(&matches.bullish()), vec![2, 8]);
+        assert_eq!(ends(&matches.bearish()), vec![5]);
+    }
+
+    #[test]
+    fn test_match_slice_with_id() {
+        let matches = sample_matches();
+        assert_eq!(ends(&matches.with_id(PatternId("CDL_HAMMER"))), vec![2, 8]);
+        assert!(matches.with_id(PatternId("CDL_ENGULFING")).is_empty());
+    }
+
+    #[test]
+    fn test_match_slice_above_strength() {
+        let matches = sample_matches();
+        assert_eq!(ends(&matches.above_strength(0.8)), vec![2, 3]);
+        assert_eq!(ends(&matches.bullish().above_strength(0.7)), vec![2]);
+    }
+
+    #[test]
+    fn test_match_slice_in_range() {
+        let matches = sample_matches();
+        assert_eq!(ends(&matches.in_range(3..8)), vec![3, 5]);
+        assert!(matches.in_range(10..20).is_empty());
+    }
+
+    #[test]
+    fn test_rising_window_filled_later() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::RisingWindow(RisingWindowDetector))
+            .build()
+            .unwrap();
+
+        let bars = vec![
+            Bar::new(100.0, 101.0, 99.0, 100.5),
+            Bar::new(102.5, 104.0, 102.0, 103.5), // gap up over 101..102
+            Bar::new(103.5, 105.0, 102.5, 104.5), // stays above the gap
+            Bar::new(104.0, 104.5, 100.5, 101.0), // trades back through it
+        ];
+
+        let patterns = engine.scan(&bars).unwrap();
+        assert_eq!(patterns.len(), 1);
+        let m = &patterns[0];
+        assert_eq!(m.pattern_id, PatternId("RISING_WINDOW"));
+
+        assert_eq!(gap_filled(m, &bars, 1), Some(false));
+        assert_eq!(gap_filled(m, &bars, 2), Some(true));
+        assert_eq!(gap_filled(m, &bars, 10), Some(true));
+    }
+
+    #[test]
+    fn test_confirm_next_bar_close() {
+        let bars: Vec<Bar> = [100.0, 99.0, 98.0, 99.5, 97.0, 96.0, 95.0, 98.0, 97.5]
+            .iter()
+            .map(|&c| Bar::new(c, c + 1.0, c - 1.0, c))
+            .collect();
+
+        // Hammer at 2 is followed by a higher close, hammer at 8 ends the series
+        let mut matches = sample_matches();
+        matches[0].end_index = 2;
+        let confirmed = confirm(&matches, &bars);
+        assert_eq!(ends(&confirmed), vec![2, 5]);
+
+        // Hammer at 3 is followed by a lower close and is dropped
+        matches[0].end_index = 3;
+        matches[0].start_index = 3;
+        assert_eq!(ends(&confirm(&matches, &bars)), vec![5]);
+    }
+
+    #[test]
+    fn test_select_non_overlapping_keeps_stronger() {
+        let m = |id, strength, start, end| PatternMatch {
+            pattern_id: PatternId(id),
+            direction: Direction::Bullish,
+            strength,
+            start_index: start,
+            end_index: end,
+            metric: None,
+        };
+        let matches = vec![
+            m("CDL_HAMMER", 0.6, 3, 4),
+            m("CDL_HAMMER", 0.9, 4, 5),
+            m("CDL_ENGULFING", 0.7, 4, 5),
+            m("CDL_HAMMER", 0.5, 7, 7),
+        ];
+
+        let selected = select_non_overlapping(&matches);
+        let summary: Vec<(&str, usize)> = selected
+            .iter()
+            .map(|m| (m.pattern_id.0, m.end_index))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("CDL_HAMMER", 5), ("CDL_ENGULFING", 5), ("CDL_HAMMER", 7)]
+        );
+        assert_eq!(selected[0].strength, 0.9);
+    }
+
+    #[test]
+    fn test_coalesce_adjacent_rising_windows() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::RisingWindow(RisingWindowDetector))
+            .build()
+            .unwrap();
+
+        let bars = vec![
+            Bar::new(100.0, 101.0, 99.0, 100.5),
+            Bar::new(102.5, 104.0, 102.0, 103.5), // gap up over 101..102
+            Bar::new(105.5, 107.0, 105.0, 106.5), // gap up over 104..105
+            Bar::new(106.0, 106.5, 104.5, 105.0),
+            Bar::new(107.5, 108.0, 107.0, 107.8), // gap up over 106.5..107
+        ];
+
+        let matches = engine.scan(&bars).unwrap();
+        assert_eq!(ends(&matches), vec![1, 2, 4]);
+
+        let coalesced = coalesce_continuations(&matches);
+        let spans: Vec<(usize, usize)> = coalesced
+            .iter()
+            .map(|m| (m.start_index, m.end_index))
+            .collect();
+        assert_eq!(spans, vec![(0, 2), (3, 4)]);
+        assert_eq!(
+            coalesced[0].strength,
+            matches[0].strength.max(matches[1].strength)
+        );
+
+        // Different patterns and directions are never merged
+        let mut mixed = matches.clone();
+        mixed[1].direction = Direction::Bearish;
+        assert_eq!(coalesce_continuations(&mixed).len(), 3);
+    }
+
+    #[test]
+    fn test_with_timestamps() {
+        let bars: Vec<OwnedBar> = (0..6)
+            .map(|i| {
+                let bar = OwnedBar::new(100.0, 101.0, 99.0, 100.5, 10.0);
+                if i == 3 {
+                    bar
+                } else {
+                    bar.with_timestamp(1_700_000_000 + i * 60)
+                }
+            })
+            .collect();
+
+        let stamped = with_timestamps(&sample_matches(), &bars);
+        let stamps: Vec<Option<i64>> = stamped.iter().map(|(_, ts)| *ts).collect();
+        assert_eq!(
+            stamps,
+            vec![Some(1_700_000_120), None, Some(1_700_000_300), None]
+        );
+        assert_eq!(stamped[0].0.end_index, 2);
+    }
+
+    #[test]
+    fn test_match_density_peaks_at_cluster() {
+        let mut matches = sample_matches();
+        matches.push(matches[1]);
+        // Ends at 2, 3, 3, 5, 8
+        let density = match_density(&matches, 3, 10);
+        assert_eq!(density, vec![0, 0, 1, 3, 3, 3, 1, 1, 1, 1]);
+        assert_eq!(density.iter().max(), Some(&3));
+
+        assert_eq!(match_density(&matches, 1, 4), vec![0, 0, 1, 2]);
+        assert_eq!(match_density(&matches, 0, 4), vec![0; 4]);
+    }
+
+    #[test]
+    fn test_decayed_sentiment_favors_recent_match() {
+        let m = |direction, end| PatternMatch {
+            pattern_id: PatternId("CDL_ENGULFING"),
+            direction,
+            strength: 0.8,
+            start_index: end - 1,
+            end_index: end,
+            metric: None,
+        };
+        // Old bullish engulfing vs. a recent bearish one of equal strength
+        let matches = vec![m(Direction::Bullish, 2), m(Direction::Bearish, 9)];
+
+        let sentiment = decayed_sentiment(&matches, 10, 2.0);
+        assert!(sentiment < 0.0);
+        let expected = 0.8 * 0.5f64.powi(4) - 0.8 * 0.5f64.powf(0.5);
+        assert!((sentiment - expected).abs() < 1e-12);
+
+        // One half-life back counts half; future matches are ignored
+        assert!((decayed_sentiment(&matches[..1], 4, 2.0) - 0.4).abs() < 1e-12);
+        assert_eq!(decayed_sentiment(&matches, 5, 2.0), 0.8 * 0.5f64.powf(1.5));
+        assert_eq!(decayed_sentiment(&matches, 10, 0.0), 0.0);
+        assert_eq!(decayed_sentiment(&matches, 10, -1.0), 0.0);
+        assert_eq!(decayed_sentiment(&matches, 10, f64::NAN), 0.0);
+    }
+
+    #[test]
+    fn test_conflicts_flags_opposing_bar() {
+        let m = |id, direction, index| PatternMatch {
+            pattern_id: PatternId(id),
+            direction,
+            strength: 0.7,
+            start_index: index,
+            end_index: index,
+            metric: None,
+        };
+        let grouped = vec![
+            vec![m("CDL_HAMMER", Direction::Bullish, 0)],
+            vec![
+                m("WHITE_CANDLE", Direction::Bullish, 1),
+                m("CDL_DOJI", Direction::Neutral, 1),
+                m("CDL_SHOOTINGSTAR", Direction::Bearish, 1),
+            ],
+            vec![],
+            vec![
+                m("CDL_DOJI", Direction::Neutral, 3),
+                m("BLACK_CANDLE", Direction::Bearish, 3),
+            ],
+        ];
+        assert_eq!(conflicts(&grouped), vec![1]);
+        assert!(conflicts(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_suggested_stop_hammer_low() {
+        let mut bars: Vec<Bar> = (0..20)
+            .map(|i| {
+                let base = 100.0 - i as f64 * 2.0;
+                Bar::new(base, base + 1.0, base - 1.0, base - 0.5)
+            })
+            .collect();
+        bars.push(Bar::new(60.0, 60.1, 55.0, 60.05));
+
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Hammer(HammerDetector::with_defaults()))
+            .build()
+            .unwrap();
+        let hammer = engine
+            .scan(&bars)
+            .unwrap()
+            .into_iter()
+            .find(|m| m.end_index == 20)
+            .unwrap();
+        assert_eq!(hammer.direction, Direction::Bullish);
+        let lowest = bars[hammer.start_index..=hammer.end_index]
+            .iter()
+            .map(|b| b.l)
+            .fold(f64::INFINITY, f64::min);
+        assert_eq!(suggested_stop(&hammer, &bars), Some(lowest));
+        assert_eq!(lowest, 55.0);
+
+        let doji = PatternMatch {
+            pattern_id: PatternId("CDL_DOJI"),
+            direction: Direction::Neutral,
+            ..hammer
+        };
+        assert_eq!(suggested_stop(&doji, &bars), None);
+    }
+
+    #[test]
+    fn test_gap_filled_non_gap_pattern() {
+        let bars = vec![Bar::new(100.0, 110.0, 90.0, 100.5)];
+        let m = PatternMatch {
+            pattern_id: PatternId("CDL_DOJI"),
+            direction: Direction::Neutral,
+            strength: 0.8,
+            start_index: 0,
+            end_index: 0,
+            metric: None,
+        };
+        assert_eq!(gap_filled(&m, &bars, 5), None);
+    }
+}
@@ -1,7 +1,9 @@
 //! Extended candlestick pattern detectors
 //!
 //! Additional patterns beyond TA-Lib: Price Lines, Windows, Meeting Lines,
-//! Northern/Southern Doji, Opening Marubozu variants, Basic candle types, etc.
+//! Northern/Southern Doji, Opening Marubozu variants, Basic candle types,
+//! Tower Top/Bottom and Fry Pan Bottom reversals,
+//! trend-independent price action (Kicker, New High/Low breakouts), etc.
 
 #![allow(
     clippy::collapsible_if,
@@ -11,10 +13,15 @@
 
 use std::collections::HashMap;
 
+use super::helpers;
+use super::multi_bar::HikkakeDetector;
+use super::two_bar::KickingDetector;
 use crate::{
-    params::{get_period, get_ratio, ParamMeta, ParamType, ParameterizedDetector},
-    Direction, MarketContext, OHLCVExt, PatternDetector, PatternId, PatternMatch, Period, Ratio,
-    Result, OHLCV,
+    params::{
+        get_flag, get_period, get_positive, get_ratio, ParamMeta, ParamType, ParameterizedDetector,
+    },
+    ColorConvention, Direction, MarketContext, OHLCVExt, PatternDetector, PatternError, PatternId,
+    PatternMatch, Period, Ratio, Result, OHLCV,
 };
 
 impl_with_defaults!(
@@ -23,6 +30,8 @@ impl_with_defaults!(
     RisingWindowDetector,
     GappingDownDojiDetector,
     GappingUpDojiDetector,
+    ConfirmedWindowDetector,
+    ThreeGapsDetector,
     AboveTheStomachDetector,
     BelowTheStomachDetector,
     CollapsingDojiStarDetector,
@@ -32,6 +41,7 @@ impl_with_defaults!(
     TwoBlackGappingDetector,
     MeetingLinesBearishDetector,
     MeetingLinesBullishDetector,
+    HikkakeConfirmedDetector,
     NorthernDojiDetector,
     SouthernDojiDetector,
     BlackMarubozuDetector,
@@ -51,6 +61,13 @@ impl_with_defaults!(
     UpsideGapThreeMethodsDetector,
     DownsideTasukiGapDetector,
     UpsideTasukiGapDetector,
+    TowerTopDetector,
+    TowerBottomDetector,
+    FryPanBottomDetector,
+    ConsolidationDetector,
+    KickerDetector,
+    NewHighDetector,
+    NewLowDetector,
 );
 
 // ============================================================
@@ -59,43 +76,85 @@ impl_with_defaults!(
 
 /// Price Lines - N consecutive candles of the same direction
 /// Signals overbought/oversold conditions and potential reversal
-#[derive(Debug, Clone)]
+///
+/// Fires for any run of `min_count..=max_count` same-color candles ending at
+/// the current bar. The run length (capped at `max_count`) is reported as the
+/// match `metric`, and the span (`start_index..=end_index`) covers the run;
+/// strength grows from 0.5 at `min_count` to 1.0 at `max_count`.
+/// Fixed-length detectors (`min_count == max_count`) keep the original
+/// strength of `0.5 + (count - 8) * 0.05`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PriceLinesDetector {
-    /// Number of consecutive candles required
-    pub count: usize,
+    /// Minimum number of consecutive candles required
+    pub min_count: usize,
+    /// Longest run measured; longer runs are reported with this length
+    pub max_count: usize,
 }
 
 impl Default for PriceLinesDetector {
     fn default() -> Self {
-        Self { count: 8 }
+        Self {
+            min_count: 8,
+            max_count: 13,
+        }
     }
 }
 
 impl PriceLinesDetector {
+    /// Fire on runs of at least `count` consecutive candles (at least 3); the
+    /// reported span is the last `count` of them
     pub fn with_count(count: usize) -> Self {
+        let count = count.max(3);
+        Self {
+            min_count: count,
+            max_count: count,
+        }
+    }
+
+    /// Fire for runs of `min_count..=max_count` candles (`min_count` at least 3)
+    pub fn with_range(min_count: usize, max_count: usize) -> Self {
+        let min_count = min_count.max(3);
         Self {
-            count: count.max(3),
+            min_count,
+            max_count: max_count.max(min_count),
         }
     }
 
+    /// Minimum run length, the sole count before `min_count`/`max_count`
+    #[deprecated(note = "use the `min_count` field")]
+    pub fn count(&self) -> usize {
+        self.min_count
+    }
+
     /// Create detector for 8 price lines
     pub fn eight() -> Self {
-        Self { count: 8 }
+        Self::with_count(8)
     }
 
     /// Create detector for 10 price lines
     pub fn ten() -> Self {
-        Self { count: 10 }
+        Self::with_count(10)
     }
 
     /// Create detector for 12 price lines
     pub fn twelve() -> Self {
-        Self { count: 12 }
+        Self::with_count(12)
     }
 
     /// Create detector for 13 price lines
     pub fn thirteen() -> Self {
-        Self { count: 13 }
+        Self::with_count(13)
+    }
+
+    /// Length of the same-color run ending at `index`, at most `max_count`
+    fn run_length<T: OHLCV>(&self, bars: &[T], index: usize, color: ColorConvention) -> usize {
+        let sign = color.sign(&bars[index]);
+        bars[..=index]
+            .iter()
+            .rev()
+            .take(self.max_count)
+            .take_while(|b| color.sign(*b) == sign)
+            .count()
     }
 }
 
@@ -105,52 +164,51 @@ impl PatternDetector for PriceLinesDetector {
     }
 
     fn min_bars(&self) -> usize {
-        self.count
+        self.min_count.max(1)
     }
 
     fn detect<T: OHLCV>(
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
-        if index < self.count - 1 {
+        let min_count = self.min_count.max(1);
+        if index + 1 < min_count || index >= bars.len() {
             return None;
         }
 
-        let start = index + 1 - self.count;
-
-        // Check if all candles are bullish
-        let all_bullish =
-            (start..=index).all(|i| bars.get(i).map(|b| b.is_bullish()).unwrap_or(false));
-
-        if all_bullish {
-            let strength = 0.5 + (self.count as f64 - 8.0) * 0.05;
-            return Some(PatternMatch {
-                pattern_id: PatternDetector::id(self),
-                direction: Direction::Bearish, // Overbought -> potential reversal down
-                strength: strength.clamp(0.5, 1.0),
-                start_index: start,
-                end_index: index,
-            });
+        let bar = &bars[index];
+        let bullish = ctx.color.is_white(bar);
+        if !bullish && !ctx.color.is_black(bar) {
+            return None;
         }
 
-        // Check if all candles are bearish
-        let all_bearish =
-            (start..=index).all(|i| bars.get(i).map(|b| b.is_bearish()).unwrap_or(false));
-
-        if all_bearish {
-            let strength = 0.5 + (self.count as f64 - 8.0) * 0.05;
-            return Some(PatternMatch {
-                pattern_id: PatternDetector::id(self),
-                direction: Direction::Bullish, // Oversold -> potential reversal up
-                strength: strength.clamp(0.5, 1.0),
-                start_index: start,
-                end_index: index,
-            });
+        let run = self.run_length(bars, index, ctx.color);
+        if run < min_count {
+            return None;
         }
 
-        None
+        let spread = self.max_count.saturating_sub(min_count);
+        let strength = if spread == 0 {
+            0.5 + (min_count as f64 - 8.0) * 0.05
+        } else {
+            0.5 + (run - min_count) as f64 / spread as f64 * 0.5
+        };
+
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            // Overbought -> potential reversal down, oversold -> reversal up
+            direction: if bullish {
+                Direction::Bearish
+            } else {
+                Direction::Bullish
+            },
+            strength: strength.clamp(0.5, 1.0),
+            start_index: index + 1 - run,
+            end_index: index,
+            metric: Some(run as f64),
+        })
     }
 }
 
@@ -160,7 +218,7 @@ impl PatternDetector for PriceLinesDetector {
 
 /// Falling Window - Gap down between two candles (bearish continuation)
 /// Current High < Previous Low
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct FallingWindowDetector;
 
 impl PatternDetector for FallingWindowDetector {
@@ -201,6 +259,7 @@ impl PatternDetector for FallingWindowDetector {
                 strength: strength.clamp(0.5, 1.0),
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -210,7 +269,7 @@ impl PatternDetector for FallingWindowDetector {
 
 /// Rising Window - Gap up between two candles (bullish continuation)
 /// Current Low > Previous High
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct RisingWindowDetector;
 
 impl PatternDetector for RisingWindowDetector {
@@ -251,6 +310,7 @@ impl PatternDetector for RisingWindowDetector {
                 strength: strength.clamp(0.5, 1.0),
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -260,7 +320,7 @@ impl PatternDetector for RisingWindowDetector {
 
 /// Gapping Down Doji - Doji that opened with a gap down
 /// Signals indecision after decline
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GappingDownDojiDetector {
     /// Maximum body size as ratio of range
     pub body_pct: Ratio,
@@ -312,9 +372,10 @@ impl PatternDetector for GappingDownDojiDetector {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Neutral, // Indecision
-                strength: 0.6 + (1.0 - body_ratio) * 0.3,
+                strength: helpers::clamp_strength(0.6 + (1.0 - body_ratio) * 0.3),
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -324,7 +385,7 @@ impl PatternDetector for GappingDownDojiDetector {
 
 /// Gapping Up Doji - Doji that opened with a gap up
 /// Signals indecision after advance
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GappingUpDojiDetector {
     /// Maximum body size as ratio of range
     pub body_pct: Ratio,
@@ -376,9 +437,10 @@ impl PatternDetector for GappingUpDojiDetector {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Neutral, // Indecision
-                strength: 0.6 + (1.0 - body_ratio) * 0.3,
+                strength: helpers::clamp_strength(0.6 + (1.0 - body_ratio) * 0.3),
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -386,13 +448,138 @@ impl PatternDetector for GappingUpDojiDetector {
     }
 }
 
+/// Confirmed Window - a rising or falling window followed by a bar closing in the gap direction
+///
+/// Fires on the confirming bar, one bar after `RISING_WINDOW`/`FALLING_WINDOW`,
+/// when it closes beyond the window bar's close. The span covers the bar
+/// before the gap, the window bar and the confirmation.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub struct ConfirmedWindowDetector;
+
+impl PatternDetector for ConfirmedWindowDetector {
+    fn id(&self) -> PatternId {
+        PatternId("CONFIRMED_WINDOW")
+    }
+
+    fn min_bars(&self) -> usize {
+        3
+    }
+
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        if index < 2 {
+            return None;
+        }
+
+        let window = bars.get(index - 1)?;
+        let confirm = bars.get(index)?;
+
+        let gap = PatternDetector::detect(&RisingWindowDetector, bars, index - 1, ctx)
+            .or_else(|| PatternDetector::detect(&FallingWindowDetector, bars, index - 1, ctx))?;
+
+        let confirmed = match gap.direction {
+            Direction::Bullish => confirm.close() > window.close(),
+            _ => confirm.close() < window.close(),
+        };
+        if !confirmed {
+            return None;
+        }
+
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction: gap.direction,
+            strength: helpers::clamp_strength(gap.strength + 0.1),
+            start_index: index - 2,
+            end_index: index,
+            metric: None,
+        })
+    }
+}
+
+/// Three Gaps - three consecutive windows in the same direction (exhaustion)
+///
+/// Fires on the third of three back-to-back rising or falling windows. A move
+/// that keeps gapping is read as running out of buyers (or sellers), so the
+/// direction is opposite the gaps: bearish after three rising windows,
+/// bullish after three falling ones. Each gap must be at least `min_gap` of
+/// the average range of the two bars around it. Strength is the mean of the
+/// three window strengths.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ThreeGapsDetector {
+    /// Minimum gap as ratio of the average range of the bars around it
+    pub min_gap: Ratio,
+}
+
+impl Default for ThreeGapsDetector {
+    fn default() -> Self {
+        Self {
+            min_gap: Ratio::new_const(0.0),
+        }
+    }
+}
+
+impl PatternDetector for ThreeGapsDetector {
+    fn id(&self) -> PatternId {
+        PatternId("THREE_GAPS")
+    }
+
+    fn min_bars(&self) -> usize {
+        4
+    }
+
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        if index < 3 {
+            return None;
+        }
+
+        let first = PatternDetector::detect(&RisingWindowDetector, bars, index - 2, ctx)
+            .or_else(|| PatternDetector::detect(&FallingWindowDetector, bars, index - 2, ctx))?;
+        let mut strength = 0.0;
+        for i in index - 2..=index {
+            let gap = if first.direction == Direction::Bullish {
+                PatternDetector::detect(&RisingWindowDetector, bars, i, ctx)?
+            } else {
+                PatternDetector::detect(&FallingWindowDetector, bars, i, ctx)?
+            };
+            let (prev, curr) = (&bars[i - 1], &bars[i]);
+            let size = (curr.low() - prev.high()).max(prev.low() - curr.high());
+            if size < self.min_gap.get() * (prev.range() + curr.range()) / 2.0 {
+                return None;
+            }
+            strength += gap.strength / 3.0;
+        }
+
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction: if first.direction == Direction::Bullish {
+                Direction::Bearish
+            } else {
+                Direction::Bullish
+            },
+            strength: helpers::clamp_strength(strength),
+            start_index: index - 3,
+            end_index: index,
+            metric: None,
+        })
+    }
+}
+
 // ============================================================
 // REVERSAL PATTERNS
 // ============================================================
 
 /// Above the Stomach - Bullish reversal
 /// White candle opens above midpoint of previous black candle's body
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AboveTheStomachDetector {
     /// Minimum penetration level (how far above midpoint)
     pub penetration: Ratio,
@@ -429,7 +616,7 @@ impl PatternDetector for AboveTheStomachDetector {
         let curr = bars.get(index)?;
 
         // Previous must be bearish, current must be bullish
-        if !prev.is_bearish() || !curr.is_bullish() {
+        if !ctx.color.is_black(prev) || !ctx.color.is_white(curr) {
             return None;
         }
 
@@ -452,6 +639,7 @@ impl PatternDetector for AboveTheStomachDetector {
                 strength: strength.clamp(0.5, 1.0),
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -461,7 +649,7 @@ impl PatternDetector for AboveTheStomachDetector {
 
 /// Below the Stomach - Bearish reversal
 /// Black candle opens below midpoint of previous white candle's body
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BelowTheStomachDetector {
     /// Minimum penetration level (how far below midpoint)
     pub penetration: Ratio,
@@ -498,7 +686,7 @@ impl PatternDetector for BelowTheStomachDetector {
         let curr = bars.get(index)?;
 
         // Previous must be bullish, current must be bearish
-        if !prev.is_bullish() || !curr.is_bearish() {
+        if !ctx.color.is_white(prev) || !ctx.color.is_black(curr) {
             return None;
         }
 
@@ -521,6 +709,7 @@ impl PatternDetector for BelowTheStomachDetector {
                 strength: strength.clamp(0.5, 1.0),
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -529,7 +718,7 @@ impl PatternDetector for BelowTheStomachDetector {
 }
 
 /// Collapsing Doji Star - Doji star with gap after strong candle, then reversal
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CollapsingDojiStarDetector {
     /// Maximum body size for doji
     pub body_pct: Ratio,
@@ -559,7 +748,7 @@ impl PatternDetector for CollapsingDojiStarDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -586,36 +775,38 @@ impl PatternDetector for CollapsingDojiStarDetector {
         }
 
         // Bullish collapse (after downtrend)
-        if first.is_bearish() {
+        if ctx.color.is_black(first) {
             // Gap down to doji
             let gap = first.close().min(first.open()) - doji.high();
             if gap >= first_body * self.gap_pct.get() {
                 // Third candle is bullish and closes into first candle's body
-                if third.is_bullish() && third.close() > first.close() {
+                if ctx.color.is_white(third) && third.close() > first.close() {
                     return Some(PatternMatch {
                         pattern_id: PatternDetector::id(self),
                         direction: Direction::Bullish,
                         strength: 0.7,
                         start_index: index - 2,
                         end_index: index,
+                        metric: None,
                     });
                 }
             }
         }
 
         // Bearish collapse (after uptrend)
-        if first.is_bullish() {
+        if ctx.color.is_white(first) {
             // Gap up to doji
             let gap = doji.low() - first.close().max(first.open());
             if gap >= first_body * self.gap_pct.get() {
                 // Third candle is bearish and closes into first candle's body
-                if third.is_bearish() && third.close() < first.close() {
+                if ctx.color.is_black(third) && third.close() < first.close() {
                     return Some(PatternMatch {
                         pattern_id: PatternDetector::id(self),
                         direction: Direction::Bearish,
                         strength: 0.7,
                         start_index: index - 2,
                         end_index: index,
+                        metric: None,
                     });
                 }
             }
@@ -626,7 +817,7 @@ impl PatternDetector for CollapsingDojiStarDetector {
 }
 
 /// Deliberation - Three white candles, third with small body (weakening trend)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DeliberationDetector {
     /// Maximum body size for third candle as ratio of first two average
     pub body_pct: Ratio,
@@ -667,7 +858,7 @@ impl PatternDetector for DeliberationDetector {
         let third = bars.get(index)?;
 
         // All three must be bullish
-        if !first.is_bullish() || !second.is_bullish() || !third.is_bullish() {
+        if !ctx.color.is_white(first) || !ctx.color.is_white(second) || !ctx.color.is_white(third) {
             return None;
         }
 
@@ -710,9 +901,10 @@ impl PatternDetector for DeliberationDetector {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bearish, // Weakening bullish momentum
-                strength: 0.6 + (1.0 - third.body() / avg_body) * 0.3,
+                strength: helpers::clamp_strength(0.6 + (1.0 - third.body() / avg_body) * 0.3),
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -721,7 +913,7 @@ impl PatternDetector for DeliberationDetector {
 }
 
 /// Last Engulfing Bottom - Final engulfing at bottom of downtrend
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct LastEngulfingBottomDetector {
     /// Period for trend detection
     pub trend_period: Period,
@@ -763,7 +955,7 @@ impl PatternDetector for LastEngulfingBottomDetector {
         }
 
         // Bullish engulfing pattern
-        if !prev.is_bearish() || !curr.is_bullish() {
+        if !ctx.color.is_black(prev) || !ctx.color.is_white(curr) {
             return None;
         }
 
@@ -775,6 +967,7 @@ impl PatternDetector for LastEngulfingBottomDetector {
                 strength: 0.75,
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -783,7 +976,7 @@ impl PatternDetector for LastEngulfingBottomDetector {
 }
 
 /// Last Engulfing Top - Final engulfing at top of uptrend
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct LastEngulfingTopDetector {
     /// Period for trend detection
     pub trend_period: Period,
@@ -825,7 +1018,7 @@ impl PatternDetector for LastEngulfingTopDetector {
         }
 
         // Bearish engulfing pattern
-        if !prev.is_bullish() || !curr.is_bearish() {
+        if !ctx.color.is_white(prev) || !ctx.color.is_black(curr) {
             return None;
         }
 
@@ -837,6 +1030,7 @@ impl PatternDetector for LastEngulfingTopDetector {
                 strength: 0.75,
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -845,7 +1039,7 @@ impl PatternDetector for LastEngulfingTopDetector {
 }
 
 /// Two Black Gapping - Two black candles with gap down between them
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct TwoBlackGappingDetector;
 
 impl PatternDetector for TwoBlackGappingDetector {
@@ -861,7 +1055,7 @@ impl PatternDetector for TwoBlackGappingDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -872,7 +1066,7 @@ impl PatternDetector for TwoBlackGappingDetector {
         let second = bars.get(index)?;
 
         // Both candles in the pair must be bearish
-        if !first.is_bearish() || !second.is_bearish() {
+        if !ctx.color.is_black(first) || !ctx.color.is_black(second) {
             return None;
         }
 
@@ -900,12 +1094,13 @@ impl PatternDetector for TwoBlackGappingDetector {
             strength: strength.clamp(0.5, 1.0),
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// Meeting Lines Bearish - White candle followed by black with same close
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MeetingLinesBearishDetector {
     /// Tolerance for price equality
     pub tolerance: Ratio,
@@ -950,7 +1145,7 @@ impl PatternDetector for MeetingLinesBearishDetector {
         }
 
         // Previous bullish, current bearish
-        if !prev.is_bullish() || !curr.is_bearish() {
+        if !ctx.color.is_white(prev) || !ctx.color.is_black(curr) {
             return None;
         }
 
@@ -974,9 +1169,12 @@ impl PatternDetector for MeetingLinesBearishDetector {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bearish,
-                strength: 0.7 - (close_diff / tolerance_val.max(f64::EPSILON)) * 0.1,
+                strength: helpers::clamp_strength(
+                    0.7 - (close_diff / tolerance_val.max(f64::EPSILON)) * 0.1,
+                ),
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -985,7 +1183,7 @@ impl PatternDetector for MeetingLinesBearishDetector {
 }
 
 /// Meeting Lines Bullish - Black candle followed by white with same close
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MeetingLinesBullishDetector {
     /// Tolerance for price equality
     pub tolerance: Ratio,
@@ -1030,7 +1228,7 @@ impl PatternDetector for MeetingLinesBullishDetector {
         }
 
         // Previous bearish, current bullish
-        if !prev.is_bearish() || !curr.is_bullish() {
+        if !ctx.color.is_black(prev) || !ctx.color.is_white(curr) {
             return None;
         }
 
@@ -1054,9 +1252,12 @@ impl PatternDetector for MeetingLinesBullishDetector {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bullish,
-                strength: 0.7 - (close_diff / tolerance_val.max(f64::EPSILON)) * 0.1,
+                strength: helpers::clamp_strength(
+                    0.7 - (close_diff / tolerance_val.max(f64::EPSILON)) * 0.1,
+                ),
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1064,12 +1265,70 @@ impl PatternDetector for MeetingLinesBullishDetector {
     }
 }
 
+/// Hikkake Confirmed - a Hikkake setup whose false break resolved
+///
+/// Fires on the bar whose close breaks the inside bar in the setup's
+/// direction (above its high for a bullish setup, below its low for a bearish
+/// one), within `confirm_within` bars of a `CDL_HIKKAKE` setup. With the
+/// default 3 bars it marks the bars of `CDL_HIKKAKE`'s TA-Lib confirmation
+/// stage; a wider window also catches slower follow-through. The span starts
+/// at the setup's reference bar.
+///
+/// Not part of the default detector sets, where it would repeat
+/// `CDL_HIKKAKE`'s confirmation; register it with `EngineBuilder::add`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HikkakeConfirmedDetector {
+    /// Bars after the setup in which the confirming close is accepted
+    /// (at least 1)
+    pub confirm_within: usize,
+}
+
+impl Default for HikkakeConfirmedDetector {
+    fn default() -> Self {
+        Self { confirm_within: 3 }
+    }
+}
+
+impl PatternDetector for HikkakeConfirmedDetector {
+    fn id(&self) -> PatternId {
+        PatternId("HIKKAKE_CONFIRMED")
+    }
+
+    fn min_bars(&self) -> usize {
+        // Reference bar of a setup `confirm_within` bars back
+        self.confirm_within.max(1) + 3
+    }
+
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        _ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        // A new setup replaces any pending one (TA-Lib behavior)
+        if HikkakeDetector::is_setup_at(bars, index).is_some() {
+            return None;
+        }
+        let (j, direction) =
+            HikkakeDetector::confirmation_at(bars, index, self.confirm_within.max(1))?;
+
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction,
+            strength: 0.75,
+            start_index: j - 2,
+            end_index: index,
+            metric: None,
+        })
+    }
+}
+
 // ============================================================
 // DOJI VARIANTS
 // ============================================================
 
 /// Northern Doji - Doji in upper part of trading range (after advance)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct NorthernDojiDetector {
     /// Maximum body size as ratio of range
     pub body_pct: Ratio,
@@ -1122,15 +1381,16 @@ impl PatternDetector for NorthernDojiDetector {
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction: Direction::Bearish, // Potential reversal
-            strength: 0.6 + (1.0 - body_ratio) * 0.3,
+            strength: helpers::clamp_strength(0.6 + (1.0 - body_ratio) * 0.3),
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// Southern Doji - Doji in lower part of trading range (after decline)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SouthernDojiDetector {
     /// Maximum body size as ratio of range
     pub body_pct: Ratio,
@@ -1183,9 +1443,10 @@ impl PatternDetector for SouthernDojiDetector {
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction: Direction::Bullish, // Potential reversal
-            strength: 0.6 + (1.0 - body_ratio) * 0.3,
+            strength: helpers::clamp_strength(0.6 + (1.0 - body_ratio) * 0.3),
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1194,17 +1455,61 @@ impl PatternDetector for SouthernDojiDetector {
 // MARUBOZU VARIANTS
 // ============================================================
 
+/// How marubozu-family detectors decide that a shadow is short enough.
+///
+/// The extended marubozu variants historically compare shadows against the
+/// bar's own range, while the TA-Lib detectors (`CDL_MARUBOZU`,
+/// `CDL_CLOSINGMARUBOZU`) use ShadowVeryShort, i.e. the trailing average range.
+/// Switching to [`ShadowMode::TrailingAvg`] makes the extended variants agree
+/// with TA-Lib on the same bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ShadowMode {
+    /// Shadow <= range * `shadow_tolerance`
+    #[default]
+    Range,
+    /// TA-Lib ShadowVeryShort: shadow < avg_range * SHADOW_VERYSHORT_FACTOR.
+    /// Falls back to [`ShadowMode::Range`] when no trailing average is available.
+    TrailingAvg,
+}
+
+impl ShadowMode {
+    /// Mode selected by the `trailing_avg_shadows` switch of `with_params`
+    fn from_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(if get_flag(params, "trailing_avg_shadows", false)? {
+            ShadowMode::TrailingAvg
+        } else {
+            ShadowMode::Range
+        })
+    }
+
+    /// Returns true if `shadow` counts as short under this mode
+    #[inline]
+    pub fn is_short(self, shadow: f64, range: f64, tolerance: f64, ctx: &MarketContext) -> bool {
+        match self {
+            // A NaN average (`WarmupPolicy::Nan`) fails the comparison
+            ShadowMode::TrailingAvg if helpers::uses_average(ctx.avg_range) => {
+                shadow < ctx.avg_range * helpers::SHADOW_VERYSHORT_FACTOR
+            }
+            _ => shadow <= range * tolerance,
+        }
+    }
+}
+
 /// Black Marubozu - Black candle with no shadows (Open=High, Close=Low)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BlackMarubozuDetector {
     /// Maximum shadow tolerance as ratio of range
     pub shadow_tolerance: Ratio,
+    /// How shadow length is judged (range-based by default)
+    #[serde(default)]
+    pub shadow_mode: ShadowMode,
 }
 
 impl Default for BlackMarubozuDetector {
     fn default() -> Self {
         Self {
             shadow_tolerance: Ratio::new_const(0.01),
+            shadow_mode: ShadowMode::Range,
         }
     }
 }
@@ -1222,12 +1527,12 @@ impl PatternDetector for BlackMarubozuDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
         // Must be bearish
-        if !bar.is_bearish() {
+        if !ctx.color.is_black(bar) {
             return None;
         }
 
@@ -1236,20 +1541,29 @@ impl PatternDetector for BlackMarubozuDetector {
             return None;
         }
 
-        let tolerance = range * self.shadow_tolerance.get();
+        let tolerance = self.shadow_tolerance.get();
 
         // Open = High (no upper shadow)
         let upper_shadow = bar.high() - bar.open();
         // Close = Low (no lower shadow)
         let lower_shadow = bar.close() - bar.low();
 
-        if upper_shadow <= tolerance && lower_shadow <= tolerance {
+        if self
+            .shadow_mode
+            .is_short(upper_shadow, range, tolerance, ctx)
+            && self
+                .shadow_mode
+                .is_short(lower_shadow, range, tolerance, ctx)
+        {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bearish,
-                strength: 0.8 + (1.0 - (upper_shadow + lower_shadow) / range) * 0.2,
+                strength: helpers::clamp_strength(
+                    0.8 + (1.0 - (upper_shadow + lower_shadow) / range) * 0.2,
+                ),
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1258,16 +1572,20 @@ impl PatternDetector for BlackMarubozuDetector {
 }
 
 /// White Marubozu - White candle with no shadows (Open=Low, Close=High)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct WhiteMarubozuDetector {
     /// Maximum shadow tolerance as ratio of range
     pub shadow_tolerance: Ratio,
+    /// How shadow length is judged (range-based by default)
+    #[serde(default)]
+    pub shadow_mode: ShadowMode,
 }
 
 impl Default for WhiteMarubozuDetector {
     fn default() -> Self {
         Self {
             shadow_tolerance: Ratio::new_const(0.01),
+            shadow_mode: ShadowMode::Range,
         }
     }
 }
@@ -1285,12 +1603,12 @@ impl PatternDetector for WhiteMarubozuDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
         // Must be bullish
-        if !bar.is_bullish() {
+        if !ctx.color.is_white(bar) {
             return None;
         }
 
@@ -1299,20 +1617,29 @@ impl PatternDetector for WhiteMarubozuDetector {
             return None;
         }
 
-        let tolerance = range * self.shadow_tolerance.get();
+        let tolerance = self.shadow_tolerance.get();
 
         // Open = Low (no lower shadow)
         let lower_shadow = bar.open() - bar.low();
         // Close = High (no upper shadow)
         let upper_shadow = bar.high() - bar.close();
 
-        if upper_shadow <= tolerance && lower_shadow <= tolerance {
+        if self
+            .shadow_mode
+            .is_short(upper_shadow, range, tolerance, ctx)
+            && self
+                .shadow_mode
+                .is_short(lower_shadow, range, tolerance, ctx)
+        {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bullish,
-                strength: 0.8 + (1.0 - (upper_shadow + lower_shadow) / range) * 0.2,
+                strength: helpers::clamp_strength(
+                    0.8 + (1.0 - (upper_shadow + lower_shadow) / range) * 0.2,
+                ),
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1321,16 +1648,20 @@ impl PatternDetector for WhiteMarubozuDetector {
 }
 
 /// Opening Black Marubozu - Black candle with no upper shadow (Open=High), has lower shadow
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct OpeningBlackMarubozuDetector {
     /// Maximum upper shadow tolerance as ratio of range
     pub shadow_tolerance: Ratio,
+    /// How shadow length is judged (range-based by default)
+    #[serde(default)]
+    pub shadow_mode: ShadowMode,
 }
 
 impl Default for OpeningBlackMarubozuDetector {
     fn default() -> Self {
         Self {
             shadow_tolerance: Ratio::new_const(0.01),
+            shadow_mode: ShadowMode::Range,
         }
     }
 }
@@ -1348,12 +1679,12 @@ impl PatternDetector for OpeningBlackMarubozuDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
         // Must be bearish
-        if !bar.is_bearish() {
+        if !ctx.color.is_black(bar) {
             return None;
         }
 
@@ -1362,20 +1693,27 @@ impl PatternDetector for OpeningBlackMarubozuDetector {
             return None;
         }
 
-        let tolerance = range * self.shadow_tolerance.get();
+        let tolerance = self.shadow_tolerance.get();
 
         // Open = High (no upper shadow)
         let upper_shadow = bar.high() - bar.open();
         // Has lower shadow (Close > Low)
         let lower_shadow = bar.close() - bar.low();
 
-        if upper_shadow <= tolerance && lower_shadow > tolerance {
+        if self
+            .shadow_mode
+            .is_short(upper_shadow, range, tolerance, ctx)
+            && !self
+                .shadow_mode
+                .is_short(lower_shadow, range, tolerance, ctx)
+        {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bearish,
                 strength: 0.7,
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1384,16 +1722,20 @@ impl PatternDetector for OpeningBlackMarubozuDetector {
 }
 
 /// Opening White Marubozu - White candle with no lower shadow (Open=Low), has upper shadow
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct OpeningWhiteMarubozuDetector {
     /// Maximum lower shadow tolerance as ratio of range
     pub shadow_tolerance: Ratio,
+    /// How shadow length is judged (range-based by default)
+    #[serde(default)]
+    pub shadow_mode: ShadowMode,
 }
 
 impl Default for OpeningWhiteMarubozuDetector {
     fn default() -> Self {
         Self {
             shadow_tolerance: Ratio::new_const(0.01),
+            shadow_mode: ShadowMode::Range,
         }
     }
 }
@@ -1411,12 +1753,12 @@ impl PatternDetector for OpeningWhiteMarubozuDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
         // Must be bullish
-        if !bar.is_bullish() {
+        if !ctx.color.is_white(bar) {
             return None;
         }
 
@@ -1425,20 +1767,27 @@ impl PatternDetector for OpeningWhiteMarubozuDetector {
             return None;
         }
 
-        let tolerance = range * self.shadow_tolerance.get();
+        let tolerance = self.shadow_tolerance.get();
 
         // Open = Low (no lower shadow)
         let lower_shadow = bar.open() - bar.low();
         // Has upper shadow (Close < High)
         let upper_shadow = bar.high() - bar.close();
 
-        if lower_shadow <= tolerance && upper_shadow > tolerance {
+        if self
+            .shadow_mode
+            .is_short(lower_shadow, range, tolerance, ctx)
+            && !self
+                .shadow_mode
+                .is_short(upper_shadow, range, tolerance, ctx)
+        {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bullish,
                 strength: 0.7,
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1451,7 +1800,7 @@ impl PatternDetector for OpeningWhiteMarubozuDetector {
 // ============================================================
 
 /// Black Candle - Simple bearish candle (Close < Open)
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct BlackCandleDetector;
 
 impl PatternDetector for BlackCandleDetector {
@@ -1467,17 +1816,18 @@ impl PatternDetector for BlackCandleDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
-        if bar.is_bearish() {
+        if ctx.color.is_black(bar) {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bearish,
                 strength: 0.5,
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1486,7 +1836,7 @@ impl PatternDetector for BlackCandleDetector {
 }
 
 /// White Candle - Simple bullish candle (Close > Open)
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct WhiteCandleDetector;
 
 impl PatternDetector for WhiteCandleDetector {
@@ -1502,17 +1852,18 @@ impl PatternDetector for WhiteCandleDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
-        if bar.is_bullish() {
+        if ctx.color.is_white(bar) {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bullish,
                 strength: 0.5,
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1521,7 +1872,7 @@ impl PatternDetector for WhiteCandleDetector {
 }
 
 /// Short Black - Bearish candle with short body
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ShortBlackDetector {
     /// Maximum body size as ratio of ATR or range
     pub body_pct: Ratio,
@@ -1552,7 +1903,7 @@ impl PatternDetector for ShortBlackDetector {
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
-        if !bar.is_bearish() {
+        if !ctx.color.is_black(bar) {
             return None;
         }
 
@@ -1572,6 +1923,7 @@ impl PatternDetector for ShortBlackDetector {
                 strength: 0.5,
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1580,7 +1932,7 @@ impl PatternDetector for ShortBlackDetector {
 }
 
 /// Short White - Bullish candle with short body
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ShortWhiteDetector {
     /// Maximum body size as ratio of ATR or range
     pub body_pct: Ratio,
@@ -1611,7 +1963,7 @@ impl PatternDetector for ShortWhiteDetector {
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
-        if !bar.is_bullish() {
+        if !ctx.color.is_white(bar) {
             return None;
         }
 
@@ -1631,6 +1983,7 @@ impl PatternDetector for ShortWhiteDetector {
                 strength: 0.5,
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1639,7 +1992,7 @@ impl PatternDetector for ShortWhiteDetector {
 }
 
 /// Long Black Day - Bearish candle with long body
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct LongBlackDayDetector {
     /// Minimum body size as ratio of range (typically >70%)
     pub body_pct: Ratio,
@@ -1666,11 +2019,11 @@ impl PatternDetector for LongBlackDayDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
-        if !bar.is_bearish() {
+        if !ctx.color.is_black(bar) {
             return None;
         }
 
@@ -1684,9 +2037,10 @@ impl PatternDetector for LongBlackDayDetector {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bearish,
-                strength: 0.6 + body_ratio * 0.3,
+                strength: helpers::clamp_strength(0.6 + body_ratio * 0.3),
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1695,7 +2049,7 @@ impl PatternDetector for LongBlackDayDetector {
 }
 
 /// Long White Day - Bullish candle with long body
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct LongWhiteDayDetector {
     /// Minimum body size as ratio of range (typically >70%)
     pub body_pct: Ratio,
@@ -1722,11 +2076,11 @@ impl PatternDetector for LongWhiteDayDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
-        if !bar.is_bullish() {
+        if !ctx.color.is_white(bar) {
             return None;
         }
 
@@ -1740,9 +2094,10 @@ impl PatternDetector for LongWhiteDayDetector {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bullish,
-                strength: 0.6 + body_ratio * 0.3,
+                strength: helpers::clamp_strength(0.6 + body_ratio * 0.3),
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1751,7 +2106,7 @@ impl PatternDetector for LongWhiteDayDetector {
 }
 
 /// Black Spinning Top - Black candle with small body and shadows on both sides
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BlackSpinningTopDetector {
     /// Maximum body size as ratio of range
     pub body_pct: Ratio,
@@ -1781,11 +2136,11 @@ impl PatternDetector for BlackSpinningTopDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
-        if !bar.is_bearish() {
+        if !ctx.color.is_black(bar) {
             return None;
         }
 
@@ -1811,9 +2166,10 @@ impl PatternDetector for BlackSpinningTopDetector {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Neutral, // Indecision
-                strength: 0.5 + (1.0 - body_ratio) * 0.3,
+                strength: helpers::clamp_strength(0.5 + (1.0 - body_ratio) * 0.3),
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1822,7 +2178,7 @@ impl PatternDetector for BlackSpinningTopDetector {
 }
 
 /// White Spinning Top - White candle with small body and shadows on both sides
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct WhiteSpinningTopDetector {
     /// Maximum body size as ratio of range
     pub body_pct: Ratio,
@@ -1852,11 +2208,11 @@ impl PatternDetector for WhiteSpinningTopDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
 
-        if !bar.is_bullish() {
+        if !ctx.color.is_white(bar) {
             return None;
         }
 
@@ -1882,9 +2238,10 @@ impl PatternDetector for WhiteSpinningTopDetector {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Neutral, // Indecision
-                strength: 0.5 + (1.0 - body_ratio) * 0.3,
+                strength: helpers::clamp_strength(0.5 + (1.0 - body_ratio) * 0.3),
                 start_index: index,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1897,19 +2254,20 @@ impl PatternDetector for WhiteSpinningTopDetector {
 // ============================================================
 
 /// Shooting Star 2-Lines - Classic shooting star pattern with trend context
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ShootingStar2LinesDetector {
     /// Maximum body size as ratio of range
     pub body_pct: Ratio,
-    /// Minimum upper shadow to body ratio
-    pub shadow_ratio: Ratio,
+    /// Minimum upper shadow to body ratio, a positive factor (default 2.0)
+    #[serde(deserialize_with = "crate::deserialize_factor")]
+    pub shadow_ratio: f64,
 }
 
 impl Default for ShootingStar2LinesDetector {
     fn default() -> Self {
         Self {
             body_pct: Ratio::new_const(0.3),
-            shadow_ratio: Ratio::new_const(2.0),
+            shadow_ratio: 2.0,
         }
     }
 }
@@ -1942,7 +2300,7 @@ impl PatternDetector for ShootingStar2LinesDetector {
         }
 
         // Previous should be bullish (continuation of uptrend)
-        if !prev.is_bullish() {
+        if !ctx.color.is_white(prev) {
             return None;
         }
 
@@ -1966,15 +2324,18 @@ impl PatternDetector for ShootingStar2LinesDetector {
         // Upper shadow should be at least shadow_ratio times the body
         // Lower shadow should be minimal
         if body > f64::EPSILON
-            && upper_shadow / body >= self.shadow_ratio.get()
+            && upper_shadow / body >= self.shadow_ratio
             && lower_shadow < upper_shadow * 0.3
         {
             return Some(PatternMatch {
                 pattern_id: PatternDetector::id(self),
                 direction: Direction::Bearish,
-                strength: 0.7 + (upper_shadow / body - self.shadow_ratio.get()).min(0.3) * 0.1,
+                strength: helpers::clamp_strength(
+                    0.7 + (upper_shadow / body - self.shadow_ratio).min(0.3) * 0.1,
+                ),
                 start_index: index - 1,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1987,7 +2348,7 @@ impl PatternDetector for ShootingStar2LinesDetector {
 // ============================================================
 
 /// Downside Gap Three Methods - Gap down, then white candle closes the gap (bearish continuation)
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct DownsideGapThreeMethodsDetector;
 
 impl PatternDetector for DownsideGapThreeMethodsDetector {
@@ -2003,7 +2364,7 @@ impl PatternDetector for DownsideGapThreeMethodsDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -2014,7 +2375,7 @@ impl PatternDetector for DownsideGapThreeMethodsDetector {
         let third = bars.get(index)?;
 
         // First two are bearish with gap down between them
-        if !first.is_bearish() || !second.is_bearish() {
+        if !ctx.color.is_black(first) || !ctx.color.is_black(second) {
             return None;
         }
 
@@ -2024,7 +2385,7 @@ impl PatternDetector for DownsideGapThreeMethodsDetector {
         }
 
         // Third is bullish and closes the gap (opens in second body, closes in first body)
-        if !third.is_bullish() {
+        if !ctx.color.is_white(third) {
             return None;
         }
 
@@ -2044,12 +2405,13 @@ impl PatternDetector for DownsideGapThreeMethodsDetector {
             strength: 0.7,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// Upside Gap Three Methods - Gap up, then black candle closes the gap (bullish continuation)
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
 pub struct UpsideGapThreeMethodsDetector;
 
 impl PatternDetector for UpsideGapThreeMethodsDetector {
@@ -2065,7 +2427,7 @@ impl PatternDetector for UpsideGapThreeMethodsDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -2076,7 +2438,7 @@ impl PatternDetector for UpsideGapThreeMethodsDetector {
         let third = bars.get(index)?;
 
         // First two are bullish with gap up between them
-        if !first.is_bullish() || !second.is_bullish() {
+        if !ctx.color.is_white(first) || !ctx.color.is_white(second) {
             return None;
         }
 
@@ -2086,7 +2448,7 @@ impl PatternDetector for UpsideGapThreeMethodsDetector {
         }
 
         // Third is bearish and closes the gap (opens in second body, closes in first body)
-        if !third.is_bearish() {
+        if !ctx.color.is_black(third) {
             return None;
         }
 
@@ -2106,6 +2468,7 @@ impl PatternDetector for UpsideGapThreeMethodsDetector {
             strength: 0.7,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -2115,7 +2478,7 @@ impl PatternDetector for UpsideGapThreeMethodsDetector {
 // ============================================================
 
 /// Downside Tasuki Gap - Gap down + white candle partially fills gap but doesn't close it
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DownsideTasukiGapDetector {
     /// Maximum gap fill percentage (pattern invalid if gap is fully closed)
     pub gap_fill_pct: Ratio,
@@ -2142,7 +2505,7 @@ impl PatternDetector for DownsideTasukiGapDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -2153,7 +2516,7 @@ impl PatternDetector for DownsideTasukiGapDetector {
         let third = bars.get(index)?;
 
         // First two are bearish
-        if !first.is_bearish() || !second.is_bearish() {
+        if !ctx.color.is_black(first) || !ctx.color.is_black(second) {
             return None;
         }
 
@@ -2167,7 +2530,7 @@ impl PatternDetector for DownsideTasukiGapDetector {
         let gap_size = gap_top - gap_bottom;
 
         // Third is bullish
-        if !third.is_bullish() {
+        if !ctx.color.is_white(third) {
             return None;
         }
 
@@ -2185,15 +2548,16 @@ impl PatternDetector for DownsideTasukiGapDetector {
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction: Direction::Bearish, // Bearish continuation
-            strength: 0.65 + (1.0 - fill_pct) * 0.2,
+            strength: helpers::clamp_strength(0.65 + (1.0 - fill_pct) * 0.2),
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// Upside Tasuki Gap - Gap up + black candle partially fills gap but doesn't close it
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct UpsideTasukiGapDetector {
     /// Maximum gap fill percentage (pattern invalid if gap is fully closed)
     pub gap_fill_pct: Ratio,
@@ -2220,7 +2584,7 @@ impl PatternDetector for UpsideTasukiGapDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -2231,7 +2595,7 @@ impl PatternDetector for UpsideTasukiGapDetector {
         let third = bars.get(index)?;
 
         // First two are bullish
-        if !first.is_bullish() || !second.is_bullish() {
+        if !ctx.color.is_white(first) || !ctx.color.is_white(second) {
             return None;
         }
 
@@ -2245,7 +2609,7 @@ impl PatternDetector for UpsideTasukiGapDetector {
         let gap_size = gap_top - gap_bottom;
 
         // Third is bearish
-        if !third.is_bearish() {
+        if !ctx.color.is_black(third) {
             return None;
         }
 
@@ -2263,77 +2627,699 @@ impl PatternDetector for UpsideTasukiGapDetector {
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction: Direction::Bullish, // Bullish continuation
-            strength: 0.65 + (1.0 - fill_pct) * 0.2,
+            strength: helpers::clamp_strength(0.65 + (1.0 - fill_pct) * 0.2),
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 // ============================================================
-// PARAMETERIZED DETECTOR IMPLEMENTATIONS
+// TOWER TOP / BOTTOM
 // ============================================================
 
-static GAPPING_DOWN_DOJI_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "body_pct",
-    param_type: ParamType::Ratio,
-    default: 0.1,
-    range: (0.05, 0.2, 0.05),
-    description: "Maximum doji body ratio",
-}];
+/// Tower Top - long white candle, a run of small candles, then a long black candle (bearish)
+///
+/// The small candles must hold above the midpoint of the first candle's body
+/// (the "tower"), and the final black candle must close back below it.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TowerTopDetector {
+    /// Number of small consolidation candles between the two long candles
+    pub consolidation: Period,
+    /// Minimum body size of the two long candles as ratio of range
+    pub body_pct: Ratio,
+    /// Maximum body of each consolidation candle as ratio of the smaller long body
+    pub small_body_ratio: Ratio,
+}
 
-static GAPPING_UP_DOJI_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "body_pct",
-    param_type: ParamType::Ratio,
-    default: 0.1,
-    range: (0.05, 0.2, 0.05),
-    description: "Maximum doji body ratio",
-}];
+impl Default for TowerTopDetector {
+    fn default() -> Self {
+        Self {
+            consolidation: Period::new_const(3),
+            body_pct: Ratio::new_const(0.6),
+            small_body_ratio: Ratio::new_const(0.5),
+        }
+    }
+}
 
-static ABOVE_THE_STOMACH_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "penetration",
-    param_type: ParamType::Ratio,
-    default: 0.0,
-    range: (0.0, 0.3, 0.1),
-    description: "Minimum penetration above midpoint",
-}];
+impl PatternDetector for TowerTopDetector {
+    fn id(&self) -> PatternId {
+        PatternId("TOWER_TOP")
+    }
 
-static BELOW_THE_STOMACH_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "penetration",
-    param_type: ParamType::Ratio,
-    default: 0.0,
-    range: (0.0, 0.3, 0.1),
-    description: "Minimum penetration below midpoint",
-}];
+    fn min_bars(&self) -> usize {
+        self.consolidation.get() + 2
+    }
 
-static COLLAPSING_DOJI_STAR_PARAMS: &[ParamMeta] = &[
-    ParamMeta {
-        name: "body_pct",
-        param_type: ParamType::Ratio,
-        default: 0.1,
-        range: (0.05, 0.2, 0.05),
-        description: "Maximum doji body ratio",
-    },
-    ParamMeta {
-        name: "gap_pct",
-        param_type: ParamType::Ratio,
-        default: 0.005,
-        range: (0.002, 0.01, 0.002),
-        description: "Minimum gap percentage",
-    },
-];
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        let start = index.checked_sub(self.consolidation.get() + 1)?;
+        let first = bars.get(start)?;
+        let last = bars.get(index)?;
 
-static DELIBERATION_PARAMS: &[ParamMeta] = &[
-    ParamMeta {
-        name: "body_pct",
-        param_type: ParamType::Ratio,
-        default: 0.3,
-        range: (0.2, 0.5, 0.1),
-        description: "Maximum third candle body ratio",
-    },
-    ParamMeta {
-        name: "long_body_pct",
-        param_type: ParamType::Ratio,
+        if !ctx.color.is_white(first) || !ctx.color.is_black(last) {
+            return None;
+        }
+        if first.body_ratio()? < self.body_pct.get() || last.body_ratio()? < self.body_pct.get() {
+            return None;
+        }
+
+        // Consolidation: small bodies holding in the upper half of the first body
+        let midpoint = (first.open() + first.close()) / 2.0;
+        let max_small = first.body().min(last.body()) * self.small_body_ratio.get();
+        let holds = bars[start + 1..index]
+            .iter()
+            .all(|b| b.body() <= max_small && b.open().min(b.close()) > midpoint);
+        if !holds {
+            return None;
+        }
+
+        // Final black candle gives the tower back
+        if last.close() >= midpoint {
+            return None;
+        }
+
+        let depth = (first.close() - last.close()) / first.body();
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction: Direction::Bearish,
+            strength: helpers::clamp_strength(0.5 + 0.3 * depth),
+            start_index: start,
+            end_index: index,
+            metric: None,
+        })
+    }
+}
+
+/// Tower Bottom - long black candle, a run of small candles, then a long white candle (bullish)
+///
+/// Mirror of [`TowerTopDetector`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TowerBottomDetector {
+    /// Number of small consolidation candles between the two long candles
+    pub consolidation: Period,
+    /// Minimum body size of the two long candles as ratio of range
+    pub body_pct: Ratio,
+    /// Maximum body of each consolidation candle as ratio of the smaller long body
+    pub small_body_ratio: Ratio,
+}
+
+impl Default for TowerBottomDetector {
+    fn default() -> Self {
+        Self {
+            consolidation: Period::new_const(3),
+            body_pct: Ratio::new_const(0.6),
+            small_body_ratio: Ratio::new_const(0.5),
+        }
+    }
+}
+
+impl PatternDetector for TowerBottomDetector {
+    fn id(&self) -> PatternId {
+        PatternId("TOWER_BOTTOM")
+    }
+
+    fn min_bars(&self) -> usize {
+        self.consolidation.get() + 2
+    }
+
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        let start = index.checked_sub(self.consolidation.get() + 1)?;
+        let first = bars.get(start)?;
+        let last = bars.get(index)?;
+
+        if !ctx.color.is_black(first) || !ctx.color.is_white(last) {
+            return None;
+        }
+        if first.body_ratio()? < self.body_pct.get() || last.body_ratio()? < self.body_pct.get() {
+            return None;
+        }
+
+        // Consolidation: small bodies holding in the lower half of the first body
+        let midpoint = (first.open() + first.close()) / 2.0;
+        let max_small = first.body().min(last.body()) * self.small_body_ratio.get();
+        let holds = bars[start + 1..index]
+            .iter()
+            .all(|b| b.body() <= max_small && b.open().max(b.close()) < midpoint);
+        if !holds {
+            return None;
+        }
+
+        // Final white candle lifts back out of the base
+        if last.close() <= midpoint {
+            return None;
+        }
+
+        let depth = (last.close() - first.close()) / first.body();
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction: Direction::Bullish,
+            strength: helpers::clamp_strength(0.5 + 0.3 * depth),
+            start_index: start,
+            end_index: index,
+            metric: None,
+        })
+    }
+}
+
+// ============================================================
+// FRY PAN BOTTOM
+// ============================================================
+
+/// Fry Pan Bottom - lows round out over a window, then a gap up or strong close (bullish)
+///
+/// The `period` bars before the breakout bar form the pan: lows drift down
+/// to a trough and back up, with the trough at least two bars from either
+/// edge. `tolerance` is the fraction of bar-to-bar steps allowed to go the
+/// wrong way on each side, so noisy series still round out. The breakout bar
+/// either gaps above the prior bar's high or is a white candle closing above
+/// every close in the pan.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FryPanBottomDetector {
+    /// Number of bars forming the rounded bottom
+    pub period: Period,
+    /// Fraction of steps on each side of the trough allowed to break the curve
+    pub tolerance: Ratio,
+}
+
+impl Default for FryPanBottomDetector {
+    fn default() -> Self {
+        Self {
+            period: Period::new_const(12),
+            tolerance: Ratio::new_const(0.2),
+        }
+    }
+}
+
+impl PatternDetector for FryPanBottomDetector {
+    fn id(&self) -> PatternId {
+        PatternId("FRY_PAN_BOTTOM")
+    }
+
+    fn min_bars(&self) -> usize {
+        self.period.get() + 1
+    }
+
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        let start = index.checked_sub(self.period.get())?;
+        let curr = bars.get(index)?;
+        let pan = &bars[start..index];
+        // Breakout bar: gap above the last pan bar or a white close above the pan
+        let prev = pan.last()?;
+        let gap_up = curr.low() > prev.high();
+        let pan_high_close = pan.iter().map(|b| b.close()).fold(f64::MIN, f64::max);
+        let strong_close = ctx.color.is_white(curr) && curr.close() > pan_high_close;
+        if !gap_up && !strong_close {
+            return None;
+        }
+
+        // Earliest lowest low, kept away from the edges of the window
+        let trough = (0..pan.len()).fold(0, |m, i| if pan[i].low() < pan[m].low() { i } else { m });
+        if trough < 2 || trough + 2 >= pan.len() {
+            return None;
+        }
+
+        // Lows fall into the trough and rise out of it, up to `tolerance` misses per side
+        let falling_misses = pan[..=trough]
+            .windows(2)
+            .filter(|w| w[1].low() > w[0].low())
+            .count();
+        let rising_misses = pan[trough..]
+            .windows(2)
+            .filter(|w| w[1].low() < w[0].low())
+            .count();
+        let tolerance = self.tolerance.get();
+        if falling_misses as f64 > tolerance * trough as f64
+            || rising_misses as f64 > tolerance * (pan.len() - 1 - trough) as f64
+        {
+            return None;
+        }
+
+        let misses = (falling_misses + rising_misses) as f64 / (pan.len() - 1) as f64;
+        let mut strength = 0.7 - 0.3 * misses;
+        if gap_up {
+            strength += 0.15;
+        }
+        if strong_close {
+            strength += 0.15;
+        }
+
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction: Direction::Bullish,
+            strength: helpers::clamp_strength(strength),
+            start_index: start,
+            end_index: index,
+            metric: None,
+        })
+    }
+}
+
+// ============================================================
+// CONSOLIDATION
+// ============================================================
+
+/// Consolidation - a cluster of spinning tops / dojis (neutral)
+///
+/// A single small-bodied candle says little; several of them close together
+/// mark a market at rest. Fires when at least `min_count` of the last
+/// `window` bars (current bar included) have a body no larger than
+/// `body_pct` of their range. Zero-range bars count as small. Strength grows
+/// from 0.5 at `min_count` small bodies to 1.0 when the whole window is small.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConsolidationDetector {
+    /// Number of bars examined (N)
+    pub window: Period,
+    /// Minimum number of small-bodied bars in the window (K)
+    pub min_count: Period,
+    /// Maximum body size as ratio of range
+    pub body_pct: Ratio,
+}
+
+impl Default for ConsolidationDetector {
+    fn default() -> Self {
+        Self {
+            window: Period::new_const(6),
+            min_count: Period::new_const(4),
+            body_pct: Ratio::new_const(0.3),
+        }
+    }
+}
+
+impl PatternDetector for ConsolidationDetector {
+    fn id(&self) -> PatternId {
+        PatternId("CONSOLIDATION")
+    }
+
+    fn min_bars(&self) -> usize {
+        self.window.get()
+    }
+
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        _ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        let window = self.window.get();
+        let min_count = self.min_count.get();
+        if min_count > window || index >= bars.len() {
+            return None;
+        }
+        let start = (index + 1).checked_sub(window)?;
+
+        let body_pct = self.body_pct.get();
+        let small = bars[start..=index]
+            .iter()
+            .filter(|b| {
+                let range = b.range();
+                range <= f64::EPSILON || b.body() / range <= body_pct
+            })
+            .count();
+        if small < min_count {
+            return None;
+        }
+
+        let spread = window - min_count;
+        let strength = if spread == 0 {
+            1.0
+        } else {
+            0.5 + 0.5 * (small - min_count) as f64 / spread as f64
+        };
+
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction: Direction::Neutral,
+            strength: helpers::clamp_strength(strength),
+            start_index: start,
+            end_index: index,
+            metric: None,
+        })
+    }
+}
+
+// ============================================================
+// PRICE ACTION
+// ============================================================
+
+/// Kicker - two opposite marubozus separated by a gap (bullish or bearish)
+///
+/// Same shape as `CDL_KICKING`, under the name most traders use for it. The
+/// prior trend is deliberately ignored: the gap between the two marubozus is
+/// the whole signal, so a kicker fires in any market context.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct KickerDetector {
+    /// Maximum shadow as ratio of range for each marubozu
+    pub shadow_max_ratio: Ratio,
+}
+
+impl Default for KickerDetector {
+    fn default() -> Self {
+        Self {
+            shadow_max_ratio: Ratio::new_const(0.05),
+        }
+    }
+}
+
+impl PatternDetector for KickerDetector {
+    fn id(&self) -> PatternId {
+        PatternId("KICKER")
+    }
+
+    fn min_bars(&self) -> usize {
+        2
+    }
+
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        let kicking = KickingDetector {
+            shadow_max_ratio: self.shadow_max_ratio,
+        };
+        let m = PatternDetector::detect(&kicking, bars, index, ctx)?;
+
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            ..m
+        })
+    }
+}
+
+/// New High - close breaks above the highest high of the prior `period` bars
+///
+/// A breakout signal rather than a candle shape; the reported span covers
+/// the lookback window and the breakout bar. Strength grows with how far the
+/// close clears the prior high, relative to the breakout bar's range.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NewHighDetector {
+    /// Number of prior bars whose highs must be exceeded
+    pub period: Period,
+}
+
+impl Default for NewHighDetector {
+    fn default() -> Self {
+        Self {
+            period: Period::new_const(20),
+        }
+    }
+}
+
+impl PatternDetector for NewHighDetector {
+    fn id(&self) -> PatternId {
+        PatternId("NEW_HIGH")
+    }
+
+    fn min_bars(&self) -> usize {
+        self.period.get() + 1
+    }
+
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        _ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        let start = index.checked_sub(self.period.get())?;
+        let curr = bars.get(index)?;
+        let prior_high = bars[start..index]
+            .iter()
+            .map(|b| b.high())
+            .fold(f64::MIN, f64::max);
+
+        let excess = curr.close() - prior_high;
+        if excess <= 0.0 {
+            return None;
+        }
+
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction: Direction::Bullish,
+            strength: helpers::clamp_strength(0.5 + 0.5 * excess / curr.range()),
+            start_index: start,
+            end_index: index,
+            metric: None,
+        })
+    }
+}
+
+/// New Low - close breaks below the lowest low of the prior `period` bars
+///
+/// Mirror of [`NewHighDetector`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NewLowDetector {
+    /// Number of prior bars whose lows must be undercut
+    pub period: Period,
+}
+
+impl Default for NewLowDetector {
+    fn default() -> Self {
+        Self {
+            period: Period::new_const(20),
+        }
+    }
+}
+
+impl PatternDetector for NewLowDetector {
+    fn id(&self) -> PatternId {
+        PatternId("NEW_LOW")
+    }
+
+    fn min_bars(&self) -> usize {
+        self.period.get() + 1
+    }
+
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        _ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        let start = index.checked_sub(self.period.get())?;
+        let curr = bars.get(index)?;
+        let prior_low = bars[start..index]
+            .iter()
+            .map(|b| b.low())
+            .fold(f64::MAX, f64::min);
+
+        let excess = prior_low - curr.close();
+        if excess <= 0.0 {
+            return None;
+        }
+
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction: Direction::Bearish,
+            strength: helpers::clamp_strength(0.5 + 0.5 * excess / curr.range()),
+            start_index: start,
+            end_index: index,
+            metric: None,
+        })
+    }
+}
+
+// ============================================================
+// PARAMETERIZED DETECTOR IMPLEMENTATIONS
+// ============================================================
+
+static TOWER_TOP_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "consolidation",
+        param_type: ParamType::Period,
+        default: 3.0,
+        range: (1.0, 8.0, 1.0),
+        description: "Small candles between the two long candles",
+    },
+    ParamMeta {
+        name: "body_pct",
+        param_type: ParamType::Ratio,
+        default: 0.6,
+        range: (0.5, 0.8, 0.1),
+        description: "Minimum long body ratio",
+    },
+    ParamMeta {
+        name: "small_body_ratio",
+        param_type: ParamType::Ratio,
+        default: 0.5,
+        range: (0.3, 0.7, 0.1),
+        description: "Maximum consolidation body relative to the long bodies",
+    },
+];
+
+static TOWER_BOTTOM_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "consolidation",
+        param_type: ParamType::Period,
+        default: 3.0,
+        range: (1.0, 8.0, 1.0),
+        description: "Small candles between the two long candles",
+    },
+    ParamMeta {
+        name: "body_pct",
+        param_type: ParamType::Ratio,
+        default: 0.6,
+        range: (0.5, 0.8, 0.1),
+        description: "Minimum long body ratio",
+    },
+    ParamMeta {
+        name: "small_body_ratio",
+        param_type: ParamType::Ratio,
+        default: 0.5,
+        range: (0.3, 0.7, 0.1),
+        description: "Maximum consolidation body relative to the long bodies",
+    },
+];
+
+static FRY_PAN_BOTTOM_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "period",
+        param_type: ParamType::Period,
+        default: 12.0,
+        range: (8.0, 30.0, 2.0),
+        description: "Bars forming the rounded bottom",
+    },
+    ParamMeta {
+        name: "tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.2,
+        range: (0.0, 0.4, 0.1),
+        description: "Fraction of steps allowed to break the curve",
+    },
+];
+
+static CONSOLIDATION_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "window",
+        param_type: ParamType::Period,
+        default: 6.0,
+        range: (4.0, 12.0, 1.0),
+        description: "Bars examined for small bodies",
+    },
+    ParamMeta {
+        name: "min_count",
+        param_type: ParamType::Period,
+        default: 4.0,
+        range: (2.0, 8.0, 1.0),
+        description: "Minimum small-bodied bars in the window",
+    },
+    ParamMeta {
+        name: "body_pct",
+        param_type: ParamType::Ratio,
+        default: 0.3,
+        range: (0.1, 0.4, 0.05),
+        description: "Maximum body/range ratio of a small body",
+    },
+];
+
+static KICKER_PARAMS: &[ParamMeta] = &[ParamMeta {
+    name: "shadow_max_ratio",
+    param_type: ParamType::Ratio,
+    default: 0.05,
+    range: (0.02, 0.1, 0.02),
+    description: "Maximum shadow ratio for marubozu",
+}];
+
+static NEW_HIGH_PARAMS: &[ParamMeta] = &[ParamMeta {
+    name: "period",
+    param_type: ParamType::Period,
+    default: 20.0,
+    range: (10.0, 55.0, 5.0),
+    description: "Prior bars whose high the close must exceed",
+}];
+
+static NEW_LOW_PARAMS: &[ParamMeta] = &[ParamMeta {
+    name: "period",
+    param_type: ParamType::Period,
+    default: 20.0,
+    range: (10.0, 55.0, 5.0),
+    description: "Prior bars whose low the close must undercut",
+}];
+
+static GAPPING_DOWN_DOJI_PARAMS: &[ParamMeta] = &[ParamMeta {
+    name: "body_pct",
+    param_type: ParamType::Ratio,
+    default: 0.1,
+    range: (0.05, 0.2, 0.05),
+    description: "Maximum doji body ratio",
+}];
+
+static GAPPING_UP_DOJI_PARAMS: &[ParamMeta] = &[ParamMeta {
+    name: "body_pct",
+    param_type: ParamType::Ratio,
+    default: 0.1,
+    range: (0.05, 0.2, 0.05),
+    description: "Maximum doji body ratio",
+}];
+
+static THREE_GAPS_PARAMS: &[ParamMeta] = &[ParamMeta {
+    name: "min_gap",
+    param_type: ParamType::Ratio,
+    default: 0.0,
+    range: (0.0, 0.5, 0.1),
+    description: "Minimum gap relative to the average range",
+}];
+
+static ABOVE_THE_STOMACH_PARAMS: &[ParamMeta] = &[ParamMeta {
+    name: "penetration",
+    param_type: ParamType::Ratio,
+    default: 0.0,
+    range: (0.0, 0.3, 0.1),
+    description: "Minimum penetration above midpoint",
+}];
+
+static BELOW_THE_STOMACH_PARAMS: &[ParamMeta] = &[ParamMeta {
+    name: "penetration",
+    param_type: ParamType::Ratio,
+    default: 0.0,
+    range: (0.0, 0.3, 0.1),
+    description: "Minimum penetration below midpoint",
+}];
+
+static COLLAPSING_DOJI_STAR_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "body_pct",
+        param_type: ParamType::Ratio,
+        default: 0.1,
+        range: (0.05, 0.2, 0.05),
+        description: "Maximum doji body ratio",
+    },
+    ParamMeta {
+        name: "gap_pct",
+        param_type: ParamType::Ratio,
+        default: 0.005,
+        range: (0.002, 0.01, 0.002),
+        description: "Minimum gap percentage",
+    },
+];
+
+static DELIBERATION_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "body_pct",
+        param_type: ParamType::Ratio,
+        default: 0.3,
+        range: (0.2, 0.5, 0.1),
+        description: "Maximum third candle body ratio",
+    },
+    ParamMeta {
+        name: "long_body_pct",
+        param_type: ParamType::Ratio,
         default: 0.6,
         range: (0.5, 0.8, 0.05),
         description: "Minimum body ratio for first/second candles",
@@ -2424,37 +3410,56 @@ static SOUTHERN_DOJI_PARAMS: &[ParamMeta] = &[
     },
 ];
 
-static BLACK_MARUBOZU_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "shadow_tolerance",
-    param_type: ParamType::Ratio,
-    default: 0.01,
-    range: (0.005, 0.03, 0.005),
-    description: "Maximum shadow tolerance",
-}];
+/// `shadow_mode` as a switch: 1 selects [`ShadowMode::TrailingAvg`]
+const SHADOW_MODE_PARAM: ParamMeta = ParamMeta::flag(
+    "trailing_avg_shadows",
+    false,
+    "Judge shadows against the trailing average range (TA-Lib)",
+);
 
-static WHITE_MARUBOZU_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "shadow_tolerance",
-    param_type: ParamType::Ratio,
-    default: 0.01,
-    range: (0.005, 0.03, 0.005),
-    description: "Maximum shadow tolerance",
-}];
+static BLACK_MARUBOZU_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "shadow_tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.01,
+        range: (0.005, 0.03, 0.005),
+        description: "Maximum shadow tolerance",
+    },
+    SHADOW_MODE_PARAM,
+];
 
-static OPENING_BLACK_MARUBOZU_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "shadow_tolerance",
-    param_type: ParamType::Ratio,
-    default: 0.01,
-    range: (0.005, 0.03, 0.005),
-    description: "Maximum shadow tolerance",
-}];
+static WHITE_MARUBOZU_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "shadow_tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.01,
+        range: (0.005, 0.03, 0.005),
+        description: "Maximum shadow tolerance",
+    },
+    SHADOW_MODE_PARAM,
+];
 
-static OPENING_WHITE_MARUBOZU_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "shadow_tolerance",
-    param_type: ParamType::Ratio,
-    default: 0.01,
-    range: (0.005, 0.03, 0.005),
-    description: "Maximum shadow tolerance",
-}];
+static OPENING_BLACK_MARUBOZU_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "shadow_tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.01,
+        range: (0.005, 0.03, 0.005),
+        description: "Maximum shadow tolerance",
+    },
+    SHADOW_MODE_PARAM,
+];
+
+static OPENING_WHITE_MARUBOZU_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "shadow_tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.01,
+        range: (0.005, 0.03, 0.005),
+        description: "Maximum shadow tolerance",
+    },
+    SHADOW_MODE_PARAM,
+];
 
 static SHORT_BLACK_PARAMS: &[ParamMeta] = &[ParamMeta {
     name: "body_pct",
@@ -2532,7 +3537,7 @@ static SHOOTING_STAR_2_LINES_PARAMS: &[ParamMeta] = &[
     },
     ParamMeta {
         name: "shadow_ratio",
-        param_type: ParamType::Ratio,
+        param_type: ParamType::Factor,
         default: 2.0,
         range: (1.5, 3.0, 0.5),
         description: "Minimum upper shadow to body ratio",
@@ -2587,6 +3592,22 @@ impl ParameterizedDetector for GappingUpDojiDetector {
     }
 }
 
+impl ParameterizedDetector for ThreeGapsDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        THREE_GAPS_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            min_gap: get_ratio(params, "min_gap", 0.0)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "THREE_GAPS"
+    }
+}
+
 impl ParameterizedDetector for AboveTheStomachDetector {
     fn param_meta() -> &'static [ParamMeta] {
         ABOVE_THE_STOMACH_PARAMS
@@ -2761,6 +3782,7 @@ impl ParameterizedDetector for BlackMarubozuDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             shadow_tolerance: get_ratio(params, "shadow_tolerance", 0.01)?,
+            shadow_mode: ShadowMode::from_params(params)?,
         })
     }
 
@@ -2777,6 +3799,7 @@ impl ParameterizedDetector for WhiteMarubozuDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             shadow_tolerance: get_ratio(params, "shadow_tolerance", 0.01)?,
+            shadow_mode: ShadowMode::from_params(params)?,
         })
     }
 
@@ -2793,6 +3816,7 @@ impl ParameterizedDetector for OpeningBlackMarubozuDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             shadow_tolerance: get_ratio(params, "shadow_tolerance", 0.01)?,
+            shadow_mode: ShadowMode::from_params(params)?,
         })
     }
 
@@ -2809,6 +3833,7 @@ impl ParameterizedDetector for OpeningWhiteMarubozuDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             shadow_tolerance: get_ratio(params, "shadow_tolerance", 0.01)?,
+            shadow_mode: ShadowMode::from_params(params)?,
         })
     }
 
@@ -2879,6 +3904,16 @@ impl ParameterizedDetector for LongWhiteDayDetector {
     fn pattern_id_str() -> &'static str {
         "LONG_WHITE_DAY"
     }
+
+    /// Largest `body_pct` that still accepts the bar at `index`
+    fn suggest_params<T: OHLCV>(bars: &[T], index: usize) -> Option<HashMap<&'static str, f64>> {
+        let bar = bars.get(index)?;
+        let body_ratio = bar.body_ratio()?;
+        if !ColorConvention::default().is_white(bar) {
+            return None;
+        }
+        Some(HashMap::from([("body_pct", body_ratio)]))
+    }
 }
 
 impl ParameterizedDetector for BlackSpinningTopDetector {
@@ -2923,7 +3958,7 @@ impl ParameterizedDetector for ShootingStar2LinesDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             body_pct: get_ratio(params, "body_pct", 0.3)?,
-            shadow_ratio: get_ratio(params, "shadow_ratio", 2.0)?,
+            shadow_ratio: get_positive(params, "shadow_ratio", 2.0)?,
         })
     }
 
@@ -2963,3 +3998,131 @@ impl ParameterizedDetector for UpsideTasukiGapDetector {
         "UPSIDE_TASUKI_GAP"
     }
 }
+
+impl ParameterizedDetector for TowerTopDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        TOWER_TOP_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            consolidation: get_period(params, "consolidation", 3)?,
+            body_pct: get_ratio(params, "body_pct", 0.6)?,
+            small_body_ratio: get_ratio(params, "small_body_ratio", 0.5)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "TOWER_TOP"
+    }
+}
+
+impl ParameterizedDetector for TowerBottomDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        TOWER_BOTTOM_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            consolidation: get_period(params, "consolidation", 3)?,
+            body_pct: get_ratio(params, "body_pct", 0.6)?,
+            small_body_ratio: get_ratio(params, "small_body_ratio", 0.5)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "TOWER_BOTTOM"
+    }
+}
+
+impl ParameterizedDetector for FryPanBottomDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        FRY_PAN_BOTTOM_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            period: get_period(params, "period", 12)?,
+            tolerance: get_ratio(params, "tolerance", 0.2)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "FRY_PAN_BOTTOM"
+    }
+}
+
+impl ParameterizedDetector for ConsolidationDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        CONSOLIDATION_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        let window = get_period(params, "window", 6)?;
+        let min_count = get_period(params, "min_count", 4)?;
+        if min_count > window {
+            return Err(PatternError::InvalidConfig(format!(
+                "CONSOLIDATION min_count {} exceeds window {}",
+                min_count.get(),
+                window.get()
+            )));
+        }
+        Ok(Self {
+            window,
+            min_count,
+            body_pct: get_ratio(params, "body_pct", 0.3)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "CONSOLIDATION"
+    }
+}
+
+impl ParameterizedDetector for KickerDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        KICKER_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            shadow_max_ratio: get_ratio(params, "shadow_max_ratio", 0.05)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "KICKER"
+    }
+}
+
+impl ParameterizedDetector for NewHighDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        NEW_HIGH_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            period: get_period(params, "period", 20)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "NEW_HIGH"
+    }
+}
+
+impl ParameterizedDetector for NewLowDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        NEW_LOW_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            period: get_period(params, "period", 20)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "NEW_LOW"
+    }
+}
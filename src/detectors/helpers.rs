@@ -39,6 +39,14 @@ pub const SHADOW_SHORT_RATIO: f64 = 0.1;
 // HELPER FUNCTIONS (TA-Lib style with ratio fallback)
 // ============================================================
 
+/// Whether a threshold compares against the trailing average rather than
+/// the ratio fallback. A NaN average (e.g. `WarmupPolicy::Nan`) counts as
+/// present, so the comparison fails and the bar is skipped.
+#[inline]
+pub(crate) fn uses_average(avg: f64) -> bool {
+    avg > 0.0 || avg.is_nan()
+}
+
 /// Check if body is doji-like (TA-Lib: BodyDoji)
 /// TA-Lib uses avg HL range * 0.1 (RangeType=HighLow, Factor=0.1)
 /// Falls back to ratio-based if avg_range is not meaningful.
@@ -49,7 +57,7 @@ pub fn is_doji(body: f64, avg_range: f64, range: f64) -> bool {
     if body <= 0.0 {
         return true;
     }
-    if avg_range > 0.0 {
+    if uses_average(avg_range) {
         body <= avg_range * DOJI_FACTOR
     } else {
         range > 0.0 && body / range <= DOJI_RATIO
@@ -59,7 +67,7 @@ pub fn is_doji(body: f64, avg_range: f64, range: f64) -> bool {
 /// Check if body is short (TA-Lib: BodyShort)
 #[inline]
 pub fn is_body_short(body: f64, avg_body: f64, range: f64) -> bool {
-    if avg_body > 0.0 {
+    if uses_average(avg_body) {
         body < avg_body * BODY_SHORT_FACTOR
     } else {
         range > 0.0 && body / range <= BODY_SHORT_RATIO
@@ -69,7 +77,7 @@ pub fn is_body_short(body: f64, avg_body: f64, range: f64) -> bool {
 /// Check if body is long (TA-Lib: BodyLong)
 #[inline]
 pub fn is_body_long(body: f64, avg_body: f64, range: f64) -> bool {
-    if avg_body > 0.0 {
+    if uses_average(avg_body) {
         body > avg_body * BODY_LONG_FACTOR
     } else {
         range > 0.0 && body / range >= BODY_LONG_RATIO
@@ -101,7 +109,7 @@ pub fn is_shadow_verylong(shadow: f64, body: f64, _range: f64) -> bool {
 /// Pass avg_shadow = avg(max(upper, lower)) over lookback period
 #[inline]
 pub fn is_shadow_short(shadow: f64, avg_shadow: f64, range: f64) -> bool {
-    if avg_shadow > 0.0 {
+    if uses_average(avg_shadow) {
         shadow < avg_shadow
     } else {
         range > 0.0 && shadow / range <= SHADOW_SHORT_RATIO
@@ -112,7 +120,7 @@ pub fn is_shadow_short(shadow: f64, avg_shadow: f64, range: f64) -> bool {
 /// Uses avg_range (HighLow) with factor 0.1
 #[inline]
 pub fn is_shadow_very_short(shadow: f64, avg_range: f64, range: f64) -> bool {
-    if avg_range > 0.0 {
+    if uses_average(avg_range) {
         shadow < avg_range * SHADOW_VERYSHORT_FACTOR
     } else {
         range > 0.0 && shadow / range <= SHADOW_SHORT_RATIO
@@ -123,26 +131,30 @@ pub fn is_shadow_very_short(shadow: f64, avg_range: f64, range: f64) -> bool {
 /// TA-Lib uses per-bar-position trailing averages for multi-bar patterns.
 #[inline]
 pub fn trailing_avg_body<T: crate::OHLCV>(bars: &[T], at: usize, period: usize) -> f64 {
-    use crate::OHLCVExt;
-    if at == 0 {
-        return OHLCVExt::body(&bars[0]);
-    }
-    let s = at.saturating_sub(period);
-    let slice = &bars[s..at];
-    let sum: f64 = slice.iter().map(|b| OHLCVExt::body(b)).sum();
-    sum / slice.len() as f64
+    crate::read_probe::untracked(|| {
+        use crate::OHLCVExt;
+        if at == 0 {
+            return OHLCVExt::body(&bars[0]);
+        }
+        let s = at.saturating_sub(period);
+        let slice = &bars[s..at];
+        let sum: f64 = slice.iter().map(|b| OHLCVExt::body(b)).sum();
+        sum / slice.len() as f64
+    })
 }
 
 /// Compute trailing average range at a specific bar index (for Near/Far/Equal).
 #[inline]
 pub fn trailing_avg_range<T: crate::OHLCV>(bars: &[T], at: usize, period: usize) -> f64 {
-    if at == 0 {
-        return crate::OHLCVExt::range(&bars[0]);
-    }
-    let s = at.saturating_sub(period);
-    let slice = &bars[s..at];
-    let sum: f64 = slice.iter().map(|b| crate::OHLCVExt::range(b)).sum();
-    sum / slice.len() as f64
+    crate::read_probe::untracked(|| {
+        if at == 0 {
+            return crate::OHLCVExt::range(&bars[0]);
+        }
+        let s = at.saturating_sub(period);
+        let slice = &bars[s..at];
+        let sum: f64 = slice.iter().map(|b| crate::OHLCVExt::range(b)).sum();
+        sum / slice.len() as f64
+    })
 }
 
 /// Compute trailing average shadow at a specific bar index.
@@ -150,17 +162,87 @@ pub fn trailing_avg_range<T: crate::OHLCV>(bars: &[T], at: usize, period: usize)
 /// Shadows = (upper_shadow + lower_shadow), then avg / 2.0 (per TA_CANDLEAVERAGE)
 #[inline]
 pub fn trailing_avg_shadow<T: crate::OHLCV>(bars: &[T], at: usize, period: usize) -> f64 {
-    use crate::OHLCVExt;
-    if at == 0 {
-        return (OHLCVExt::upper_shadow(&bars[0]) + OHLCVExt::lower_shadow(&bars[0])) / 2.0;
+    crate::read_probe::untracked(|| {
+        use crate::OHLCVExt;
+        if at == 0 {
+            return (OHLCVExt::upper_shadow(&bars[0]) + OHLCVExt::lower_shadow(&bars[0])) / 2.0;
+        }
+        let s = at.saturating_sub(period);
+        let slice = &bars[s..at];
+        let sum: f64 = slice
+            .iter()
+            .map(|b| OHLCVExt::upper_shadow(b) + OHLCVExt::lower_shadow(b))
+            .sum();
+        sum / slice.len() as f64 / 2.0
+    })
+}
+
+/// TA-Lib `TA_CANDLECOLOR`: `1` for white (`close >= open`), `-1` for black
+/// (`close < open`). A flat bar is white.
+///
+/// For custom detectors that must match TA-Lib exactly; builtin detectors go
+/// through `ctx.color`, whose default `ColorConvention::TaLib` is this
+/// function.
+#[inline]
+pub fn candle_color<T: crate::OHLCV + ?Sized>(bar: &T) -> i8 {
+    if bar.close() >= bar.open() {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Clamp a computed strength into `0.0..=1.0`, mapping NaN to `0.0`.
+/// Degenerate bars (zero range, extreme magnitudes) can push the strength
+/// formulas outside the unit interval; every computed strength goes through here.
+#[inline]
+pub fn clamp_strength(strength: f64) -> f64 {
+    if strength.is_nan() {
+        0.0
+    } else {
+        strength.clamp(0.0, 1.0)
+    }
+}
+
+/// Overlap still accepted as a real-body gap: `tolerance` of the average
+/// range. Zero (strict TA-Lib gap) when the average is not finite.
+#[inline]
+pub fn gap_slack(tolerance: crate::Ratio, avg_range: f64) -> f64 {
+    let slack = tolerance.get() * avg_range;
+    if slack.is_finite() {
+        slack.max(0.0)
+    } else {
+        0.0
+    }
+}
+
+/// Strength of a forming match whose setup is complete except for its last
+/// bar; scaled by the fraction of bars already formed.
+pub const FORMING_MAX_STRENGTH: f64 = 0.3;
+
+/// Speculative one-bar match at `index` for a pattern of `total_bars`,
+/// `formed` of which are present. The completeness is `formed / total_bars`
+/// and the strength `FORMING_MAX_STRENGTH` times that.
+#[inline]
+pub fn forming_match(
+    id: &'static str,
+    direction: crate::Direction,
+    index: usize,
+    formed: usize,
+    total_bars: usize,
+) -> crate::FormingMatch {
+    let completeness = formed as f64 / total_bars as f64;
+    crate::FormingMatch {
+        m: crate::PatternMatch {
+            pattern_id: crate::PatternId(id),
+            direction,
+            strength: clamp_strength(FORMING_MAX_STRENGTH * completeness),
+            start_index: index,
+            end_index: index,
+            metric: None,
+        },
+        completeness,
     }
-    let s = at.saturating_sub(period);
-    let slice = &bars[s..at];
-    let sum: f64 = slice
-        .iter()
-        .map(|b| OHLCVExt::upper_shadow(b) + OHLCVExt::lower_shadow(b))
-        .sum();
-    sum / slice.len() as f64 / 2.0
 }
 
 /// Check if a bar is a marubozu (no/minimal shadows).
@@ -172,6 +254,30 @@ pub fn is_marubozu<T: crate::OHLCVExt>(bar: &T, shadow_max_ratio: f64) -> Option
     Some(upper <= shadow_max_ratio && lower <= shadow_max_ratio)
 }
 
+/// Check for the shape shared by Hammer and Hanging Man: short real body
+/// (TA-Lib BodyShort), long lower shadow (ShadowLong: > body) and very short
+/// upper shadow (ShadowVeryShort). The two patterns differ only in the trend
+/// that precedes them.
+#[inline]
+pub fn is_hammer_shape<T: crate::OHLCVExt>(
+    bar: &T,
+    avg_body: f64,
+    avg_range: f64,
+    body_short_factor: f64,
+    shadow_veryshort_factor: f64,
+) -> bool {
+    let body = bar.body();
+    let range = bar.range();
+    is_body_short_f(body, avg_body, range, body_short_factor)
+        && is_shadow_long(bar.lower_shadow(), body, range)
+        && is_shadow_very_short_f(
+            bar.upper_shadow(),
+            avg_range,
+            range,
+            shadow_veryshort_factor,
+        )
+}
+
 // ============================================================
 // FACTOR-PARAMETERIZED VARIANTS
 // ============================================================
@@ -184,7 +290,7 @@ pub fn is_doji_f(body: f64, avg_range: f64, range: f64, factor: f64) -> bool {
     if body <= 0.0 {
         return true;
     }
-    if avg_range > 0.0 {
+    if uses_average(avg_range) {
         body <= avg_range * factor
     } else {
         range > 0.0 && body / range <= DOJI_RATIO
@@ -194,7 +300,7 @@ pub fn is_doji_f(body: f64, avg_range: f64, range: f64, factor: f64) -> bool {
 /// Like [`is_body_short`] but with a custom factor (replaces [`BODY_SHORT_FACTOR`]).
 #[inline]
 pub fn is_body_short_f(body: f64, avg_body: f64, range: f64, factor: f64) -> bool {
-    if avg_body > 0.0 {
+    if uses_average(avg_body) {
         body < avg_body * factor
     } else {
         range > 0.0 && body / range <= BODY_SHORT_RATIO
@@ -204,7 +310,7 @@ pub fn is_body_short_f(body: f64, avg_body: f64, range: f64, factor: f64) -> boo
 /// Like [`is_body_long`] but with a custom factor (replaces [`BODY_LONG_FACTOR`]).
 #[inline]
 pub fn is_body_long_f(body: f64, avg_body: f64, range: f64, factor: f64) -> bool {
-    if avg_body > 0.0 {
+    if uses_average(avg_body) {
         body > avg_body * factor
     } else {
         range > 0.0 && body / range >= BODY_LONG_RATIO
@@ -220,7 +326,7 @@ pub fn is_shadow_verylong_f(shadow: f64, body: f64, _range: f64, factor: f64) ->
 /// Like [`is_shadow_very_short`] but with a custom factor (replaces [`SHADOW_VERYSHORT_FACTOR`]).
 #[inline]
 pub fn is_shadow_very_short_f(shadow: f64, avg_range: f64, range: f64, factor: f64) -> bool {
-    if avg_range > 0.0 {
+    if uses_average(avg_range) {
         shadow < avg_range * factor
     } else {
         range > 0.0 && shadow / range <= SHADOW_SHORT_RATIO
@@ -233,7 +339,7 @@ pub fn is_shadow_very_short_f(shadow: f64, avg_range: f64, range: f64, factor: f
 #[inline]
 pub fn shadow_exceeds_veryshort(shadow: f64, avg_range: f64, factor: f64, range: f64) -> bool {
     let threshold = avg_range * factor;
-    if threshold > 0.0 {
+    if uses_average(threshold) {
         shadow > threshold
     } else if range > 0.0 {
         shadow / range > SHADOW_SHORT_RATIO
@@ -30,7 +30,7 @@ impl_with_defaults!(
 // ============================================================
 
 /// CDLBREAKAWAY - Breakaway (5-bar pattern)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BreakawayDetector {
     pub body_long_factor: f64,
 }
@@ -56,7 +56,7 @@ impl PatternDetector for BreakawayDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 4 {
             return None;
@@ -68,18 +68,10 @@ impl PatternDetector for BreakawayDetector {
         let fifth = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR convention (close >= open = white/+1, close < open = black/-1)
-        let color_first = if first.close() >= first.open() { 1 } else { -1 };
-        let color_second = if second.close() >= second.open() {
-            1
-        } else {
-            -1
-        };
-        let color_fourth = if fourth.close() >= fourth.open() {
-            1
-        } else {
-            -1
-        };
-        let color_fifth = if fifth.close() >= fifth.open() { 1 } else { -1 };
+        let color_first = ctx.color.sign(first);
+        let color_second = ctx.color.sign(second);
+        let color_fourth = ctx.color.sign(fourth);
+        let color_fifth = ctx.color.sign(fifth);
 
         // TA-Lib: colors i-4, i-3, i-1 same; i opposite (no check on i-2)
         if color_first != color_second {
@@ -138,6 +130,7 @@ impl PatternDetector for BreakawayDetector {
                 strength: 0.7,
                 start_index: index - 4,
                 end_index: index,
+                metric: None,
             })
         } else {
             // Black/bearish first — gap down: RealBodyGapDown(i-3, i-4)
@@ -167,6 +160,7 @@ impl PatternDetector for BreakawayDetector {
                 strength: 0.7,
                 start_index: index - 4,
                 end_index: index,
+                metric: None,
             })
         }
     }
@@ -177,7 +171,7 @@ impl PatternDetector for BreakawayDetector {
 // ============================================================
 
 /// CDLCONCEALBABYSWALL - Concealing Baby Swallow (4-bar pattern)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ConcealingBabySwallowDetector {
     pub shadow_max_ratio: Ratio,
 }
@@ -203,7 +197,7 @@ impl PatternDetector for ConcealingBabySwallowDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 3 {
             return None;
@@ -214,10 +208,10 @@ impl PatternDetector for ConcealingBabySwallowDetector {
         let fourth = bars.get(index)?;
 
         // All four must be bearish
-        if !first.is_bearish()
-            || !second.is_bearish()
-            || !third.is_bearish()
-            || !fourth.is_bearish()
+        if !ctx.color.is_black(first)
+            || !ctx.color.is_black(second)
+            || !ctx.color.is_black(third)
+            || !ctx.color.is_black(fourth)
         {
             return None;
         }
@@ -253,12 +247,22 @@ impl PatternDetector for ConcealingBabySwallowDetector {
             return None;
         }
 
+        // Graded by engulfing depth: fourth body relative to third's full range,
+        // 0.6 for a bare engulf up to 0.9 when the body is twice the range
+        let third_range = third.range();
+        let depth = if third_range > f64::EPSILON {
+            (fourth.body() / third_range - 1.0).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction: Direction::Bullish,
-            strength: 0.75,
+            strength: super::helpers::clamp_strength(0.6 + depth * 0.3),
             start_index: index - 3,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -275,7 +279,7 @@ impl PatternDetector for ConcealingBabySwallowDetector {
 ///
 /// This implementation simulates TA-Lib's state machine statelessly by looking backward
 /// from each bar to find the latest active setup and check for confirmation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HikkakeDetector;
 
 impl Default for HikkakeDetector {
@@ -289,7 +293,7 @@ impl HikkakeDetector {
     ///
     /// Setup: bars[j-1] is strictly inside bars[j-2], and bars[j] breaks out.
     /// Returns the direction if setup is found.
-    fn is_setup_at<T: OHLCV>(bars: &[T], j: usize) -> Option<Direction> {
+    pub(crate) fn is_setup_at<T: OHLCV>(bars: &[T], j: usize) -> Option<Direction> {
         if j < 2 || j >= bars.len() {
             return None;
         }
@@ -313,6 +317,44 @@ impl HikkakeDetector {
             None
         }
     }
+
+    /// Setup confirmed by the close at `index`: the latest setup within
+    /// `window` bars before it, not yet confirmed by an earlier bar.
+    ///
+    /// Returns the setup's breakout bar and direction.
+    pub(crate) fn confirmation_at<T: OHLCV>(
+        bars: &[T],
+        index: usize,
+        window: usize,
+    ) -> Option<(usize, Direction)> {
+        // Find the latest active setup within `window` bars back.
+        // We search from most recent (delta=1) first — a newer setup overwrites older ones,
+        // matching TA-Lib's patternIdx overwrite behavior.
+        for delta in 1..=window {
+            let j = match index.checked_sub(delta) {
+                Some(j) if j >= 2 => j,
+                _ => continue,
+            };
+
+            if let Some(dir) = Self::is_setup_at(bars, j) {
+                // Found the latest active setup at bar j.
+                let inside_bar = bars.get(j - 1)?;
+                let confirms = |bar: &T| match dir {
+                    Direction::Bullish => bar.close() > inside_bar.high(),
+                    Direction::Bearish => bar.close() < inside_bar.low(),
+                    _ => false,
+                };
+
+                // Not already confirmed at a bar between j+1 and index-1
+                let already_confirmed = bars.get((j + 1)..index)?.iter().any(confirms);
+                if !already_confirmed && confirms(bars.get(index)?) {
+                    return Some((j, dir));
+                }
+                return None; // Found the latest setup, stop searching
+            }
+        }
+        None
+    }
 }
 
 impl PatternDetector for HikkakeDetector {
@@ -344,60 +386,20 @@ impl PatternDetector for HikkakeDetector {
                 strength: 0.65,
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
         // Step 2: Check for confirmation → output ±200
-        // Find the latest active setup within 3 bars back.
-        // We search from most recent (delta=1) first — a newer setup overwrites older ones,
-        // matching TA-Lib's patternIdx overwrite behavior.
-        for delta in 1..=3usize {
-            let j = match index.checked_sub(delta) {
-                Some(j) if j >= 2 => j,
-                _ => continue,
-            };
-
-            if let Some(dir) = Self::is_setup_at(bars, j) {
-                // Found the latest active setup at bar j.
-                // Check if already confirmed at a bar between j+1 and index-1.
-                let inside_bar = &bars[j - 1];
-                let mut already_confirmed = false;
-
-                for bar in &bars[(j + 1)..index] {
-                    let confirmed_here = match dir {
-                        Direction::Bullish => bar.close() > inside_bar.high(),
-                        Direction::Bearish => bar.close() < inside_bar.low(),
-                        _ => false,
-                    };
-                    if confirmed_here {
-                        already_confirmed = true;
-                        break;
-                    }
-                }
-
-                if !already_confirmed {
-                    // Check confirmation at current bar
-                    let confirmed = match dir {
-                        Direction::Bullish => bars[index].close() > inside_bar.high(),
-                        Direction::Bearish => bars[index].close() < inside_bar.low(),
-                        _ => false,
-                    };
-                    if confirmed {
-                        return Some(PatternMatch {
-                            pattern_id: PatternDetector::id(self),
-                            direction: dir,
-                            strength: 0.75,
-                            start_index: j - 2,
-                            end_index: index,
-                        });
-                    }
-                }
-
-                break; // Found the latest setup, stop searching
-            }
-        }
-
-        None
+        let (j, dir) = Self::confirmation_at(bars, index, 3)?;
+        Some(PatternMatch {
+            pattern_id: PatternDetector::id(self),
+            direction: dir,
+            strength: 0.75,
+            start_index: j - 2,
+            end_index: index,
+            metric: None,
+        })
     }
 }
 
@@ -408,7 +410,7 @@ impl PatternDetector for HikkakeDetector {
 /// - **Confirmation** (±200): Within 3 bars after setup, close breaks the 3rd bar's level
 ///
 /// This implementation simulates TA-Lib's state machine statelessly by looking backward.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HikkakeModDetector {
     pub near_factor: f64,
 }
@@ -501,6 +503,7 @@ impl PatternDetector for HikkakeModDetector {
                 strength: 0.7,
                 start_index: index - 3,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -543,6 +546,7 @@ impl PatternDetector for HikkakeModDetector {
                             strength: 0.8,
                             start_index: j - 3,
                             end_index: index,
+                            metric: None,
                         });
                     }
                 }
@@ -560,15 +564,22 @@ impl PatternDetector for HikkakeModDetector {
 // ============================================================
 
 /// CDLLADDERBOTTOM - Ladder Bottom (5-bar pattern)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct LadderBottomDetector {
+    /// Fourth candle's upper shadow must exceed this times the average range
     pub shadow_veryshort_factor: f64,
+    /// Allowed rise in the opens and closes of the first three candles, as a
+    /// fraction of the trailing 5-bar average range at the first candle. 0
+    /// (TA-Lib) requires strictly descending values.
+    #[serde(default)]
+    pub close_tolerance: Ratio,
 }
 
 impl Default for LadderBottomDetector {
     fn default() -> Self {
         Self {
             shadow_veryshort_factor: super::helpers::SHADOW_VERYSHORT_FACTOR,
+            close_tolerance: Ratio::new_const(0.0),
         }
     }
 }
@@ -586,7 +597,7 @@ impl PatternDetector for LadderBottomDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 4 {
             return None;
@@ -598,24 +609,26 @@ impl PatternDetector for LadderBottomDetector {
         let fifth = bars.get(index)?;
 
         // TA-Lib: First three are black (close < open) with descending opens and closes
-        if first.close() >= first.open() {
+        if ctx.color.is_white(first) {
             return None;
         }
-        if second.close() >= second.open() {
+        if ctx.color.is_white(second) {
             return None;
         }
-        if third.close() >= third.open() {
+        if ctx.color.is_white(third) {
             return None;
         }
-        if second.open() >= first.open() || third.open() >= second.open() {
+        let tol =
+            super::helpers::trailing_avg_range(bars, index - 4, 5) * self.close_tolerance.get();
+        if second.open() >= first.open() + tol || third.open() >= second.open() + tol {
             return None;
         }
-        if second.close() >= first.close() || third.close() >= second.close() {
+        if second.close() >= first.close() + tol || third.close() >= second.close() + tol {
             return None;
         }
 
         // TA-Lib: Fourth is black (close < open) with upper shadow > ShadowVeryShort
-        if fourth.close() >= fourth.open() {
+        if ctx.color.is_white(fourth) {
             return None;
         }
         let fourth_upper = fourth.upper_shadow();
@@ -626,7 +639,7 @@ impl PatternDetector for LadderBottomDetector {
         }
 
         // TA-Lib: Fifth is white (close >= open), opens above fourth's open, closes above fourth's high
-        if fifth.close() < fifth.open() {
+        if ctx.color.is_black(fifth) {
             return None;
         }
         if fifth.open() <= fourth.open() {
@@ -642,6 +655,7 @@ impl PatternDetector for LadderBottomDetector {
             strength: 0.7,
             start_index: index - 4,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -651,7 +665,7 @@ impl PatternDetector for LadderBottomDetector {
 // ============================================================
 
 /// CDLMATHOLD - Mat Hold (5-bar pattern)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MatHoldDetector {
     pub body_long_factor: f64,
     pub body_short_factor: f64,
@@ -681,7 +695,7 @@ impl PatternDetector for MatHoldDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 4 {
             return None;
@@ -736,15 +750,15 @@ impl PatternDetector for MatHoldDetector {
         }
 
         // TA-Lib condition 5: first white (close >= open)
-        if first.close() < first.open() {
+        if ctx.color.is_black(first) {
             return None;
         }
         // TA-Lib condition 6: second black (close < open)
-        if second.close() >= second.open() {
+        if ctx.color.is_white(second) {
             return None;
         }
         // TA-Lib condition 7: fifth white (close >= open)
-        if fifth.close() < fifth.open() {
+        if ctx.color.is_black(fifth) {
             return None;
         }
 
@@ -807,6 +821,7 @@ impl PatternDetector for MatHoldDetector {
             strength: 0.75,
             start_index: index - 4,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -816,10 +831,14 @@ impl PatternDetector for MatHoldDetector {
 // ============================================================
 
 /// CDLRISEFALL3METHODS - Rising/Falling Three Methods (5-bar pattern)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RiseFallThreeMethodsDetector {
     pub body_long_factor: f64,
     pub body_short_factor: f64,
+    /// Emit `RISING_THREE_METHODS` / `FALLING_THREE_METHODS` instead of the
+    /// single `CDL_RISEFALL3METHODS` id
+    #[serde(default)]
+    pub split_directional_ids: bool,
 }
 
 impl Default for RiseFallThreeMethodsDetector {
@@ -827,6 +846,7 @@ impl Default for RiseFallThreeMethodsDetector {
         Self {
             body_long_factor: super::helpers::BODY_LONG_FACTOR,
             body_short_factor: super::helpers::BODY_SHORT_FACTOR,
+            split_directional_ids: false,
         }
     }
 }
@@ -844,7 +864,7 @@ impl PatternDetector for RiseFallThreeMethodsDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 4 {
             return None;
@@ -863,31 +883,11 @@ impl PatternDetector for RiseFallThreeMethodsDetector {
         let avg_body_fifth = super::helpers::trailing_avg_body(bars, index, 10);
 
         // TA-Lib: TA_CANDLECOLOR convention (close >= open = white/+1, close < open = black/-1)
-        let color_first = if first.close() >= first.open() {
-            1_i32
-        } else {
-            -1
-        };
-        let color_second = if second.close() >= second.open() {
-            1_i32
-        } else {
-            -1
-        };
-        let color_third = if third.close() >= third.open() {
-            1_i32
-        } else {
-            -1
-        };
-        let color_fourth = if fourth.close() >= fourth.open() {
-            1_i32
-        } else {
-            -1
-        };
-        let color_fifth = if fifth.close() >= fifth.open() {
-            1_i32
-        } else {
-            -1
-        };
+        let color_first = ctx.color.sign(first);
+        let color_second = ctx.color.sign(second);
+        let color_third = ctx.color.sign(third);
+        let color_fourth = ctx.color.sign(fourth);
+        let color_fifth = ctx.color.sign(fifth);
 
         // TA-Lib: first and fifth same color, middle three opposite
         if color_first != -color_second {
@@ -995,12 +995,19 @@ impl PatternDetector for RiseFallThreeMethodsDetector {
             Direction::Bearish
         };
 
+        let pattern_id = match (self.split_directional_ids, direction) {
+            (true, Direction::Bullish) => PatternId("RISING_THREE_METHODS"),
+            (true, _) => PatternId("FALLING_THREE_METHODS"),
+            (false, _) => PatternDetector::id(self),
+        };
+
         Some(PatternMatch {
-            pattern_id: PatternDetector::id(self),
+            pattern_id,
             direction,
             strength: 0.8,
             start_index: index - 4,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1010,7 +1017,7 @@ impl PatternDetector for RiseFallThreeMethodsDetector {
 // ============================================================
 
 /// CDLXSIDEGAP3METHODS - Up/Down-gap side-by-side white lines (4-bar pattern)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct XSideGapThreeMethodsDetector {
     pub tolerance: Ratio,
 }
@@ -1036,7 +1043,7 @@ impl PatternDetector for XSideGapThreeMethodsDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1046,9 +1053,9 @@ impl PatternDetector for XSideGapThreeMethodsDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: 1st and 2nd same color, 3rd opposite (TA_CANDLECOLOR: close >= open = white)
-        let first_bullish = first.close() >= first.open();
-        let second_bullish = second.close() >= second.open();
-        let third_bullish = third.close() >= third.open();
+        let first_bullish = ctx.color.is_white(first);
+        let second_bullish = ctx.color.is_white(second);
+        let third_bullish = ctx.color.is_white(third);
 
         if first_bullish != second_bullish {
             return None;
@@ -1090,6 +1097,7 @@ impl PatternDetector for XSideGapThreeMethodsDetector {
             strength: 0.65,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1106,6 +1114,23 @@ static CONCEALING_BABY_SWALLOW_PARAMS: &[ParamMeta] = &[ParamMeta {
     description: "Maximum shadow ratio",
 }];
 
+static LADDER_BOTTOM_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "shadow_veryshort_factor",
+        param_type: ParamType::Ratio,
+        default: 0.1,
+        range: (0.05, 0.2, 0.05),
+        description: "Fourth candle upper shadow threshold factor",
+    },
+    ParamMeta {
+        name: "close_tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.0,
+        range: (0.0, 0.2, 0.05),
+        description: "Allowed rise in the descending opens/closes, relative to the average range",
+    },
+];
+
 static XSIDE_GAP_THREE_METHODS_PARAMS: &[ParamMeta] = &[ParamMeta {
     name: "tolerance",
     param_type: ParamType::Ratio,
@@ -1130,6 +1155,28 @@ impl ParameterizedDetector for ConcealingBabySwallowDetector {
     }
 }
 
+impl ParameterizedDetector for LadderBottomDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        LADDER_BOTTOM_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            shadow_veryshort_factor: get_ratio(
+                params,
+                "shadow_veryshort_factor",
+                super::helpers::SHADOW_VERYSHORT_FACTOR,
+            )?
+            .get(),
+            close_tolerance: get_ratio(params, "close_tolerance", 0.0)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "CDL_LADDERBOTTOM"
+    }
+}
+
 impl ParameterizedDetector for XSideGapThreeMethodsDetector {
     fn param_meta() -> &'static [ParamMeta] {
         XSIDE_GAP_THREE_METHODS_PARAMS
@@ -9,11 +9,17 @@
 
 #![allow(clippy::collapsible_if, clippy::collapsible_else_if)]
 
+use std::collections::HashMap;
+
 use super::helpers::{
     self, is_body_long_f, is_body_short_f, is_doji_f, is_shadow_long, is_shadow_short,
     is_shadow_very_short_f, is_shadow_verylong_f, shadow_exceeds_veryshort,
 };
-use crate::{Direction, MarketContext, OHLCVExt, PatternDetector, PatternId, PatternMatch, OHLCV};
+use crate::{
+    params::{get_flag, get_positive, get_ratio, ParamMeta, ParamType, ParameterizedDetector},
+    Direction, MarketContext, OHLCVExt, PatternDetector, PatternId, PatternMatch, Ratio, Result,
+    OHLCV,
+};
 
 mod talib {
     pub use super::super::helpers::DOJI_RATIO;
@@ -44,19 +50,58 @@ impl_with_defaults!(
 // ============================================================
 
 /// CDLDOJI - Doji (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct DojiDetector {
     pub doji_factor: f64,
+    /// Skip bars that also match a more specific doji (long-legged,
+    /// dragonfly, gravestone, rickshaw man) in its default configuration,
+    /// so only the specific id is reported. Off by default (TA-Lib reports
+    /// both).
+    #[serde(default)]
+    pub suppress_generic: bool,
 }
 
 impl Default for DojiDetector {
     fn default() -> Self {
         Self {
             doji_factor: helpers::DOJI_FACTOR,
+            suppress_generic: false,
         }
     }
 }
 
+impl DojiDetector {
+    fn specific_variant_matches<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> bool {
+        let doji_factor = self.doji_factor;
+        LongLeggedDojiDetector { doji_factor }
+            .detect(bars, index, ctx)
+            .is_some()
+            || DragonflyDojiDetector {
+                doji_factor,
+                ..Default::default()
+            }
+            .detect(bars, index, ctx)
+            .is_some()
+            || GravestoneDojiDetector {
+                doji_factor,
+                ..Default::default()
+            }
+            .detect(bars, index, ctx)
+            .is_some()
+            || RickshawManDetector {
+                doji_factor,
+                ..Default::default()
+            }
+            .detect(bars, index, ctx)
+            .is_some()
+    }
+}
+
 impl PatternDetector for DojiDetector {
     fn id(&self) -> PatternId {
         PatternId("CDL_DOJI")
@@ -79,6 +124,9 @@ impl PatternDetector for DojiDetector {
         if !is_doji_f(body, ctx.avg_range, range, self.doji_factor) {
             return None;
         }
+        if self.suppress_generic && self.specific_variant_matches(bars, index, ctx) {
+            return None;
+        }
 
         let strength = if range > 0.0 {
             1.0 - (body / range / talib::DOJI_RATIO).min(1.0)
@@ -89,20 +137,35 @@ impl PatternDetector for DojiDetector {
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction: Direction::Neutral,
-            strength: 0.5 + strength * 0.5,
+            strength: helpers::clamp_strength(0.5 + strength * 0.5),
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLDRAGONFLYDOJI - Dragonfly Doji (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct DragonflyDojiDetector {
     pub doji_factor: f64,
     pub shadow_veryshort_factor: f64,
 }
 
+/// Strength for a doji whose signal comes from one long shadow.
+/// Grades the shadow against the average range (or the bar's own range when
+/// no average is available): 0.5 for a marginal tail, 1.0 once the tail is as
+/// long as an average candle.
+#[inline]
+fn dominant_shadow_strength(shadow: f64, avg_range: f64, range: f64) -> f64 {
+    let reference = if avg_range > 0.0 { avg_range } else { range };
+    if reference > 0.0 {
+        0.5 + (shadow / reference).min(1.0) * 0.5
+    } else {
+        0.5
+    }
+}
+
 impl Default for DragonflyDojiDetector {
     fn default() -> Self {
         Self {
@@ -149,15 +212,20 @@ impl PatternDetector for DragonflyDojiDetector {
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction: Direction::Bullish,
-            strength: 0.7,
+            strength: helpers::clamp_strength(dominant_shadow_strength(
+                lower,
+                ctx.avg_range,
+                range,
+            )),
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLGRAVESTONEDOJI - Gravestone Doji (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct GravestoneDojiDetector {
     pub doji_factor: f64,
     pub shadow_veryshort_factor: f64,
@@ -208,15 +276,20 @@ impl PatternDetector for GravestoneDojiDetector {
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction: Direction::Neutral,
-            strength: 0.7,
+            strength: helpers::clamp_strength(dominant_shadow_strength(
+                upper,
+                ctx.avg_range,
+                range,
+            )),
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLLONGLEGGEDDOJI - Long Legged Doji (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct LongLeggedDojiDetector {
     pub doji_factor: f64,
 }
@@ -264,22 +337,36 @@ impl PatternDetector for LongLeggedDojiDetector {
             strength: 0.6,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLRICKSHAWMAN - Rickshaw Man (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct RickshawManDetector {
     pub doji_factor: f64,
-    pub near_factor: f64,
+    /// How far the body may sit from the range midpoint, as a fraction of the
+    /// trailing 5-bar average range (TA-Lib Near factor)
+    ///
+    /// Serialized configs using the former `near_factor` key still load.
+    #[serde(alias = "near_factor")]
+    pub center_tolerance: Ratio,
+}
+
+impl RickshawManDetector {
+    /// Former name of [`center_tolerance`](Self::center_tolerance)
+    #[deprecated(note = "use the `center_tolerance` field")]
+    pub fn near_factor(&self) -> f64 {
+        self.center_tolerance.get()
+    }
 }
 
 impl Default for RickshawManDetector {
     fn default() -> Self {
         Self {
             doji_factor: helpers::DOJI_FACTOR,
-            near_factor: helpers::NEAR_FACTOR,
+            center_tolerance: Ratio::new_const(helpers::NEAR_FACTOR),
         }
     }
 }
@@ -326,7 +413,8 @@ impl PatternDetector for RickshawManDetector {
         // TA-Lib: body must be near the midpoint of the range
         // Near: HighLow, Period=5, Factor=0.2 (per-candle trailing at i)
         let midpoint = bar.low() + range / 2.0;
-        let near_threshold = helpers::trailing_avg_range(bars, index, 5) * self.near_factor;
+        let near_threshold =
+            helpers::trailing_avg_range(bars, index, 5) * self.center_tolerance.get();
         let body_low = bar.open().min(bar.close());
         let body_high = bar.open().max(bar.close());
         if body_low > midpoint + near_threshold || body_high < midpoint - near_threshold {
@@ -339,6 +427,7 @@ impl PatternDetector for RickshawManDetector {
             strength: 0.6,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -348,11 +437,19 @@ impl PatternDetector for RickshawManDetector {
 // ============================================================
 
 /// CDLHAMMER - Hammer (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+///
+/// Same shape as [`HangingManDetector`] (see [`helpers::is_hammer_shape`]); a
+/// hammer is the bullish reading after a decline. With `require_trend` set
+/// (the default) it only fires when `ctx.trend` is down, so Hammer and Hanging
+/// Man never fire on the same bar. Disable it for strict TA-Lib output.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct HammerDetector {
     pub body_short_factor: f64,
     pub shadow_veryshort_factor: f64,
     pub near_factor: f64,
+    /// Require a downtrend (`ctx.trend.is_down()`)
+    #[serde(default = "crate::default_true")]
+    pub require_trend: bool,
 }
 
 impl Default for HammerDetector {
@@ -361,6 +458,7 @@ impl Default for HammerDetector {
             body_short_factor: helpers::BODY_SHORT_FACTOR,
             shadow_veryshort_factor: helpers::SHADOW_VERYSHORT_FACTOR,
             near_factor: helpers::NEAR_FACTOR,
+            require_trend: true,
         }
     }
 }
@@ -383,24 +481,20 @@ impl PatternDetector for HammerDetector {
         if index < 1 {
             return None;
         }
+        if self.require_trend && !ctx.trend.is_down() {
+            return None;
+        }
         let bar = bars.get(index)?;
         let prev = bars.get(index - 1)?;
 
-        let body = bar.body();
-        let upper = bar.upper_shadow();
-        let lower = bar.lower_shadow();
-        let range = bar.range();
-
-        // TA-Lib: BodyShort — small real body
-        if !is_body_short_f(body, ctx.avg_body, range, self.body_short_factor) {
-            return None;
-        }
-        // TA-Lib: ShadowLong — long lower shadow (shadow > body)
-        if !is_shadow_long(lower, body, range) {
-            return None;
-        }
-        // TA-Lib: ShadowVeryShort — very short upper shadow
-        if !is_shadow_very_short_f(upper, ctx.avg_range, range, self.shadow_veryshort_factor) {
+        // TA-Lib: BodyShort, ShadowLong lower, ShadowVeryShort upper
+        if !helpers::is_hammer_shape(
+            bar,
+            ctx.avg_body,
+            ctx.avg_range,
+            self.body_short_factor,
+            self.shadow_veryshort_factor,
+        ) {
             return None;
         }
         // TA-Lib: position check — body at or below prior candle's low
@@ -418,16 +512,24 @@ impl PatternDetector for HammerDetector {
             strength: 0.6,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLHANGINGMAN - Hanging Man (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+///
+/// Same shape as [`HammerDetector`]; a hanging man is the bearish reading after
+/// an advance. With `require_trend` set (the default) it only fires when
+/// `ctx.trend` is up. Disable it for strict TA-Lib output.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct HangingManDetector {
     pub body_short_factor: f64,
     pub shadow_veryshort_factor: f64,
     pub near_factor: f64,
+    /// Require an uptrend (`ctx.trend.is_up()`)
+    #[serde(default = "crate::default_true")]
+    pub require_trend: bool,
 }
 
 impl Default for HangingManDetector {
@@ -436,6 +538,7 @@ impl Default for HangingManDetector {
             body_short_factor: helpers::BODY_SHORT_FACTOR,
             shadow_veryshort_factor: helpers::SHADOW_VERYSHORT_FACTOR,
             near_factor: helpers::NEAR_FACTOR,
+            require_trend: true,
         }
     }
 }
@@ -453,31 +556,28 @@ impl PatternDetector for HangingManDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
         }
+        if self.require_trend && !ctx.trend.is_up() {
+            return None;
+        }
         let bar = bars.get(index)?;
         let prev = bars.get(index - 1)?;
 
-        let body = bar.body();
-        let upper = bar.upper_shadow();
-        let lower = bar.lower_shadow();
-        let range = bar.range();
-
-        // TA-Lib: BodyShort — small real body (per-candle trailing avg at i)
+        // TA-Lib: BodyShort, ShadowLong lower, ShadowVeryShort upper
+        // (per-candle trailing avg at i)
         let avg_body = helpers::trailing_avg_body(bars, index, 10);
-        if !is_body_short_f(body, avg_body, range, self.body_short_factor) {
-            return None;
-        }
-        // TA-Lib: ShadowLong — long lower shadow (shadow > body)
-        if !is_shadow_long(lower, body, range) {
-            return None;
-        }
-        // TA-Lib: ShadowVeryShort — very short upper shadow (per-candle trailing avg at i)
         let avg_range = helpers::trailing_avg_range(bars, index, 10);
-        if !is_shadow_very_short_f(upper, avg_range, range, self.shadow_veryshort_factor) {
+        if !helpers::is_hammer_shape(
+            bar,
+            avg_body,
+            avg_range,
+            self.body_short_factor,
+            self.shadow_veryshort_factor,
+        ) {
             return None;
         }
         // TA-Lib: position check — body at or above prior candle's high
@@ -495,15 +595,19 @@ impl PatternDetector for HangingManDetector {
             strength: 0.6,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLINVERTEDHAMMER - Inverted Hammer (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct InvertedHammerDetector {
     pub body_short_factor: f64,
     pub shadow_veryshort_factor: f64,
+    /// Require a downtrend (`ctx.trend.is_down()`)
+    #[serde(default = "crate::default_true")]
+    pub require_trend: bool,
 }
 
 impl Default for InvertedHammerDetector {
@@ -511,6 +615,7 @@ impl Default for InvertedHammerDetector {
         Self {
             body_short_factor: helpers::BODY_SHORT_FACTOR,
             shadow_veryshort_factor: helpers::SHADOW_VERYSHORT_FACTOR,
+            require_trend: true,
         }
     }
 }
@@ -528,11 +633,14 @@ impl PatternDetector for InvertedHammerDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
         }
+        if self.require_trend && !ctx.trend.is_down() {
+            return None;
+        }
         let bar = bars.get(index)?;
         let prev = bars.get(index - 1)?;
 
@@ -570,15 +678,25 @@ impl PatternDetector for InvertedHammerDetector {
             strength: 0.7,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLSHOOTINGSTAR - Shooting Star (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct ShootingStarDetector {
     pub body_short_factor: f64,
     pub shadow_veryshort_factor: f64,
+    /// Minimum upper shadow as a multiple of the body (TA-Lib ShadowLong: 1.0)
+    #[serde(default = "crate::default_one")]
+    pub shadow_ratio: f64,
+    /// Maximum body as a fraction of the range (1.0 = no limit, as in TA-Lib)
+    #[serde(default = "crate::default_one")]
+    pub body_pct: f64,
+    /// Require an uptrend (`ctx.trend.is_up()`)
+    #[serde(default = "crate::default_true")]
+    pub require_trend: bool,
 }
 
 impl Default for ShootingStarDetector {
@@ -586,6 +704,9 @@ impl Default for ShootingStarDetector {
         Self {
             body_short_factor: helpers::BODY_SHORT_FACTOR,
             shadow_veryshort_factor: helpers::SHADOW_VERYSHORT_FACTOR,
+            shadow_ratio: 1.0,
+            body_pct: 1.0,
+            require_trend: true,
         }
     }
 }
@@ -603,11 +724,14 @@ impl PatternDetector for ShootingStarDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
         }
+        if self.require_trend && !ctx.trend.is_up() {
+            return None;
+        }
         let bar = bars.get(index)?;
         let prev = bars.get(index - 1)?;
 
@@ -629,8 +753,11 @@ impl PatternDetector for ShootingStarDetector {
         if !is_body_short_f(body, avg_body, range, self.body_short_factor) {
             return None;
         }
+        if range > 0.0 && body / range > self.body_pct {
+            return None;
+        }
         // TA-Lib: ShadowLong — upper shadow > avg(ShadowLong) which is body * 1.0 (Period=0)
-        if !is_shadow_long(upper, body, range) {
+        if upper <= body * self.shadow_ratio {
             return None;
         }
         // TA-Lib: ShadowVeryShort — very short lower shadow (per-candle trailing avg at i)
@@ -645,12 +772,13 @@ impl PatternDetector for ShootingStarDetector {
             strength: 0.7,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLTAKURI - Takuri (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct TakuriDetector {
     pub doji_factor: f64,
     pub shadow_verylong_factor: f64,
@@ -709,6 +837,7 @@ impl PatternDetector for TakuriDetector {
             strength: 0.8,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -718,7 +847,7 @@ impl PatternDetector for TakuriDetector {
 // ============================================================
 
 /// CDLMARUBOZU - Marubozu (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct MarubozuDetector {
     pub body_long_factor: f64,
     pub shadow_veryshort_factor: f64,
@@ -766,10 +895,10 @@ impl PatternDetector for MarubozuDetector {
         }
 
         // TA-Lib: TA_CANDLECOLOR: close >= open → bullish(+1), close < open → bearish(-1)
-        let direction = if bar.close() >= bar.open() {
-            Direction::Bullish
-        } else {
-            Direction::Bearish
+        let direction = match ctx.color.sign(bar) {
+            1 => Direction::Bullish,
+            -1 => Direction::Bearish,
+            _ => Direction::Neutral,
         };
 
         Some(PatternMatch {
@@ -778,12 +907,13 @@ impl PatternDetector for MarubozuDetector {
             strength: 0.8,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLCLOSINGMARUBOZU - Closing Marubozu (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct ClosingMarubozuDetector {
     pub body_long_factor: f64,
     pub shadow_veryshort_factor: f64,
@@ -824,7 +954,7 @@ impl PatternDetector for ClosingMarubozuDetector {
         }
 
         // TA-Lib: close-side shadow must be ShadowVeryShort (< avg_range * 0.1)
-        let (direction, valid) = if bar.is_bullish() {
+        let (direction, valid) = if ctx.color.is_white(bar) {
             (
                 Direction::Bullish,
                 is_shadow_very_short_f(upper, ctx.avg_range, range, self.shadow_veryshort_factor),
@@ -846,6 +976,7 @@ impl PatternDetector for ClosingMarubozuDetector {
             strength: 0.7,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -855,7 +986,7 @@ impl PatternDetector for ClosingMarubozuDetector {
 // ============================================================
 
 /// CDLLONGLINE - Long Line Candle (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct LongLineDetector {
     pub body_long_factor: f64,
 }
@@ -901,10 +1032,10 @@ impl PatternDetector for LongLineDetector {
         }
 
         // TA-Lib: TA_CANDLECOLOR: close >= open → bullish(+1), close < open → bearish(-1)
-        let direction = if bar.close() >= bar.open() {
-            Direction::Bullish
-        } else {
-            Direction::Bearish
+        let direction = match ctx.color.sign(bar) {
+            1 => Direction::Bullish,
+            -1 => Direction::Bearish,
+            _ => Direction::Neutral,
         };
 
         Some(PatternMatch {
@@ -913,12 +1044,13 @@ impl PatternDetector for LongLineDetector {
             strength: 0.6,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLSHORTLINE - Short Line Candle (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct ShortLineDetector {
     pub body_short_factor: f64,
 }
@@ -944,7 +1076,7 @@ impl PatternDetector for ShortLineDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
         let body = bar.body();
@@ -967,10 +1099,10 @@ impl PatternDetector for ShortLineDetector {
         }
 
         // TA-Lib: TA_CANDLECOLOR: close >= open → bullish(+1), close < open → bearish(-1)
-        let direction = if bar.close() >= bar.open() {
-            Direction::Bullish
-        } else {
-            Direction::Bearish
+        let direction = match ctx.color.sign(bar) {
+            1 => Direction::Bullish,
+            -1 => Direction::Bearish,
+            _ => Direction::Neutral,
         };
 
         Some(PatternMatch {
@@ -979,6 +1111,7 @@ impl PatternDetector for ShortLineDetector {
             strength: 0.5,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -988,7 +1121,7 @@ impl PatternDetector for ShortLineDetector {
 // ============================================================
 
 /// CDLSPINNINGTOP - Spinning Top (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct SpinningTopDetector {
     pub body_short_factor: f64,
 }
@@ -1014,7 +1147,7 @@ impl PatternDetector for SpinningTopDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
         let body = bar.body();
@@ -1037,10 +1170,10 @@ impl PatternDetector for SpinningTopDetector {
         }
 
         // TA-Lib: TA_CANDLECOLOR: close >= open → bullish(+1), close < open → bearish(-1)
-        let direction = if bar.close() >= bar.open() {
-            Direction::Bullish
-        } else {
-            Direction::Bearish
+        let direction = match ctx.color.sign(bar) {
+            1 => Direction::Bullish,
+            -1 => Direction::Bearish,
+            _ => Direction::Neutral,
         };
 
         Some(PatternMatch {
@@ -1049,12 +1182,13 @@ impl PatternDetector for SpinningTopDetector {
             strength: 0.5,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLHIGHWAVE - High-Wave Candle (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct HighWaveDetector {
     pub body_short_factor: f64,
     pub shadow_verylong_factor: f64,
@@ -1082,7 +1216,7 @@ impl PatternDetector for HighWaveDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         let bar = bars.get(index)?;
         let body = bar.body();
@@ -1104,10 +1238,10 @@ impl PatternDetector for HighWaveDetector {
         }
 
         // TA-Lib: TA_CANDLECOLOR: close >= open → bullish(+1), close < open → bearish(-1)
-        let direction = if bar.close() >= bar.open() {
-            Direction::Bullish
-        } else {
-            Direction::Bearish
+        let direction = match ctx.color.sign(bar) {
+            1 => Direction::Bullish,
+            -1 => Direction::Bearish,
+            _ => Direction::Neutral,
         };
 
         Some(PatternMatch {
@@ -1116,6 +1250,7 @@ impl PatternDetector for HighWaveDetector {
             strength: 0.6,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1125,7 +1260,7 @@ impl PatternDetector for HighWaveDetector {
 // ============================================================
 
 /// CDLBELTHOLD - Belt Hold (TA-Lib compatible)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct BeltHoldDetector {
     pub body_long_factor: f64,
     pub shadow_veryshort_factor: f64,
@@ -1166,12 +1301,12 @@ impl PatternDetector for BeltHoldDetector {
         }
 
         // TA-Lib: open-side shadow must be ShadowVeryShort (< avg_range * 0.1)
-        let (direction, valid) = if bar.is_bullish() {
+        let (direction, valid) = if ctx.color.is_white(bar) {
             (
                 Direction::Bullish,
                 is_shadow_very_short_f(lower, ctx.avg_range, range, self.shadow_veryshort_factor),
             )
-        } else if bar.is_bearish() {
+        } else if ctx.color.is_black(bar) {
             (
                 Direction::Bearish,
                 is_shadow_very_short_f(upper, ctx.avg_range, range, self.shadow_veryshort_factor),
@@ -1190,6 +1325,114 @@ impl PatternDetector for BeltHoldDetector {
             strength: 0.7,
             start_index: index,
             end_index: index,
+            metric: None,
         })
     }
 }
+
+// ============================================================
+// PARAMETERIZED DETECTOR IMPLEMENTATIONS
+// ============================================================
+
+static SHOOTING_STAR_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "body_short_factor",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (0.5, 1.5, 0.1),
+        description: "Body short threshold factor",
+    },
+    ParamMeta {
+        name: "shadow_veryshort_factor",
+        param_type: ParamType::Ratio,
+        default: 0.1,
+        range: (0.05, 0.2, 0.05),
+        description: "Shadow very short threshold factor",
+    },
+    ParamMeta {
+        name: "shadow_ratio",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (1.0, 3.0, 0.5),
+        description: "Minimum upper shadow to body ratio",
+    },
+    ParamMeta {
+        name: "body_pct",
+        param_type: ParamType::Ratio,
+        default: 1.0,
+        range: (0.2, 1.0, 0.1),
+        description: "Maximum body ratio",
+    },
+    ParamMeta::flag("require_trend", true, "Require an uptrend"),
+];
+
+impl ParameterizedDetector for ShootingStarDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        SHOOTING_STAR_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            body_short_factor: get_positive(
+                params,
+                "body_short_factor",
+                helpers::BODY_SHORT_FACTOR,
+            )?,
+            shadow_veryshort_factor: get_ratio(
+                params,
+                "shadow_veryshort_factor",
+                helpers::SHADOW_VERYSHORT_FACTOR,
+            )?
+            .get(),
+            shadow_ratio: get_positive(params, "shadow_ratio", 1.0)?,
+            body_pct: get_ratio(params, "body_pct", 1.0)?.get(),
+            require_trend: get_flag(params, "require_trend", true)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "CDL_SHOOTINGSTAR"
+    }
+}
+
+static RICKSHAW_MAN_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "doji_factor",
+        param_type: ParamType::Ratio,
+        default: 0.1,
+        range: (0.05, 0.2, 0.05),
+        description: "Doji body threshold factor",
+    },
+    ParamMeta {
+        name: "center_tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.2,
+        range: (0.1, 0.6, 0.1),
+        description: "Maximum body distance from range midpoint",
+    },
+];
+
+impl ParameterizedDetector for RickshawManDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        RICKSHAW_MAN_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            doji_factor: get_ratio(params, "doji_factor", helpers::DOJI_FACTOR)?.get(),
+            // `near_factor` is the pre-`center_tolerance` key
+            center_tolerance: get_ratio(
+                params,
+                "center_tolerance",
+                params
+                    .get("near_factor")
+                    .copied()
+                    .unwrap_or(helpers::NEAR_FACTOR),
+            )?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "CDL_RICKSHAWMAN"
+    }
+}
@@ -13,9 +13,9 @@ use super::helpers::{
     self, is_body_long_f, is_body_short_f, is_doji_f, is_shadow_very_short, is_shadow_very_short_f,
 };
 use crate::{
-    params::{get_ratio, ParamMeta, ParamType, ParameterizedDetector},
-    Direction, MarketContext, OHLCVExt, PatternDetector, PatternId, PatternMatch, Ratio, Result,
-    OHLCV,
+    params::{get_positive, get_ratio, ParamMeta, ParamType, ParameterizedDetector},
+    Direction, MarketContext, OHLCVExt, PatternDetector, PatternError, PatternId, PatternMatch,
+    Ratio, Result, OHLCV,
 };
 
 impl_with_defaults!(
@@ -48,7 +48,7 @@ impl_with_defaults!(
 // ============================================================
 
 /// CDL3WHITESOLDIERS - Three White Soldiers (TA-Lib compatible)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ThreeWhiteSoldiersDetector {
     pub shadow_veryshort_factor: f64,
     pub near_factor: f64,
@@ -67,6 +67,35 @@ impl Default for ThreeWhiteSoldiersDetector {
     }
 }
 
+impl ThreeWhiteSoldiersDetector {
+    /// Set the very-short upper shadow factor (must be positive)
+    pub fn with_shadow_factor(mut self, factor: f64) -> Result<Self> {
+        self.shadow_veryshort_factor = positive_factor(factor, "shadow factor must be positive")?;
+        Ok(self)
+    }
+
+    /// Set the factor for opens near the previous close (must be positive)
+    pub fn with_near_factor(mut self, factor: f64) -> Result<Self> {
+        self.near_factor = positive_factor(factor, "near factor must be positive")?;
+        Ok(self)
+    }
+
+    /// Set the factor for bodies shrinking too far (must be positive)
+    pub fn with_far_factor(mut self, factor: f64) -> Result<Self> {
+        self.far_factor = positive_factor(factor, "far factor must be positive")?;
+        Ok(self)
+    }
+}
+
+/// Accept finite factors greater than zero
+fn positive_factor(factor: f64, msg: &'static str) -> Result<f64> {
+    if factor.is_finite() && factor > 0.0 {
+        Ok(factor)
+    } else {
+        Err(PatternError::InvalidValue(msg))
+    }
+}
+
 impl PatternDetector for ThreeWhiteSoldiersDetector {
     fn id(&self) -> PatternId {
         PatternId("CDL_3WHITESOLDIERS")
@@ -80,7 +109,7 @@ impl PatternDetector for ThreeWhiteSoldiersDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -90,7 +119,7 @@ impl PatternDetector for ThreeWhiteSoldiersDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: All three must be bullish
-        if !first.is_bullish() || !second.is_bullish() || !third.is_bullish() {
+        if !ctx.color.is_white(first) || !ctx.color.is_white(second) || !ctx.color.is_white(third) {
             return None;
         }
 
@@ -154,12 +183,13 @@ impl PatternDetector for ThreeWhiteSoldiersDetector {
             strength: 0.8,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDL3BLACKCROWS - Three Black Crows (TA-Lib compatible)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ThreeBlackCrowsDetector {
     pub shadow_veryshort_factor: f64,
 }
@@ -185,7 +215,7 @@ impl PatternDetector for ThreeBlackCrowsDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 3 {
             return None;
@@ -196,12 +226,12 @@ impl PatternDetector for ThreeBlackCrowsDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: Prior candle must be bullish (white)
-        if !prior.is_bullish() {
+        if !ctx.color.is_white(prior) {
             return None;
         }
 
         // TA-Lib: All three crows must be bearish
-        if !first.is_bearish() || !second.is_bearish() || !third.is_bearish() {
+        if !ctx.color.is_black(first) || !ctx.color.is_black(second) || !ctx.color.is_black(third) {
             return None;
         }
 
@@ -247,6 +277,7 @@ impl PatternDetector for ThreeBlackCrowsDetector {
             strength: 0.8,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -256,7 +287,7 @@ impl PatternDetector for ThreeBlackCrowsDetector {
 // ============================================================
 
 /// CDL3INSIDE - Three Inside Up/Down (TA-Lib compatible)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ThreeInsideDetector {
     pub body_long_factor: f64,
     pub body_short_factor: f64,
@@ -284,7 +315,7 @@ impl PatternDetector for ThreeInsideDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -330,8 +361,8 @@ impl PatternDetector for ThreeInsideDetector {
         }
 
         // TA-Lib: TA_CANDLECOLOR convention (close >= open = white/+1)
-        let first_white = first.close() >= first.open();
-        let third_white = third.close() >= third.open();
+        let first_white = ctx.color.is_white(first);
+        let third_white = ctx.color.is_white(third);
 
         // Three Inside Up: first black, third white, closes above first's open
         if !first_white && third_white && third.close() > first.open() {
@@ -341,6 +372,7 @@ impl PatternDetector for ThreeInsideDetector {
                 strength: 0.7,
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -352,6 +384,7 @@ impl PatternDetector for ThreeInsideDetector {
                 strength: 0.7,
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -360,7 +393,7 @@ impl PatternDetector for ThreeInsideDetector {
 }
 
 /// CDL3OUTSIDE - Three Outside Up/Down
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ThreeOutsideDetector;
 
 impl Default for ThreeOutsideDetector {
@@ -382,7 +415,7 @@ impl PatternDetector for ThreeOutsideDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -393,7 +426,7 @@ impl PatternDetector for ThreeOutsideDetector {
 
         // TA-Lib: TA_CANDLECOLOR: close >= open → white(+1), close < open → black(-1)
         // Three Outside Up: first black, second white, second strictly engulfs first, third closes above second
-        if second.close() >= second.open() && first.close() < first.open() {
+        if ctx.color.is_white(second) && ctx.color.is_black(first) {
             // TA-Lib: close[i-1] > open[i-2] && open[i-1] < close[i-2] (strict engulf)
             if second.close() > first.open() && second.open() < first.close() {
                 // TA-Lib: close[i] > close[i-1] (confirmation)
@@ -404,13 +437,14 @@ impl PatternDetector for ThreeOutsideDetector {
                         strength: 0.75,
                         start_index: index - 2,
                         end_index: index,
+                        metric: None,
                     });
                 }
             }
         }
 
         // Three Outside Down: first white, second black, second strictly engulfs first, third closes below second
-        if second.close() < second.open() && first.close() >= first.open() {
+        if ctx.color.is_black(second) && ctx.color.is_white(first) {
             // TA-Lib: open[i-1] > close[i-2] && close[i-1] < open[i-2] (strict engulf)
             if second.open() > first.close() && second.close() < first.open() {
                 // TA-Lib: close[i] < close[i-1] (confirmation)
@@ -421,6 +455,7 @@ impl PatternDetector for ThreeOutsideDetector {
                         strength: 0.75,
                         start_index: index - 2,
                         end_index: index,
+                        metric: None,
                     });
                 }
             }
@@ -435,7 +470,7 @@ impl PatternDetector for ThreeOutsideDetector {
 // ============================================================
 
 /// CDL3LINESTRIKE - Three-Line Strike
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ThreeLineStrikeDetector {
     pub near_factor: f64,
 }
@@ -461,7 +496,7 @@ impl PatternDetector for ThreeLineStrikeDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 3 {
             return None;
@@ -472,26 +507,10 @@ impl PatternDetector for ThreeLineStrikeDetector {
         let fourth = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR convention (close >= open = white/+1)
-        let color_first = if first.close() >= first.open() {
-            1_i32
-        } else {
-            -1
-        };
-        let color_second = if second.close() >= second.open() {
-            1_i32
-        } else {
-            -1
-        };
-        let color_third = if third.close() >= third.open() {
-            1_i32
-        } else {
-            -1
-        };
-        let color_fourth = if fourth.close() >= fourth.open() {
-            1_i32
-        } else {
-            -1
-        };
+        let color_first = ctx.color.sign(first);
+        let color_second = ctx.color.sign(second);
+        let color_third = ctx.color.sign(third);
+        let color_fourth = ctx.color.sign(fourth);
 
         // TA-Lib: first three same color, fourth opposite
         if color_first != color_second || color_second != color_third {
@@ -533,6 +552,7 @@ impl PatternDetector for ThreeLineStrikeDetector {
                     strength: 0.7,
                     start_index: index - 3,
                     end_index: index,
+                    metric: None,
                 });
             }
         } else {
@@ -548,6 +568,7 @@ impl PatternDetector for ThreeLineStrikeDetector {
                     strength: 0.7,
                     start_index: index - 3,
                     end_index: index,
+                    metric: None,
                 });
             }
         }
@@ -561,7 +582,7 @@ impl PatternDetector for ThreeLineStrikeDetector {
 // ============================================================
 
 /// CDL3STARSINSOUTH - Three Stars In The South
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ThreeStarsInSouthDetector {
     pub body_long_factor: f64,
     pub body_short_factor: f64,
@@ -591,7 +612,7 @@ impl PatternDetector for ThreeStarsInSouthDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -601,7 +622,7 @@ impl PatternDetector for ThreeStarsInSouthDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: All three bearish (black)
-        if !first.is_bearish() || !second.is_bearish() || !third.is_bearish() {
+        if !ctx.color.is_black(first) || !ctx.color.is_black(second) || !ctx.color.is_black(third) {
             return None;
         }
 
@@ -700,6 +721,7 @@ impl PatternDetector for ThreeStarsInSouthDetector {
             strength: 0.65,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -709,11 +731,15 @@ impl PatternDetector for ThreeStarsInSouthDetector {
 // ============================================================
 
 /// CDLMORNINGSTAR - Morning Star (TA-Lib compatible)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MorningStarDetector {
     pub body_long_factor: f64,
     pub body_short_factor: f64,
     pub penetration: f64,
+    /// Real-body overlap between the first two bars still counted as a gap,
+    /// as a fraction of the average range; 0.0 requires a strict gap
+    #[serde(default)]
+    pub gap_tolerance: Ratio,
 }
 
 impl Default for MorningStarDetector {
@@ -722,6 +748,7 @@ impl Default for MorningStarDetector {
             body_long_factor: helpers::BODY_LONG_FACTOR,
             body_short_factor: helpers::BODY_SHORT_FACTOR,
             penetration: 0.3,
+            gap_tolerance: Ratio::default(),
         }
     }
 }
@@ -739,7 +766,7 @@ impl PatternDetector for MorningStarDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -749,11 +776,11 @@ impl PatternDetector for MorningStarDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR(i-2) == -1 (black/bearish: close < open)
-        if first.close() >= first.open() {
+        if ctx.color.is_white(first) {
             return None;
         }
         // TA-Lib: TA_CANDLECOLOR(i) == 1 (white/bullish: close >= open)
-        if third.close() < third.open() {
+        if ctx.color.is_black(third) {
             return None;
         }
 
@@ -785,7 +812,8 @@ impl PatternDetector for MorningStarDetector {
         // max(open[i-1], close[i-1]) < min(open[i-2], close[i-2])
         let second_body_top = second.open().max(second.close());
         let first_body_bottom = first.open().min(first.close());
-        if second_body_top >= first_body_bottom {
+        let slack = helpers::gap_slack(self.gap_tolerance, ctx.avg_range);
+        if second_body_top >= first_body_bottom + slack {
             return None;
         }
 
@@ -808,16 +836,21 @@ impl PatternDetector for MorningStarDetector {
             strength: 0.75,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLEVENINGSTAR - Evening Star (TA-Lib compatible)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct EveningStarDetector {
     pub body_long_factor: f64,
     pub body_short_factor: f64,
     pub penetration: f64,
+    /// Real-body overlap between the first two bars still counted as a gap,
+    /// as a fraction of the average range; 0.0 requires a strict gap
+    #[serde(default)]
+    pub gap_tolerance: Ratio,
 }
 
 impl Default for EveningStarDetector {
@@ -826,6 +859,7 @@ impl Default for EveningStarDetector {
             body_long_factor: helpers::BODY_LONG_FACTOR,
             body_short_factor: helpers::BODY_SHORT_FACTOR,
             penetration: 0.3,
+            gap_tolerance: Ratio::default(),
         }
     }
 }
@@ -843,7 +877,7 @@ impl PatternDetector for EveningStarDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -853,11 +887,11 @@ impl PatternDetector for EveningStarDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR(i-2) == 1 (white/bullish: close >= open)
-        if first.close() < first.open() {
+        if ctx.color.is_black(first) {
             return None;
         }
         // TA-Lib: TA_CANDLECOLOR(i) == -1 (black/bearish: close < open)
-        if third.close() >= third.open() {
+        if ctx.color.is_white(third) {
             return None;
         }
 
@@ -889,7 +923,8 @@ impl PatternDetector for EveningStarDetector {
         // min(open[i-1], close[i-1]) > max(open[i-2], close[i-2])
         let second_body_bottom = second.open().min(second.close());
         let first_body_top = first.open().max(first.close());
-        if second_body_bottom <= first_body_top {
+        let slack = helpers::gap_slack(self.gap_tolerance, ctx.avg_range);
+        if second_body_bottom <= first_body_top - slack {
             return None;
         }
 
@@ -912,12 +947,13 @@ impl PatternDetector for EveningStarDetector {
             strength: 0.75,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLMORNINGDOJISTAR - Morning Doji Star (TA-Lib compatible)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MorningDojiStarDetector {
     pub body_long_factor: f64,
     pub doji_factor: f64,
@@ -947,7 +983,7 @@ impl PatternDetector for MorningDojiStarDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -957,11 +993,11 @@ impl PatternDetector for MorningDojiStarDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR(i-2) == -1 (black: close < open)
-        if first.close() >= first.open() {
+        if ctx.color.is_white(first) {
             return None;
         }
         // TA-Lib: TA_CANDLECOLOR(i) == 1 (white: close >= open)
-        if third.close() < third.open() {
+        if ctx.color.is_black(third) {
             return None;
         }
 
@@ -1015,12 +1051,13 @@ impl PatternDetector for MorningDojiStarDetector {
             strength: 0.8,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLEVENINGDOJISTAR - Evening Doji Star (TA-Lib compatible)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct EveningDojiStarDetector {
     pub body_long_factor: f64,
     pub doji_factor: f64,
@@ -1050,7 +1087,7 @@ impl PatternDetector for EveningDojiStarDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1060,11 +1097,11 @@ impl PatternDetector for EveningDojiStarDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR(i-2) == 1 (white: close >= open)
-        if first.close() < first.open() {
+        if ctx.color.is_black(first) {
             return None;
         }
         // TA-Lib: TA_CANDLECOLOR(i) == -1 (black: close < open)
-        if third.close() >= third.open() {
+        if ctx.color.is_white(third) {
             return None;
         }
 
@@ -1118,6 +1155,7 @@ impl PatternDetector for EveningDojiStarDetector {
             strength: 0.8,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1127,7 +1165,7 @@ impl PatternDetector for EveningDojiStarDetector {
 // ============================================================
 
 /// CDLABANDONEDBABY - Abandoned Baby (TA-Lib compatible)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AbandonedBabyDetector {
     pub body_long_factor: f64,
     pub doji_factor: f64,
@@ -1157,7 +1195,7 @@ impl PatternDetector for AbandonedBabyDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1200,7 +1238,7 @@ impl PatternDetector for AbandonedBabyDetector {
         let penetration = self.penetration; // TA-Lib default optInPenetration
 
         // Bearish Abandoned Baby: first white, third black
-        if first.close() >= first.open() && third.close() < third.open() {
+        if ctx.color.is_white(first) && ctx.color.is_black(third) {
             // TA-Lib: close[i] < close[i-2] - body[i-2] * penetration
             if third.close() >= first.close() - first_body * penetration {
                 return None;
@@ -1220,11 +1258,12 @@ impl PatternDetector for AbandonedBabyDetector {
                 strength: 0.85,
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
         // Bullish Abandoned Baby: first black, third white
-        if first.close() < first.open() && third.close() >= third.open() {
+        if ctx.color.is_black(first) && ctx.color.is_white(third) {
             // TA-Lib: close[i] > close[i-2] + body[i-2] * penetration
             if third.close() <= first.close() + first_body * penetration {
                 return None;
@@ -1244,6 +1283,7 @@ impl PatternDetector for AbandonedBabyDetector {
                 strength: 0.85,
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1256,7 +1296,7 @@ impl PatternDetector for AbandonedBabyDetector {
 // ============================================================
 
 /// CDL2CROWS - Two Crows
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TwoCrowsDetector {
     pub body_long_factor: f64,
 }
@@ -1282,7 +1322,7 @@ impl PatternDetector for TwoCrowsDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1292,7 +1332,7 @@ impl PatternDetector for TwoCrowsDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR(i-2) == 1 (white: close >= open)
-        if first.close() < first.open() {
+        if ctx.color.is_black(first) {
             return None;
         }
         // TA-Lib: first BodyLong (per-candle trailing at i-2)
@@ -1306,7 +1346,7 @@ impl PatternDetector for TwoCrowsDetector {
             return None;
         }
         // TA-Lib: TA_CANDLECOLOR(i-1) == -1 (black: close < open)
-        if second.close() >= second.open() {
+        if ctx.color.is_white(second) {
             return None;
         }
         // TA-Lib: TA_REALBODYGAPUP(i-1, i-2)
@@ -1317,7 +1357,7 @@ impl PatternDetector for TwoCrowsDetector {
             return None;
         }
         // TA-Lib: TA_CANDLECOLOR(i) == -1 (black: close < open)
-        if third.close() >= third.open() {
+        if ctx.color.is_white(third) {
             return None;
         }
         // TA-Lib: open[i] < open[i-1]
@@ -1343,12 +1383,13 @@ impl PatternDetector for TwoCrowsDetector {
             strength: 0.7,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLUPSIDEGAP2CROWS - Upside Gap Two Crows
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct UpsideGapTwoCrowsDetector {
     pub body_long_factor: f64,
 }
@@ -1374,7 +1415,7 @@ impl PatternDetector for UpsideGapTwoCrowsDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1384,7 +1425,7 @@ impl PatternDetector for UpsideGapTwoCrowsDetector {
         let third = bars.get(index)?;
 
         // First long bullish (TA-Lib: BodyLong at i-2, per-candle trailing)
-        if !first.is_bullish() {
+        if !ctx.color.is_white(first) {
             return None;
         }
         let first_body = first.body();
@@ -1400,7 +1441,7 @@ impl PatternDetector for UpsideGapTwoCrowsDetector {
         }
 
         // Second and third bearish
-        if !second.is_bearish() || !third.is_bearish() {
+        if !ctx.color.is_black(second) || !ctx.color.is_black(third) {
             return None;
         }
 
@@ -1426,6 +1467,7 @@ impl PatternDetector for UpsideGapTwoCrowsDetector {
             strength: 0.65,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1435,7 +1477,7 @@ impl PatternDetector for UpsideGapTwoCrowsDetector {
 // ============================================================
 
 /// CDLIDENTICAL3CROWS - Identical Three Crows
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct IdenticalThreeCrowsDetector {
     pub tolerance: Ratio,
 }
@@ -1461,7 +1503,7 @@ impl PatternDetector for IdenticalThreeCrowsDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1471,7 +1513,7 @@ impl PatternDetector for IdenticalThreeCrowsDetector {
         let third = bars.get(index)?;
 
         // All three bearish
-        if !first.is_bearish() || !second.is_bearish() || !third.is_bearish() {
+        if !ctx.color.is_black(first) || !ctx.color.is_black(second) || !ctx.color.is_black(third) {
             return None;
         }
 
@@ -1510,6 +1552,7 @@ impl PatternDetector for IdenticalThreeCrowsDetector {
             strength: 0.8,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1519,7 +1562,7 @@ impl PatternDetector for IdenticalThreeCrowsDetector {
 // ============================================================
 
 /// CDLADVANCEBLOCK - Advance Block
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AdvanceBlockDetector {
     pub body_long_factor: f64,
     pub near_factor: f64,
@@ -1549,7 +1592,7 @@ impl PatternDetector for AdvanceBlockDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1559,7 +1602,7 @@ impl PatternDetector for AdvanceBlockDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: All three white (bullish)
-        if !first.is_bullish() || !second.is_bullish() || !third.is_bullish() {
+        if !ctx.color.is_white(first) || !ctx.color.is_white(second) || !ctx.color.is_white(third) {
             return None;
         }
 
@@ -1647,12 +1690,13 @@ impl PatternDetector for AdvanceBlockDetector {
             strength: 0.6,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLSTALLEDPATTERN - Stalled Pattern (Deliberation)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct StalledPatternDetector {
     pub body_long_factor: f64,
     pub body_short_factor: f64,
@@ -1684,7 +1728,7 @@ impl PatternDetector for StalledPatternDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1694,7 +1738,7 @@ impl PatternDetector for StalledPatternDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: All three white (bullish)
-        if !first.is_bullish() || !second.is_bullish() || !third.is_bullish() {
+        if !ctx.color.is_white(first) || !ctx.color.is_white(second) || !ctx.color.is_white(third) {
             return None;
         }
 
@@ -1774,6 +1818,7 @@ impl PatternDetector for StalledPatternDetector {
             strength: 0.55,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1783,7 +1828,7 @@ impl PatternDetector for StalledPatternDetector {
 // ============================================================
 
 /// CDLSTICKSANDWICH - Stick Sandwich
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct StickSandwichDetector {
     pub tolerance: Ratio,
 }
@@ -1809,7 +1854,7 @@ impl PatternDetector for StickSandwichDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1820,13 +1865,13 @@ impl PatternDetector for StickSandwichDetector {
 
         // TA-Lib StickSandwich (TA_CANDLECOLOR convention):
         // 1. first black (close < open), second white (close >= open), third black (close < open)
-        if first.close() >= first.open() {
+        if ctx.color.is_white(first) {
             return None;
         }
-        if second.close() < second.open() {
+        if ctx.color.is_black(second) {
             return None;
         }
-        if third.close() >= third.open() {
+        if ctx.color.is_white(third) {
             return None;
         }
 
@@ -1850,6 +1895,7 @@ impl PatternDetector for StickSandwichDetector {
             strength: 0.6,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1859,15 +1905,20 @@ impl PatternDetector for StickSandwichDetector {
 // ============================================================
 
 /// CDLTASUKIGAP - Tasuki Gap
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TasukiGapDetector {
     pub near_factor: f64,
+    /// Real-body overlap between the first two bars still counted as a gap,
+    /// as a fraction of the average range; 0.0 requires a strict gap
+    #[serde(default)]
+    pub gap_tolerance: Ratio,
 }
 
 impl Default for TasukiGapDetector {
     fn default() -> Self {
         Self {
             near_factor: helpers::NEAR_FACTOR,
+            gap_tolerance: Ratio::default(),
         }
     }
 }
@@ -1885,7 +1936,7 @@ impl PatternDetector for TasukiGapDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1896,17 +1947,18 @@ impl PatternDetector for TasukiGapDetector {
 
         // TA-Lib: Near threshold at i-1 for body size comparison
         let near_threshold = helpers::trailing_avg_range(bars, index - 1, 5) * self.near_factor;
+        let slack = helpers::gap_slack(self.gap_tolerance, ctx.avg_range);
 
         // TA-Lib: TA_CANDLECOLOR convention (close >= open = white/+1)
-        let second_white = second.close() >= second.open();
-        let third_white = third.close() >= third.open();
+        let second_white = ctx.color.is_white(second);
+        let third_white = ctx.color.is_white(third);
 
         // Upside Tasuki Gap: 2nd white, 3rd black
         if second_white && !third_white {
             // TA-Lib: RealBodyGapUp between i-1 and i-2
             let first_body_hi = first.open().max(first.close());
             let second_body_lo = second.open().min(second.close());
-            if second_body_lo <= first_body_hi {
+            if second_body_lo <= first_body_hi - slack {
                 return None;
             }
 
@@ -1921,7 +1973,7 @@ impl PatternDetector for TasukiGapDetector {
                 return None;
             }
             // TA-Lib: third close stays above max(close[i-2], open[i-2]) — gap not filled
-            if third.close() <= first_body_hi {
+            if third.close() <= first_body_hi - slack {
                 return None;
             }
 
@@ -1936,6 +1988,7 @@ impl PatternDetector for TasukiGapDetector {
                 strength: 0.6,
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1944,7 +1997,7 @@ impl PatternDetector for TasukiGapDetector {
             // TA-Lib: RealBodyGapDown between i-1 and i-2
             let first_body_lo = first.open().min(first.close());
             let second_body_hi = second.open().max(second.close());
-            if second_body_hi >= first_body_lo {
+            if second_body_hi >= first_body_lo + slack {
                 return None;
             }
 
@@ -1959,7 +2012,7 @@ impl PatternDetector for TasukiGapDetector {
                 return None;
             }
             // TA-Lib: third close stays below min(close[i-2], open[i-2]) — gap not filled
-            if third.close() >= first_body_lo {
+            if third.close() >= first_body_lo + slack {
                 return None;
             }
 
@@ -1974,6 +2027,7 @@ impl PatternDetector for TasukiGapDetector {
                 strength: 0.6,
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -1986,7 +2040,7 @@ impl PatternDetector for TasukiGapDetector {
 // ============================================================
 
 /// CDLTRISTAR - Tristar Pattern (TA-Lib compatible)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TristarDetector {
     pub doji_factor: f64,
 }
@@ -2056,6 +2110,7 @@ impl PatternDetector for TristarDetector {
                 strength: 0.7,
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -2067,6 +2122,7 @@ impl PatternDetector for TristarDetector {
                 strength: 0.7,
                 start_index: index - 2,
                 end_index: index,
+                metric: None,
             });
         }
 
@@ -2079,7 +2135,7 @@ impl PatternDetector for TristarDetector {
 // ============================================================
 
 /// CDLUNIQUE3RIVER - Unique 3 River
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Unique3RiverDetector {
     pub body_long_factor: f64,
     pub body_short_factor: f64,
@@ -2107,7 +2163,7 @@ impl PatternDetector for Unique3RiverDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -2117,7 +2173,7 @@ impl PatternDetector for Unique3RiverDetector {
         let third = bars.get(index)?;
 
         // TA-Lib condition 1: first BodyLong + black (close < open)
-        if first.close() >= first.open() {
+        if ctx.color.is_white(first) {
             return None;
         }
         let avg_body_first = helpers::trailing_avg_body(bars, index - 2, 10);
@@ -2131,7 +2187,7 @@ impl PatternDetector for Unique3RiverDetector {
         }
 
         // TA-Lib condition 2: second black (close < open)
-        if second.close() >= second.open() {
+        if ctx.color.is_white(second) {
             return None;
         }
         // TA-Lib condition 3: close[i-1] > close[i-2] (second close above first close)
@@ -2148,7 +2204,7 @@ impl PatternDetector for Unique3RiverDetector {
         }
 
         // TA-Lib condition 6: third BodyShort + white (close >= open)
-        if third.close() < third.open() {
+        if ctx.color.is_black(third) {
             return None;
         }
         let avg_body_third = helpers::trailing_avg_body(bars, index, 10);
@@ -2171,6 +2227,7 @@ impl PatternDetector for Unique3RiverDetector {
             strength: 0.6,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -2180,7 +2237,7 @@ impl PatternDetector for Unique3RiverDetector {
 // ============================================================
 
 /// Tweezer Top
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TweezerTopDetector {
     pub tolerance: Ratio,
 }
@@ -2217,7 +2274,7 @@ impl PatternDetector for TweezerTopDetector {
         if !ctx.trend.is_up() {
             return None;
         }
-        if !prev.is_bullish() || !curr.is_bearish() {
+        if !ctx.color.is_white(prev) || !ctx.color.is_black(curr) {
             return None;
         }
 
@@ -2234,12 +2291,13 @@ impl PatternDetector for TweezerTopDetector {
             strength: 0.6,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// Tweezer Bottom
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TweezerBottomDetector {
     pub tolerance: Ratio,
 }
@@ -2276,7 +2334,7 @@ impl PatternDetector for TweezerBottomDetector {
         if !ctx.trend.is_down() {
             return None;
         }
-        if !prev.is_bearish() || !curr.is_bullish() {
+        if !ctx.color.is_black(prev) || !ctx.color.is_white(curr) {
             return None;
         }
 
@@ -2293,6 +2351,7 @@ impl PatternDetector for TweezerBottomDetector {
             strength: 0.6,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -2325,7 +2384,7 @@ static THREE_WHITE_SOLDIERS_PARAMS: &[ParamMeta] = &[
     },
     ParamMeta {
         name: "body_short_factor",
-        param_type: ParamType::Ratio,
+        param_type: ParamType::Factor,
         default: 1.0,
         range: (0.5, 1.5, 0.1),
         description: "Body short threshold factor",
@@ -2372,6 +2431,240 @@ static TWEEZER_BOTTOM_PARAMS: &[ParamMeta] = &[ParamMeta {
     description: "Low price tolerance",
 }];
 
+static MORNING_STAR_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "body_long_factor",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (0.5, 1.5, 0.1),
+        description: "Body long threshold factor",
+    },
+    ParamMeta {
+        name: "body_short_factor",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (0.5, 1.5, 0.1),
+        description: "Body short threshold factor",
+    },
+    ParamMeta {
+        name: "penetration",
+        param_type: ParamType::Ratio,
+        default: 0.3,
+        range: (0.1, 0.5, 0.1),
+        description: "Third candle penetration into the first body",
+    },
+    ParamMeta {
+        name: "gap_tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.0,
+        range: (0.0, 0.2, 0.05),
+        description: "Body overlap still counted as a gap, as a fraction of the average range",
+    },
+];
+
+static EVENING_STAR_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "body_long_factor",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (0.5, 1.5, 0.1),
+        description: "Body long threshold factor",
+    },
+    ParamMeta {
+        name: "body_short_factor",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (0.5, 1.5, 0.1),
+        description: "Body short threshold factor",
+    },
+    ParamMeta {
+        name: "penetration",
+        param_type: ParamType::Ratio,
+        default: 0.3,
+        range: (0.1, 0.5, 0.1),
+        description: "Third candle penetration into the first body",
+    },
+    ParamMeta {
+        name: "gap_tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.0,
+        range: (0.0, 0.2, 0.05),
+        description: "Body overlap still counted as a gap, as a fraction of the average range",
+    },
+];
+
+static MORNING_DOJI_STAR_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "body_long_factor",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (0.5, 1.5, 0.1),
+        description: "Body long threshold factor",
+    },
+    ParamMeta {
+        name: "doji_factor",
+        param_type: ParamType::Ratio,
+        default: 0.1,
+        range: (0.05, 0.2, 0.05),
+        description: "Doji body threshold factor",
+    },
+    ParamMeta {
+        name: "penetration",
+        param_type: ParamType::Ratio,
+        default: 0.3,
+        range: (0.1, 0.5, 0.1),
+        description: "Third candle penetration into the first body",
+    },
+];
+
+static EVENING_DOJI_STAR_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "body_long_factor",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (0.5, 1.5, 0.1),
+        description: "Body long threshold factor",
+    },
+    ParamMeta {
+        name: "doji_factor",
+        param_type: ParamType::Ratio,
+        default: 0.1,
+        range: (0.05, 0.2, 0.05),
+        description: "Doji body threshold factor",
+    },
+    ParamMeta {
+        name: "penetration",
+        param_type: ParamType::Ratio,
+        default: 0.3,
+        range: (0.1, 0.5, 0.1),
+        description: "Third candle penetration into the first body",
+    },
+];
+
+static ABANDONED_BABY_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "body_long_factor",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (0.5, 1.5, 0.1),
+        description: "Body long threshold factor",
+    },
+    ParamMeta {
+        name: "doji_factor",
+        param_type: ParamType::Ratio,
+        default: 0.1,
+        range: (0.05, 0.2, 0.05),
+        description: "Doji body threshold factor",
+    },
+    ParamMeta {
+        name: "penetration",
+        param_type: ParamType::Ratio,
+        default: 0.3,
+        range: (0.1, 0.5, 0.1),
+        description: "Third candle penetration into the first body",
+    },
+];
+
+impl ParameterizedDetector for MorningStarDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        MORNING_STAR_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            body_long_factor: get_positive(params, "body_long_factor", helpers::BODY_LONG_FACTOR)?,
+            body_short_factor: get_positive(
+                params,
+                "body_short_factor",
+                helpers::BODY_SHORT_FACTOR,
+            )?,
+            penetration: get_ratio(params, "penetration", 0.3)?.get(),
+            gap_tolerance: get_ratio(params, "gap_tolerance", 0.0)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "CDL_MORNINGSTAR"
+    }
+}
+
+impl ParameterizedDetector for EveningStarDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        EVENING_STAR_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            body_long_factor: get_positive(params, "body_long_factor", helpers::BODY_LONG_FACTOR)?,
+            body_short_factor: get_positive(
+                params,
+                "body_short_factor",
+                helpers::BODY_SHORT_FACTOR,
+            )?,
+            penetration: get_ratio(params, "penetration", 0.3)?.get(),
+            gap_tolerance: get_ratio(params, "gap_tolerance", 0.0)?,
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "CDL_EVENINGSTAR"
+    }
+}
+
+impl ParameterizedDetector for MorningDojiStarDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        MORNING_DOJI_STAR_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            body_long_factor: get_positive(params, "body_long_factor", helpers::BODY_LONG_FACTOR)?,
+            doji_factor: get_ratio(params, "doji_factor", helpers::DOJI_FACTOR)?.get(),
+            penetration: get_ratio(params, "penetration", 0.3)?.get(),
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "CDL_MORNINGDOJISTAR"
+    }
+}
+
+impl ParameterizedDetector for EveningDojiStarDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        EVENING_DOJI_STAR_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            body_long_factor: get_positive(params, "body_long_factor", helpers::BODY_LONG_FACTOR)?,
+            doji_factor: get_ratio(params, "doji_factor", helpers::DOJI_FACTOR)?.get(),
+            penetration: get_ratio(params, "penetration", 0.3)?.get(),
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "CDL_EVENINGDOJISTAR"
+    }
+}
+
+impl ParameterizedDetector for AbandonedBabyDetector {
+    fn param_meta() -> &'static [ParamMeta] {
+        ABANDONED_BABY_PARAMS
+    }
+
+    fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
+        Ok(Self {
+            body_long_factor: get_positive(params, "body_long_factor", helpers::BODY_LONG_FACTOR)?,
+            doji_factor: get_ratio(params, "doji_factor", helpers::DOJI_FACTOR)?.get(),
+            penetration: get_ratio(params, "penetration", 0.3)?.get(),
+        })
+    }
+
+    fn pattern_id_str() -> &'static str {
+        "CDL_ABANDONEDBABY"
+    }
+}
+
 impl ParameterizedDetector for ThreeWhiteSoldiersDetector {
     fn param_meta() -> &'static [ParamMeta] {
         THREE_WHITE_SOLDIERS_PARAMS
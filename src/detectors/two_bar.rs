@@ -18,9 +18,9 @@ use super::{
     helpers::{is_body_long, is_body_long_f, is_body_short, is_body_short_f, is_doji},
 };
 use crate::{
-    params::{get_ratio, ParamMeta, ParamType, ParameterizedDetector},
-    Direction, MarketContext, OHLCVExt, PatternDetector, PatternId, PatternMatch, Ratio, Result,
-    OHLCV,
+    params::{get_flag, get_positive, get_ratio, ParamMeta, ParamType, ParameterizedDetector},
+    Direction, FormingMatch, MarketContext, OHLCVExt, PatternDetector, PatternId, PatternMatch,
+    Ratio, Result, OHLCV,
 };
 
 impl_with_defaults!(
@@ -47,19 +47,40 @@ impl_with_defaults!(
 // ============================================================
 
 /// CDLENGULFING - Engulfing Pattern (bullish and bearish)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct EngulfingDetector {
+    /// Minimum ratio of the engulfing body to the engulfed body, up to 1. The
+    /// TA-Lib default of 1.0 is implied by the engulfing itself; use
+    /// `min_engulf_factor` for larger ratios.
     pub min_engulf_ratio: Ratio,
+    /// Minimum ratio of the engulfing body to the engulfed body, which may
+    /// exceed 1 (e.g. 1.2 for a body at least 20% larger)
+    #[serde(default = "crate::default_one")]
+    pub min_engulf_factor: f64,
+    /// Also require the engulfing body to exceed the trailing average body
+    /// (off by default for TA-Lib compatibility)
+    #[serde(default)]
+    pub require_above_avg_body: bool,
 }
 
 impl Default for EngulfingDetector {
     fn default() -> Self {
         Self {
             min_engulf_ratio: Ratio::new_const(1.0),
+            min_engulf_factor: 1.0,
+            require_above_avg_body: false,
         }
     }
 }
 
+impl EngulfingDetector {
+    /// Required engulfing to engulfed body ratio, the stricter of
+    /// `min_engulf_ratio` and `min_engulf_factor`
+    fn min_body_ratio(&self) -> f64 {
+        self.min_engulf_factor.max(self.min_engulf_ratio.get())
+    }
+}
+
 impl PatternDetector for EngulfingDetector {
     fn id(&self) -> PatternId {
         PatternId("CDL_ENGULFING")
@@ -73,7 +94,7 @@ impl PatternDetector for EngulfingDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -82,10 +103,18 @@ impl PatternDetector for EngulfingDetector {
         let curr = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR: close >= open → white(+1), close < open → black(-1)
-        let curr_white = curr.close() >= curr.open();
-        let curr_black = curr.close() < curr.open();
-        let prev_white = prev.close() >= prev.open();
-        let prev_black = prev.close() < prev.open();
+        let curr_white = ctx.color.is_white(curr);
+        let curr_black = ctx.color.is_black(curr);
+        let prev_white = ctx.color.is_white(prev);
+        let prev_black = ctx.color.is_black(prev);
+
+        if curr.body() < prev.body() * self.min_body_ratio() {
+            return None;
+        }
+        // A NaN average (`WarmupPolicy::Nan`) rejects the bar
+        if self.require_above_avg_body && (ctx.avg_body.is_nan() || curr.body() <= ctx.avg_body) {
+            return None;
+        }
 
         // TA-Lib: Bullish engulfing — white engulfs black
         if curr_white && prev_black {
@@ -101,9 +130,10 @@ impl PatternDetector for EngulfingDetector {
                 return Some(PatternMatch {
                     pattern_id: PatternDetector::id(self),
                     direction: Direction::Bullish,
-                    strength,
+                    strength: helpers::clamp_strength(strength),
                     start_index: index - 1,
                     end_index: index,
+                    metric: None,
                 });
             }
         }
@@ -121,9 +151,10 @@ impl PatternDetector for EngulfingDetector {
                 return Some(PatternMatch {
                     pattern_id: PatternDetector::id(self),
                     direction: Direction::Bearish,
-                    strength,
+                    strength: helpers::clamp_strength(strength),
                     start_index: index - 1,
                     end_index: index,
+                    metric: None,
                 });
             }
         }
@@ -132,12 +163,93 @@ impl PatternDetector for EngulfingDetector {
     }
 }
 
+impl EngulfingDetector {
+    /// The first condition of [`detect`](PatternDetector::detect) that fails
+    /// at `index`, checked in the same order; `None` if it would match.
+    pub fn explain<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<String> {
+        if index < 1 || index >= bars.len() {
+            return Some("needs a previous bar".to_string());
+        }
+        let prev = &bars[index - 1];
+        let curr = &bars[index];
+
+        if curr.body() < prev.body() * self.min_body_ratio() {
+            return Some(format!(
+                "body {:.4} below {} x previous body {:.4}",
+                curr.body(),
+                self.min_body_ratio(),
+                prev.body()
+            ));
+        }
+        if self.require_above_avg_body && (ctx.avg_body.is_nan() || curr.body() <= ctx.avg_body) {
+            return Some(format!(
+                "body {:.4} not above average body {:.4}",
+                curr.body(),
+                ctx.avg_body
+            ));
+        }
+
+        let bullish = ctx.color.is_white(curr) && ctx.color.is_black(prev);
+        let bearish = ctx.color.is_black(curr) && ctx.color.is_white(prev);
+        if !bullish && !bearish {
+            return Some("candles are not of opposite colors".to_string());
+        }
+        if self.detect(bars, index, ctx).is_some() {
+            return None;
+        }
+        Some(format!(
+            "body {:.4}..{:.4} does not engulf previous body {:.4}..{:.4}",
+            curr.open(),
+            curr.close(),
+            prev.open(),
+            prev.close()
+        ))
+    }
+
+    /// Early warning: `bars[index]` can be engulfed by the next bar.
+    ///
+    /// A black bar in a downtrend sets up a bullish engulfing, a white bar in
+    /// an uptrend a bearish one. Doji bars are skipped. Returns a
+    /// `CDL_ENGULFING_PENDING` match on `index`, see [`helpers::forming_match`].
+    pub fn detect_forming<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<FormingMatch> {
+        let bar = bars.get(index)?;
+        if ctx.avg_range.is_nan() || is_doji(bar.body(), ctx.avg_range, bar.range()) {
+            return None;
+        }
+
+        let direction = if ctx.color.is_black(bar) && ctx.trend.is_down() {
+            Direction::Bullish
+        } else if ctx.color.is_white(bar) && ctx.trend.is_up() {
+            Direction::Bearish
+        } else {
+            return None;
+        };
+        Some(helpers::forming_match(
+            "CDL_ENGULFING_PENDING",
+            direction,
+            index,
+            1,
+            2,
+        ))
+    }
+}
+
 // ============================================================
 // HARAMI PATTERNS
 // ============================================================
 
 /// CDLHARAMI - Harami Pattern (bullish and bearish)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HaramiDetector {
     pub max_body_ratio: Ratio,
 }
@@ -201,7 +313,7 @@ impl PatternDetector for HaramiDetector {
             return None;
         }
 
-        let direction = if prev.is_bearish() {
+        let direction = if ctx.color.is_black(prev) {
             Direction::Bullish
         } else {
             Direction::Bearish
@@ -214,23 +326,29 @@ impl PatternDetector for HaramiDetector {
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction,
-            strength,
+            strength: helpers::clamp_strength(strength),
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLHARAMICROSS - Harami Cross (harami with doji)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HaramiCrossDetector {
     pub doji_body_max_ratio: Ratio,
+    /// Emit `CDL_HARAMICROSS_BULL` / `CDL_HARAMICROSS_BEAR` instead of the
+    /// single `CDL_HARAMICROSS` id
+    #[serde(default)]
+    pub split_directional_ids: bool,
 }
 
 impl Default for HaramiCrossDetector {
     fn default() -> Self {
         Self {
             doji_body_max_ratio: Ratio::new_const(0.1),
+            split_directional_ids: false,
         }
     }
 }
@@ -282,18 +400,25 @@ impl PatternDetector for HaramiCrossDetector {
             return None;
         }
 
-        let direction = if prev.is_bearish() {
+        let direction = if ctx.color.is_black(prev) {
             Direction::Bullish
         } else {
             Direction::Bearish
         };
 
+        let pattern_id = match (self.split_directional_ids, direction) {
+            (true, Direction::Bullish) => PatternId("CDL_HARAMICROSS_BULL"),
+            (true, _) => PatternId("CDL_HARAMICROSS_BEAR"),
+            (false, _) => PatternDetector::id(self),
+        };
+
         Some(PatternMatch {
-            pattern_id: PatternDetector::id(self),
+            pattern_id,
             direction,
             strength: 0.65,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -303,15 +428,23 @@ impl PatternDetector for HaramiCrossDetector {
 // ============================================================
 
 /// CDLPIERCING - Piercing Pattern
-#[derive(Debug, Clone)]
+///
+/// TA-Lib requires both candles to have long bodies. Clear
+/// `require_second_long_body` for the textbook reading, where only the black
+/// candle has to be long.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PiercingDetector {
     pub min_pierce_ratio: Ratio,
+    /// Require the white candle to have a long body too
+    #[serde(default = "crate::default_true")]
+    pub require_second_long_body: bool,
 }
 
 impl Default for PiercingDetector {
     fn default() -> Self {
         Self {
             min_pierce_ratio: Ratio::new_const(0.5),
+            require_second_long_body: true,
         }
     }
 }
@@ -329,7 +462,7 @@ impl PatternDetector for PiercingDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -338,11 +471,11 @@ impl PatternDetector for PiercingDetector {
         let curr = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR(i-1) == -1 (black: close < open)
-        if prev.close() >= prev.open() {
+        if ctx.color.is_white(prev) {
             return None;
         }
         // TA-Lib: TA_CANDLECOLOR(i) == 1 (white: close >= open)
-        if curr.close() < curr.open() {
+        if ctx.color.is_black(curr) {
             return None;
         }
 
@@ -355,11 +488,11 @@ impl PatternDetector for PiercingDetector {
         }
 
         // TA-Lib: second candle must also have BodyLong (per-candle trailing avg at i)
-        let curr_body = curr.body();
-        let curr_range = curr.range();
-        let curr_avg_body = helpers::trailing_avg_body(bars, index, 10);
-        if !is_body_long(curr_body, curr_avg_body, curr_range) {
-            return None;
+        if self.require_second_long_body {
+            let curr_avg_body = helpers::trailing_avg_body(bars, index, 10);
+            if !is_body_long(curr.body(), curr_avg_body, curr.range()) {
+                return None;
+            }
         }
 
         // TA-Lib: open[i] < low[i-1] (opens below previous LOW)
@@ -383,20 +516,57 @@ impl PatternDetector for PiercingDetector {
             strength: 0.7,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
 
+impl PiercingDetector {
+    /// Early warning: `bars[index]` is a long black candle in a downtrend that
+    /// the next bar can pierce. Returns a `CDL_PIERCING_PENDING` match on
+    /// `index`, see [`helpers::forming_match`].
+    pub fn detect_forming<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<FormingMatch> {
+        let bar = bars.get(index)?;
+        if !ctx.color.is_black(bar) || !ctx.trend.is_down() {
+            return None;
+        }
+        let avg_body = helpers::trailing_avg_body(bars, index, 10);
+        if !is_body_long(bar.body(), avg_body, bar.range()) {
+            return None;
+        }
+        Some(helpers::forming_match(
+            "CDL_PIERCING_PENDING",
+            Direction::Bullish,
+            index,
+            1,
+            2,
+        ))
+    }
+}
+
 /// CDLDARKCLOUDCOVER - Dark Cloud Cover
-#[derive(Debug, Clone)]
+///
+/// TA-Lib only requires the white candle to have a long body. Set
+/// `require_second_long_body` to demand a long black candle as well,
+/// mirroring [`PiercingDetector`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DarkCloudCoverDetector {
     pub min_pierce_ratio: Ratio,
+    /// Require the black candle to have a long body too
+    #[serde(default)]
+    pub require_second_long_body: bool,
 }
 
 impl Default for DarkCloudCoverDetector {
     fn default() -> Self {
         Self {
             min_pierce_ratio: Ratio::new_const(0.5),
+            require_second_long_body: false,
         }
     }
 }
@@ -414,7 +584,7 @@ impl PatternDetector for DarkCloudCoverDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -423,11 +593,11 @@ impl PatternDetector for DarkCloudCoverDetector {
         let curr = bars.get(index)?;
 
         // TA-Lib: TA_CANDLECOLOR(i-1) == 1 (white: close >= open)
-        if prev.close() < prev.open() {
+        if ctx.color.is_black(prev) {
             return None;
         }
         // TA-Lib: TA_CANDLECOLOR(i) == -1 (black: close < open)
-        if curr.close() >= curr.open() {
+        if ctx.color.is_white(curr) {
             return None;
         }
 
@@ -439,6 +609,14 @@ impl PatternDetector for DarkCloudCoverDetector {
             return None;
         }
 
+        // Optional: second candle also long (not checked by TA-Lib)
+        if self.require_second_long_body {
+            let curr_avg_body = helpers::trailing_avg_body(bars, index, 10);
+            if !is_body_long(curr.body(), curr_avg_body, curr.range()) {
+                return None;
+            }
+        }
+
         // TA-Lib: open[i] > high[i-1] (opens above previous HIGH, not just close)
         if curr.open() <= prev.high() {
             return None;
@@ -461,6 +639,7 @@ impl PatternDetector for DarkCloudCoverDetector {
             strength: 0.7,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -470,7 +649,7 @@ impl PatternDetector for DarkCloudCoverDetector {
 // ============================================================
 
 /// CDLDOJISTAR - Doji Star
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DojiStarDetector {
     pub doji_body_max_ratio: Ratio,
 }
@@ -496,7 +675,7 @@ impl PatternDetector for DojiStarDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -522,8 +701,8 @@ impl PatternDetector for DojiStarDetector {
 
         // TA-Lib: gap required using TA_REALBODYGAPUP/DOWN (real body gap, not shadow gap)
         // TA_CANDLECOLOR: close >= open → white(+1), close < open → black(-1)
-        let prev_white = prev.close() >= prev.open();
-        let prev_black = prev.close() < prev.open();
+        let prev_white = ctx.color.is_white(prev);
+        let prev_black = ctx.color.is_black(prev);
 
         let prev_body_top = prev.open().max(prev.close());
         let prev_body_bottom = prev.open().min(prev.close());
@@ -554,6 +733,7 @@ impl PatternDetector for DojiStarDetector {
             strength: 0.6,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -563,7 +743,7 @@ impl PatternDetector for DojiStarDetector {
 // ============================================================
 
 /// CDLCOUNTERATTACK - Counterattack
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CounterattackDetector {
     pub close_tolerance: Ratio,
 }
@@ -589,7 +769,7 @@ impl PatternDetector for CounterattackDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -598,7 +778,7 @@ impl PatternDetector for CounterattackDetector {
         let curr = bars.get(index)?;
 
         // TA-Lib: opposite colors
-        if prev.is_bullish() == curr.is_bullish() {
+        if ctx.color.is_white(prev) == ctx.color.is_white(curr) {
             return None;
         }
 
@@ -625,7 +805,7 @@ impl PatternDetector for CounterattackDetector {
         }
 
         // TA-Lib: direction based on current candle color
-        let direction = if curr.is_bullish() {
+        let direction = if ctx.color.is_white(curr) {
             Direction::Bullish
         } else {
             Direction::Bearish
@@ -637,6 +817,7 @@ impl PatternDetector for CounterattackDetector {
             strength: 0.6,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -645,8 +826,93 @@ impl PatternDetector for CounterattackDetector {
 // NECK PATTERNS
 // ============================================================
 
+/// Neck-family continuation pattern formed by a black-white candle pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeckPattern {
+    OnNeck,
+    InNeck,
+    Thrusting,
+}
+
+/// Long black candle followed by a white candle opening below its low.
+///
+/// Returns both bars and the Equal threshold (trailing-5 average range at the
+/// black candle times `equal_factor`).
+fn neck_setup<'a, T: OHLCV>(
+    bars: &'a [T],
+    index: usize,
+    ctx: &MarketContext,
+    body_long_factor: f64,
+    equal_factor: f64,
+) -> Option<(&'a T, &'a T, f64)> {
+    if index < 1 {
+        return None;
+    }
+    let prev = bars.get(index - 1)?;
+    let curr = bars.get(index)?;
+
+    // TA-Lib: 1st black (close < open), 2nd white (close >= open)
+    if ctx.color.is_white(prev) {
+        return None;
+    }
+    if ctx.color.is_black(curr) {
+        return None;
+    }
+
+    // TA-Lib: 1st candle must have BodyLong (per-candle trailing avg at i-1)
+    let prev_avg_body = helpers::trailing_avg_body(bars, index - 1, 10);
+    if !is_body_long_f(prev.body(), prev_avg_body, prev.range(), body_long_factor) {
+        return None;
+    }
+
+    // TA-Lib: 2nd opens below 1st low
+    if curr.open() >= prev.low() {
+        return None;
+    }
+
+    // Equal threshold at i-1 (per-candle trailing)
+    let equal_threshold = helpers::trailing_avg_range(bars, index - 1, 5) * equal_factor;
+    Some((prev, curr, equal_threshold))
+}
+
+/// Classify the pair ending at `index` as On-Neck, In-Neck or Thrusting.
+///
+/// The three patterns differ only in where the white candle closes relative
+/// to the black one (`eq` is the Equal threshold):
+/// - In-Neck: `prev close ..= prev close + eq`
+/// - Thrusting: above that, up to the black body midpoint
+/// - On-Neck: below the prior close, within `eq` of the prior low
+///
+/// The bands are checked in that order, so at most one pattern applies. The
+/// neck detectors all classify through this function and are mutually
+/// exclusive with default settings.
+pub fn classify_neck<T: OHLCV>(
+    bars: &[T],
+    index: usize,
+    ctx: &MarketContext,
+    body_long_factor: f64,
+    equal_factor: f64,
+) -> Option<NeckPattern> {
+    let (prev, curr, eq) = neck_setup(bars, index, ctx, body_long_factor, equal_factor)?;
+    let close = curr.close();
+
+    if close >= prev.close() {
+        if close <= prev.close() + eq {
+            Some(NeckPattern::InNeck)
+        } else if close <= prev.close() + prev.body() * 0.5 {
+            Some(NeckPattern::Thrusting)
+        } else {
+            None
+        }
+    } else if close <= prev.low() + eq && close >= prev.low() - eq {
+        Some(NeckPattern::OnNeck)
+    } else {
+        None
+    }
+}
+
 /// CDLINNECK - In-Neck Pattern
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct InNeckDetector {
     pub tolerance: Ratio,
 }
@@ -672,43 +938,17 @@ impl PatternDetector for InNeckDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
-        if index < 1 {
-            return None;
-        }
-        let prev = bars.get(index - 1)?;
-        let curr = bars.get(index)?;
-
-        // TA-Lib: 1st black (close < open), 2nd white (close >= open)
-        if prev.close() >= prev.open() {
-            return None;
-        }
-        if curr.close() < curr.open() {
-            return None;
-        }
-
-        // TA-Lib: 1st candle must have BodyLong (per-candle trailing avg at i-1)
-        let prev_body = prev.body();
-        let prev_range = prev.range();
-        let prev_avg_body = helpers::trailing_avg_body(bars, index - 1, 10);
-        if !is_body_long(prev_body, prev_avg_body, prev_range) {
-            return None;
-        }
-
-        // TA-Lib: 2nd opens below 1st low
-        if curr.open() >= prev.low() {
-            return None;
-        }
-
         // TA-Lib: close[i] <= close[i-1] + Equal_avg AND close[i] >= close[i-1]
-        // Equal threshold at i-1 (per-candle trailing)
-        let equal_threshold =
-            helpers::trailing_avg_range(bars, index - 1, 5) * helpers::EQUAL_FACTOR;
-        if curr.close() > prev.close() + equal_threshold {
-            return None;
-        }
-        if curr.close() < prev.close() {
+        let class = classify_neck(
+            bars,
+            index,
+            ctx,
+            helpers::BODY_LONG_FACTOR,
+            helpers::EQUAL_FACTOR,
+        )?;
+        if class != NeckPattern::InNeck {
             return None;
         }
 
@@ -718,20 +958,30 @@ impl PatternDetector for InNeckDetector {
             strength: 0.5,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLONNECK - On-Neck Pattern
-#[derive(Debug, Clone)]
+///
+/// By default a close at or above the prior close is left to In-Neck or
+/// Thrusting (see `classify_neck`). TA-Lib also flags such a close as On-Neck
+/// when it is within the Equal threshold of the prior low (short lower
+/// shadow); disable `exclusive` for that output.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct OnNeckDetector {
     pub tolerance: Ratio,
+    /// Defer to In-Neck/Thrusting where the bands overlap
+    #[serde(default = "crate::default_true")]
+    pub exclusive: bool,
 }
 
 impl Default for OnNeckDetector {
     fn default() -> Self {
         Self {
             tolerance: Ratio::new_const(0.01),
+            exclusive: true,
         }
     }
 }
@@ -749,44 +999,19 @@ impl PatternDetector for OnNeckDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
-        if index < 1 {
-            return None;
-        }
-        let prev = bars.get(index - 1)?;
-        let curr = bars.get(index)?;
-
-        // TA-Lib: 1st black (close < open), 2nd white (close >= open)
-        if prev.close() >= prev.open() {
-            return None;
-        }
-        if curr.close() < curr.open() {
-            return None;
-        }
-
-        // TA-Lib: 1st candle must have BodyLong (per-candle trailing avg at i-1)
-        let prev_body = prev.body();
-        let prev_range = prev.range();
-        let prev_avg_body = helpers::trailing_avg_body(bars, index - 1, 10);
-        if !is_body_long(prev_body, prev_avg_body, prev_range) {
-            return None;
-        }
-
-        // TA-Lib: 2nd opens below 1st low
-        if curr.open() >= prev.low() {
-            return None;
-        }
-
-        // TA-Lib: close[i] <= low[i-1] + Equal_avg AND close[i] >= low[i-1] - Equal_avg
-        // Equal threshold at i-1 (per-candle trailing)
-        let equal_threshold =
-            helpers::trailing_avg_range(bars, index - 1, 5) * helpers::EQUAL_FACTOR;
-        if curr.close() > prev.low() + equal_threshold {
-            return None;
-        }
-        if curr.close() < prev.low() - equal_threshold {
-            return None;
+        let factors = (helpers::BODY_LONG_FACTOR, helpers::EQUAL_FACTOR);
+        if self.exclusive {
+            if classify_neck(bars, index, ctx, factors.0, factors.1)? != NeckPattern::OnNeck {
+                return None;
+            }
+        } else {
+            // TA-Lib: close[i] <= low[i-1] + Equal_avg AND close[i] >= low[i-1] - Equal_avg
+            let (prev, curr, eq) = neck_setup(bars, index, ctx, factors.0, factors.1)?;
+            if curr.close() > prev.low() + eq || curr.close() < prev.low() - eq {
+                return None;
+            }
         }
 
         Some(PatternMatch {
@@ -795,12 +1020,13 @@ impl PatternDetector for OnNeckDetector {
             strength: 0.5,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLTHRUSTING - Thrusting Pattern
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ThrustingDetector {
     pub body_long_factor: f64,
     pub equal_factor: f64,
@@ -828,44 +1054,11 @@ impl PatternDetector for ThrustingDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
-        if index < 1 {
-            return None;
-        }
-        let prev = bars.get(index - 1)?;
-        let curr = bars.get(index)?;
-
-        // TA-Lib: 1st black (close < open), 2nd white (close >= open)
-        if prev.close() >= prev.open() {
-            return None;
-        }
-        if curr.close() < curr.open() {
-            return None;
-        }
-
-        // TA-Lib: 1st candle must have BodyLong (per-candle trailing avg at i-1)
-        let prev_body = prev.body();
-        let prev_range = prev.range();
-        let prev_avg_body = helpers::trailing_avg_body(bars, index - 1, 10);
-        if !is_body_long_f(prev_body, prev_avg_body, prev_range, self.body_long_factor) {
-            return None;
-        }
-
-        // TA-Lib: 2nd opens below 1st low
-        if curr.open() >= prev.low() {
-            return None;
-        }
-
-        // TA-Lib: close[i] > close[i-1] + Equal_avg (must be above close+tolerance)
-        // Equal threshold at i-1 (per-candle trailing)
-        let equal_threshold = helpers::trailing_avg_range(bars, index - 1, 5) * self.equal_factor;
-        if curr.close() <= prev.close() + equal_threshold {
-            return None;
-        }
-
-        // TA-Lib: close[i] <= close[i-1] + body[i-1] * 0.5 (at or below midpoint)
-        if curr.close() > prev.close() + prev_body * 0.5 {
+        // TA-Lib: close[i] > close[i-1] + Equal_avg AND close[i] <= body midpoint
+        let class = classify_neck(bars, index, ctx, self.body_long_factor, self.equal_factor)?;
+        if class != NeckPattern::Thrusting {
             return None;
         }
 
@@ -875,6 +1068,7 @@ impl PatternDetector for ThrustingDetector {
             strength: 0.5,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -884,7 +1078,7 @@ impl PatternDetector for ThrustingDetector {
 // ============================================================
 
 /// CDLKICKING - Kicking Pattern
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct KickingDetector {
     pub shadow_max_ratio: Ratio,
 }
@@ -910,7 +1104,7 @@ impl PatternDetector for KickingDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -925,13 +1119,13 @@ impl PatternDetector for KickingDetector {
         }
 
         // Opposite colors with gap
-        let direction = if prev.is_bearish() && curr.is_bullish() {
+        let direction = if ctx.color.is_black(prev) && ctx.color.is_white(curr) {
             // Bullish kicking: gap up
             if curr.low() <= prev.high() {
                 return None;
             }
             Direction::Bullish
-        } else if prev.is_bullish() && curr.is_bearish() {
+        } else if ctx.color.is_white(prev) && ctx.color.is_black(curr) {
             // Bearish kicking: gap down
             if curr.high() >= prev.low() {
                 return None;
@@ -947,12 +1141,13 @@ impl PatternDetector for KickingDetector {
             strength: 0.8,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLKICKINGBYLENGTH - Kicking by Length
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct KickingByLengthDetector {
     pub shadow_max_ratio: Ratio,
 }
@@ -978,7 +1173,7 @@ impl PatternDetector for KickingByLengthDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -993,12 +1188,12 @@ impl PatternDetector for KickingByLengthDetector {
         }
 
         // Opposite colors with gap
-        if prev.is_bearish() == curr.is_bearish() {
+        if ctx.color.is_black(prev) == ctx.color.is_black(curr) {
             return None;
         }
 
         // Gap required
-        let has_gap = if curr.is_bullish() {
+        let has_gap = if ctx.color.is_white(curr) {
             curr.low() > prev.high()
         } else {
             curr.high() < prev.low()
@@ -1009,12 +1204,12 @@ impl PatternDetector for KickingByLengthDetector {
 
         // Direction determined by longer marubozu
         let direction = if curr.body() > prev.body() {
-            if curr.is_bullish() {
+            if ctx.color.is_white(curr) {
                 Direction::Bullish
             } else {
                 Direction::Bearish
             }
-        } else if prev.is_bullish() {
+        } else if ctx.color.is_white(prev) {
             Direction::Bullish
         } else {
             Direction::Bearish
@@ -1026,6 +1221,7 @@ impl PatternDetector for KickingByLengthDetector {
             strength: 0.75,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1035,7 +1231,7 @@ impl PatternDetector for KickingByLengthDetector {
 // ============================================================
 
 /// CDLMATCHINGLOW - Matching Low
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MatchingLowDetector {
     pub tolerance: Ratio,
 }
@@ -1061,7 +1257,7 @@ impl PatternDetector for MatchingLowDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -1070,7 +1266,7 @@ impl PatternDetector for MatchingLowDetector {
         let curr = bars.get(index)?;
 
         // TA-Lib: both bearish
-        if !prev.is_bearish() || !curr.is_bearish() {
+        if !ctx.color.is_black(prev) || !ctx.color.is_black(curr) {
             return None;
         }
 
@@ -1089,12 +1285,13 @@ impl PatternDetector for MatchingLowDetector {
             strength: 0.55,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLHOMINGPIGEON - Homing Pigeon
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HomingPigeonDetector {
     pub body_long_factor: f64,
     pub body_short_factor: f64,
@@ -1122,7 +1319,7 @@ impl PatternDetector for HomingPigeonDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -1131,7 +1328,7 @@ impl PatternDetector for HomingPigeonDetector {
         let curr = bars.get(index)?;
 
         // TA-Lib: both bearish (black)
-        if !prev.is_bearish() || !curr.is_bearish() {
+        if !ctx.color.is_black(prev) || !ctx.color.is_black(curr) {
             return None;
         }
 
@@ -1165,6 +1362,7 @@ impl PatternDetector for HomingPigeonDetector {
             strength: 0.55,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1174,15 +1372,23 @@ impl PatternDetector for HomingPigeonDetector {
 // ============================================================
 
 /// CDLSEPARATINGLINES - Separating Lines
-#[derive(Debug, Clone)]
+///
+/// A continuation pattern: with `require_trend` set (the default) a bullish
+/// line only fires in an uptrend and a bearish one in a downtrend. Disable it
+/// for strict TA-Lib output.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SeparatingLinesDetector {
     pub tolerance: Ratio,
+    /// Require `ctx.trend` to point in the pattern's direction
+    #[serde(default = "crate::default_true")]
+    pub require_trend: bool,
 }
 
 impl Default for SeparatingLinesDetector {
     fn default() -> Self {
         Self {
             tolerance: Ratio::new_const(0.005),
+            require_trend: true,
         }
     }
 }
@@ -1200,7 +1406,7 @@ impl PatternDetector for SeparatingLinesDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 1 {
             return None;
@@ -1210,8 +1416,8 @@ impl PatternDetector for SeparatingLinesDetector {
 
         // TA-Lib: TA_CANDLECOLOR(i-1) == -TA_CANDLECOLOR(i) (opposite colors)
         // TA_CANDLECOLOR: close >= open → 1 (white), close < open → -1 (black)
-        let curr_white = curr.close() >= curr.open();
-        let prev_white = prev.close() >= prev.open();
+        let curr_white = ctx.color.is_white(curr);
+        let prev_white = ctx.color.is_white(prev);
         if curr_white == prev_white {
             return None;
         }
@@ -1253,18 +1459,30 @@ impl PatternDetector for SeparatingLinesDetector {
             Direction::Bearish
         };
 
+        // Continuation: the line must run with the prevailing trend
+        if self.require_trend {
+            let with_trend = match direction {
+                Direction::Bullish => ctx.trend.is_up(),
+                _ => ctx.trend.is_down(),
+            };
+            if !with_trend {
+                return None;
+            }
+        }
+
         Some(PatternMatch {
             pattern_id: PatternDetector::id(self),
             direction,
             strength: 0.55,
             start_index: index - 1,
             end_index: index,
+            metric: None,
         })
     }
 }
 
 /// CDLGAPSIDESIDEWHITE - Gap Side-by-Side White Lines
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GapSideSideWhiteDetector {
     pub tolerance: Ratio,
 }
@@ -1290,7 +1508,7 @@ impl PatternDetector for GapSideSideWhiteDetector {
         &self,
         bars: &[T],
         index: usize,
-        _ctx: &MarketContext,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch> {
         if index < 2 {
             return None;
@@ -1300,8 +1518,8 @@ impl PatternDetector for GapSideSideWhiteDetector {
         let third = bars.get(index)?;
 
         // TA-Lib: 2nd and 3rd both white (close >= open)
-        let second_white = second.close() >= second.open();
-        let third_white = third.close() >= third.open();
+        let second_white = ctx.color.is_white(second);
+        let third_white = ctx.color.is_white(third);
         if !second_white || !third_white {
             return None;
         }
@@ -1354,6 +1572,7 @@ impl PatternDetector for GapSideSideWhiteDetector {
             strength: 0.6,
             start_index: index - 2,
             end_index: index,
+            metric: None,
         })
     }
 }
@@ -1363,13 +1582,27 @@ impl PatternDetector for GapSideSideWhiteDetector {
 // ============================================================
 
 // Static parameter metadata definitions
-static ENGULFING_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "min_engulf_ratio",
-    param_type: ParamType::Ratio,
-    default: 1.0,
-    range: (0.8, 1.2, 0.1),
-    description: "Minimum engulfing body ratio",
-}];
+static ENGULFING_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "min_engulf_ratio",
+        param_type: ParamType::Ratio,
+        default: 1.0,
+        range: (0.8, 1.0, 0.1),
+        description: "Minimum engulfing to engulfed body ratio, up to 1",
+    },
+    ParamMeta {
+        name: "min_engulf_factor",
+        param_type: ParamType::Factor,
+        default: 1.0,
+        range: (1.0, 1.5, 0.1),
+        description: "Minimum engulfing to engulfed body ratio (may exceed 1)",
+    },
+    ParamMeta::flag(
+        "require_above_avg_body",
+        false,
+        "Require the engulfing body to exceed the average body",
+    ),
+];
 
 static HARAMI_PARAMS: &[ParamMeta] = &[ParamMeta {
     name: "max_body_ratio",
@@ -1379,29 +1612,50 @@ static HARAMI_PARAMS: &[ParamMeta] = &[ParamMeta {
     description: "Maximum inside body ratio",
 }];
 
-static HARAMICROSS_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "doji_body_max_ratio",
-    param_type: ParamType::Ratio,
-    default: 0.1,
-    range: (0.05, 0.2, 0.05),
-    description: "Maximum doji body ratio",
-}];
-
-static PIERCING_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "min_pierce_ratio",
-    param_type: ParamType::Ratio,
-    default: 0.5,
-    range: (0.4, 0.7, 0.1),
-    description: "Minimum piercing level",
-}];
-
-static DARKCLOUDCOVER_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "min_pierce_ratio",
-    param_type: ParamType::Ratio,
-    default: 0.5,
-    range: (0.4, 0.7, 0.1),
-    description: "Minimum piercing level",
-}];
+static HARAMICROSS_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "doji_body_max_ratio",
+        param_type: ParamType::Ratio,
+        default: 0.1,
+        range: (0.05, 0.2, 0.05),
+        description: "Maximum doji body ratio",
+    },
+    ParamMeta::flag(
+        "split_directional_ids",
+        false,
+        "Emit CDL_HARAMICROSS_BULL / CDL_HARAMICROSS_BEAR",
+    ),
+];
+
+static PIERCING_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "min_pierce_ratio",
+        param_type: ParamType::Ratio,
+        default: 0.5,
+        range: (0.4, 0.7, 0.1),
+        description: "Minimum piercing level",
+    },
+    ParamMeta::flag(
+        "require_second_long_body",
+        true,
+        "Require the white candle to have a long body too",
+    ),
+];
+
+static DARKCLOUDCOVER_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "min_pierce_ratio",
+        param_type: ParamType::Ratio,
+        default: 0.5,
+        range: (0.4, 0.7, 0.1),
+        description: "Minimum piercing level",
+    },
+    ParamMeta::flag(
+        "require_second_long_body",
+        false,
+        "Require the black candle to have a long body too",
+    ),
+];
 
 static DOJISTAR_PARAMS: &[ParamMeta] = &[ParamMeta {
     name: "doji_body_max_ratio",
@@ -1427,13 +1681,20 @@ static INNECK_PARAMS: &[ParamMeta] = &[ParamMeta {
     description: "Price tolerance",
 }];
 
-static ONNECK_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "tolerance",
-    param_type: ParamType::Ratio,
-    default: 0.01,
-    range: (0.005, 0.03, 0.005),
-    description: "Price tolerance",
-}];
+static ONNECK_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.01,
+        range: (0.005, 0.03, 0.005),
+        description: "Price tolerance",
+    },
+    ParamMeta::flag(
+        "exclusive",
+        true,
+        "Defer to In-Neck/Thrusting where the bands overlap (off for TA-Lib)",
+    ),
+];
 
 static KICKING_PARAMS: &[ParamMeta] = &[ParamMeta {
     name: "shadow_max_ratio",
@@ -1459,13 +1720,20 @@ static MATCHINGLOW_PARAMS: &[ParamMeta] = &[ParamMeta {
     description: "Price matching tolerance",
 }];
 
-static SEPARATINGLINES_PARAMS: &[ParamMeta] = &[ParamMeta {
-    name: "tolerance",
-    param_type: ParamType::Ratio,
-    default: 0.005,
-    range: (0.002, 0.01, 0.002),
-    description: "Open price tolerance",
-}];
+static SEPARATINGLINES_PARAMS: &[ParamMeta] = &[
+    ParamMeta {
+        name: "tolerance",
+        param_type: ParamType::Ratio,
+        default: 0.005,
+        range: (0.002, 0.01, 0.002),
+        description: "Open price tolerance",
+    },
+    ParamMeta::flag(
+        "require_trend",
+        true,
+        "Require a trend in the pattern's direction",
+    ),
+];
 
 static GAPSIDESIDEWHITE_PARAMS: &[ParamMeta] = &[ParamMeta {
     name: "tolerance",
@@ -1483,6 +1751,8 @@ impl ParameterizedDetector for EngulfingDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             min_engulf_ratio: get_ratio(params, "min_engulf_ratio", 1.0)?,
+            min_engulf_factor: get_positive(params, "min_engulf_factor", 1.0)?,
+            require_above_avg_body: get_flag(params, "require_above_avg_body", false)?,
         })
     }
 
@@ -1515,6 +1785,7 @@ impl ParameterizedDetector for HaramiCrossDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             doji_body_max_ratio: get_ratio(params, "doji_body_max_ratio", 0.1)?,
+            split_directional_ids: get_flag(params, "split_directional_ids", false)?,
         })
     }
 
@@ -1531,6 +1802,7 @@ impl ParameterizedDetector for PiercingDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             min_pierce_ratio: get_ratio(params, "min_pierce_ratio", 0.5)?,
+            require_second_long_body: get_flag(params, "require_second_long_body", true)?,
         })
     }
 
@@ -1547,6 +1819,7 @@ impl ParameterizedDetector for DarkCloudCoverDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             min_pierce_ratio: get_ratio(params, "min_pierce_ratio", 0.5)?,
+            require_second_long_body: get_flag(params, "require_second_long_body", false)?,
         })
     }
 
@@ -1611,6 +1884,7 @@ impl ParameterizedDetector for OnNeckDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             tolerance: get_ratio(params, "tolerance", 0.01)?,
+            exclusive: get_flag(params, "exclusive", true)?,
         })
     }
 
@@ -1675,6 +1949,7 @@ impl ParameterizedDetector for SeparatingLinesDetector {
     fn with_params(params: &HashMap<&str, f64>) -> Result<Self> {
         Ok(Self {
             tolerance: get_ratio(params, "tolerance", 0.005)?,
+            require_trend: get_flag(params, "require_trend", true)?,
         })
     }
 
@@ -29,29 +29,60 @@
 //! let patterns = engine.scan(&bars).unwrap();
 //! ```
 
+pub mod analysis;
 pub mod detectors;
 pub mod params;
+pub mod resample;
+pub mod streaming;
 
 pub mod prelude {
     pub use crate::{
+        // Aggregation
+        aggregate_stats,
+        // Analysis
+        analysis::{
+            coalesce_continuations, confirm, conflicts, decayed_sentiment, gap_filled,
+            match_density, select_non_overlapping, suggested_stop, with_timestamps, MatchSliceExt,
+        },
         // Detectors
         detectors::*,
+        // Trend
+        overall_trend,
         // Parameters
         params::{get_period, get_ratio, ParamMeta, ParamType, ParameterizedDetector},
+        // Catalog
+        pattern_catalog,
+        // Resampling
+        resample::{resample_by, snap_to_tick, to_line_bars, OwnedBar, PriceField},
         // Parallel
         scan_parallel,
+        // Streaming
+        streaming::{scan_streaming, StreamingEngine},
+        // Statistics
+        window_stats,
+        // Types
+        AverageMethod,
         // Iterator
         BarPatterns,
         // Engine
         BuiltinDetector,
-        // Types
+        CachingContextProvider,
+        CheckedDetector,
+        ColorConvention,
+        CompositeContextProvider,
         ContextProvider,
+        DetectedEvent,
+        DetectorSnapshot,
         Direction,
         // Core traits
         DynPatternDetector,
         EngineBuilder,
+        EngineSnapshot,
+        FormingMatch,
+        IncrementalContext,
         MarketContext,
         OHLCVExt,
+        OverrideContexts,
         PatternDetector,
         PatternEngine,
         // Errors
@@ -59,12 +90,19 @@ pub mod prelude {
         PatternId,
         PatternIterator,
         PatternMatch,
+        PatternStats,
         Period,
         Ratio,
         Result,
         ScanError,
         ScanResult,
+        ScoreModel,
+        StrengthCalibration,
         Trend,
+        TrendMethod,
+        WarmupPolicy,
+        WindowStats,
+        BUILTIN_PATTERN_COUNT,
         OHLCV,
     };
 }
@@ -97,6 +135,9 @@ pub enum PatternError {
 
     #[error("Invalid OHLCV at index {index}: {reason}")]
     InvalidOHLCV { index: usize, reason: &'static str },
+
+    #[error("I/O error: {0}")]
+    Io(String),
 }
 
 // ============================================================
@@ -138,6 +179,13 @@ impl Ratio {
     }
 }
 
+/// `0.0`
+impl Default for Ratio {
+    fn default() -> Self {
+        Self(0.0)
+    }
+}
+
 impl serde::Serialize for Ratio {
     fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
         self.0.serialize(s)
@@ -151,6 +199,31 @@ impl<'de> serde::Deserialize<'de> for Ratio {
     }
 }
 
+/// Deserialize an `f64` factor, which may exceed 1.0, with the checks of
+/// [`params::get_positive`]
+pub(crate) fn deserialize_factor<'de, D: serde::Deserializer<'de>>(
+    d: D,
+) -> std::result::Result<f64, D::Error> {
+    let value = <f64 as serde::Deserialize>::deserialize(d)?;
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(serde::de::Error::custom(
+            "factor must be positive and finite",
+        ))
+    }
+}
+
+/// Serde default for switches that are on unless configured otherwise
+pub(crate) fn default_true() -> bool {
+    true
+}
+
+/// Serde default for multipliers that are neutral unless configured otherwise
+pub(crate) fn default_one() -> f64 {
+    1.0
+}
+
 /// Period (must be > 0)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Period(usize);
@@ -193,6 +266,11 @@ impl<'de> serde::Deserialize<'de> for Period {
 // ============================================================
 
 /// Core OHLCV data trait
+///
+/// Prices are expected to be finite. Non-finite values are only rejected when
+/// `EngineConfig::validate_data` is enabled; otherwise NaN makes every
+/// comparison false, so detectors silently stop matching on the affected bars
+/// (and the ratio helpers in [`OHLCVExt`] return `None`) instead of erroring.
 pub trait OHLCV {
     fn open(&self) -> f64;
     fn high(&self) -> f64;
@@ -264,23 +342,40 @@ pub trait OHLCVExt: OHLCV {
         self.close() < self.open()
     }
 
-    /// Body as ratio of range. Returns None if range ≈ 0
+    /// Body as ratio of range. Returns None if range ≈ 0 or any input is NaN/infinite
     #[inline]
     fn body_ratio(&self) -> Option<f64> {
-        let range = self.range();
-        (range > f64::EPSILON).then(|| self.body() / range)
+        ratio_of_range(self, self.body())
     }
 
+    /// Upper shadow as ratio of range. Returns None if range ≈ 0 or any input is NaN/infinite
     #[inline]
     fn upper_shadow_ratio(&self) -> Option<f64> {
-        let range = self.range();
-        (range > f64::EPSILON).then(|| self.upper_shadow() / range)
+        ratio_of_range(self, self.upper_shadow())
     }
 
+    /// Lower shadow as ratio of range. Returns None if range ≈ 0 or any input is NaN/infinite
     #[inline]
     fn lower_shadow_ratio(&self) -> Option<f64> {
-        let range = self.range();
-        (range > f64::EPSILON).then(|| self.lower_shadow() / range)
+        ratio_of_range(self, self.lower_shadow())
+    }
+
+    /// Typical price: (high + low + close) / 3
+    #[inline]
+    fn typical_price(&self) -> f64 {
+        (self.high() + self.low() + self.close()) / 3.0
+    }
+
+    /// Weighted close: (high + low + 2 * close) / 4
+    #[inline]
+    fn weighted_close(&self) -> f64 {
+        (self.high() + self.low() + 2.0 * self.close()) / 4.0
+    }
+
+    /// Median price: (high + low) / 2
+    #[inline]
+    fn median_price(&self) -> f64 {
+        (self.high() + self.low()) / 2.0
     }
 
     /// Validate OHLCV data consistency
@@ -311,12 +406,63 @@ pub trait OHLCVExt: OHLCV {
                 reason: "Infinite value in OHLCV",
             });
         }
+        let (low, high) = (self.low(), self.high());
+        if !(low..=high).contains(&self.open()) || !(low..=high).contains(&self.close()) {
+            return Err(PatternError::InvalidOHLCV {
+                index: 0,
+                reason: "open/close outside high/low",
+            });
+        }
         Ok(())
     }
 }
 
 impl<T: OHLCV> OHLCVExt for T {}
 
+/// `value / range`, or None if the range is ≈ 0 or the bar has non-finite prices
+#[inline]
+fn ratio_of_range<T: OHLCVExt + ?Sized>(bar: &T, value: f64) -> Option<f64> {
+    let range = bar.range();
+    let finite = bar.open().is_finite()
+        && bar.high().is_finite()
+        && bar.low().is_finite()
+        && bar.close().is_finite();
+    (finite && range > f64::EPSILON).then(|| value / range)
+}
+
+/// Summary of a slice of bars, see [`window_stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WindowStats {
+    pub mean_close: f64,
+    /// Population standard deviation of the closes
+    pub std_close: f64,
+    pub max_high: f64,
+    pub min_low: f64,
+    pub total_volume: f64,
+}
+
+/// Close mean/deviation, extreme high/low and total volume of `bars`, for
+/// custom detectors and context providers. All zero for an empty slice.
+pub fn window_stats<T: OHLCV>(bars: &[T]) -> WindowStats {
+    if bars.is_empty() {
+        return WindowStats::default();
+    }
+    let n = bars.len() as f64;
+    let mean_close = bars.iter().map(|b| b.close()).sum::<f64>() / n;
+    let variance = bars
+        .iter()
+        .map(|b| (b.close() - mean_close).powi(2))
+        .sum::<f64>()
+        / n;
+    WindowStats {
+        mean_close,
+        std_close: variance.sqrt(),
+        max_high: bars.iter().map(|b| b.high()).fold(f64::MIN, f64::max),
+        min_low: bars.iter().map(|b| b.low()).fold(f64::MAX, f64::min),
+        total_volume: bars.iter().map(|b| b.volume()).sum(),
+    }
+}
+
 // ============================================================
 // PATTERN MATCH - result of detection (Copy, no allocations)
 // ============================================================
@@ -326,6 +472,10 @@ impl<T: OHLCV> OHLCVExt for T {}
 pub struct PatternId(pub &'static str);
 
 impl PatternId {
+    /// Synthetic marker emitted by `scan_grouped` for bars without any match
+    /// when `EngineConfig::emit_empty_markers` is enabled.
+    pub const NO_PATTERN: PatternId = PatternId("NO_PATTERN");
+
     /// Returns the string identifier
     #[inline]
     pub fn as_str(&self) -> &'static str {
@@ -348,6 +498,7 @@ impl PatternId {
             | "CDL_MORNINGSTAR"
             | "CDL_MORNINGDOJISTAR"
             | "CDL_PIERCING"
+            | "CDL_PIERCING_PENDING"
             | "CDL_LADDERBOTTOM"
             | "CDL_HOMINGPIGEON"
             | "CDL_TAKURI"
@@ -358,6 +509,7 @@ impl PatternId {
             | "CDL_MATHOLD"
             | "CDL_GAPSIDESIDEWHITE"
             | "RISING_WINDOW"
+            | "NEW_HIGH"
             | "GAPPING_UP_DOJI"
             | "WHITE_MARUBOZU"
             | "OPENING_WHITE_MARUBOZU"
@@ -370,7 +522,11 @@ impl PatternId {
             | "MEETING_LINES_BULLISH"
             | "UPSIDE_TASUKI_GAP"
             | "UPSIDE_GAP_THREE_METHODS"
-            | "CDL_TWEEZERBOTTOM" => Some(Direction::Bullish),
+            | "TOWER_BOTTOM"
+            | "FRY_PAN_BOTTOM"
+            | "CDL_TWEEZERBOTTOM"
+            | "CDL_HARAMICROSS_BULL"
+            | "RISING_THREE_METHODS" => Some(Direction::Bullish),
             // Bearish patterns
             "CDL_3BLACKCROWS"
             | "CDL_2CROWS"
@@ -389,6 +545,7 @@ impl PatternId {
             | "CDL_CONCEALBABYSWALL"
             | "CDL_GRAVESTONEDOJI"
             | "FALLING_WINDOW"
+            | "NEW_LOW"
             | "GAPPING_DOWN_DOJI"
             | "BLACK_MARUBOZU"
             | "OPENING_BLACK_MARUBOZU"
@@ -401,11 +558,14 @@ impl PatternId {
             | "MEETING_LINES_BEARISH"
             | "DOWNSIDE_TASUKI_GAP"
             | "DOWNSIDE_GAP_THREE_METHODS"
+            | "TOWER_TOP"
             | "TWO_BLACK_GAPPING"
             | "SHOOTING_STAR_2_LINES"
             | "COLLAPSING_DOJI_STAR"
             | "DELIBERATION"
-            | "CDL_TWEEZERTOP" => Some(Direction::Bearish),
+            | "CDL_TWEEZERTOP"
+            | "CDL_HARAMICROSS_BEAR"
+            | "FALLING_THREE_METHODS" => Some(Direction::Bearish),
             // Neutral patterns
             "CDL_DOJI"
             | "CDL_LONGLEGGEDDOJI"
@@ -418,9 +578,11 @@ impl PatternId {
             | "CDL_SHORTLINE"
             | "PRICE_LINES"
             | "NORTHERN_DOJI"
-            | "SOUTHERN_DOJI" => Some(Direction::Neutral),
+            | "SOUTHERN_DOJI"
+            | "CONSOLIDATION" => Some(Direction::Neutral),
             // Bidirectional patterns (return None)
             "CDL_ENGULFING"
+            | "CDL_ENGULFING_PENDING"
             | "CDL_3INSIDE"
             | "CDL_3OUTSIDE"
             | "CDL_3LINESTRIKE"
@@ -428,6 +590,9 @@ impl PatternId {
             | "CDL_HARAMICROSS"
             | "CDL_KICKING"
             | "CDL_KICKINGBYLENGTH"
+            | "KICKER"
+            | "CONFIRMED_WINDOW"
+            | "THREE_GAPS"
             | "CDL_BELTHOLD"
             | "CDL_COUNTERATTACK"
             | "CDL_SEPARATINGLINES"
@@ -438,6 +603,7 @@ impl PatternId {
             | "CDL_XSIDEGAP3METHODS"
             | "CDL_HIKKAKE"
             | "CDL_HIKKAKEMOD"
+            | "HIKKAKE_CONFIRMED"
             | "CDL_TRISTAR"
             | "CDL_DOJISTAR" => None,
             // Default to None for unknown patterns
@@ -451,6 +617,29 @@ impl PatternId {
         self.typical_direction().is_none()
     }
 
+    /// Returns true if this is a bidirectional reversal pattern whose meaning
+    /// is set by the trend it appears in (engulfing, harami, stars, ...).
+    ///
+    /// Continuation patterns, momentum patterns such as kicking, and unknown
+    /// ids are not reversals.
+    pub fn is_trend_reversal(&self) -> bool {
+        matches!(
+            self.0,
+            "CDL_ENGULFING"
+                | "CDL_ENGULFING_PENDING"
+                | "CDL_3INSIDE"
+                | "CDL_3OUTSIDE"
+                | "CDL_HARAMI"
+                | "CDL_HARAMICROSS"
+                | "CDL_BELTHOLD"
+                | "CDL_COUNTERATTACK"
+                | "CDL_BREAKAWAY"
+                | "CDL_ABANDONEDBABY"
+                | "CDL_DOJISTAR"
+                | "CDL_TRISTAR"
+        )
+    }
+
     /// Returns true if this pattern typically signals bullish moves
     pub fn is_typically_bullish(&self) -> bool {
         matches!(self.typical_direction(), Some(Direction::Bullish))
@@ -485,6 +674,26 @@ impl Direction {
     pub fn is_bearish(self) -> bool {
         matches!(self, Direction::Bearish)
     }
+
+    /// Lowercase name: `"bullish"`, `"neutral"` or `"bearish"`
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Direction::Bullish => "bullish",
+            Direction::Neutral => "neutral",
+            Direction::Bearish => "bearish",
+        }
+    }
+
+    /// `1.0` for bullish, `-1.0` for bearish, `0.0` for neutral
+    #[inline]
+    pub fn sign(self) -> f64 {
+        match self {
+            Direction::Bullish => 1.0,
+            Direction::Neutral => 0.0,
+            Direction::Bearish => -1.0,
+        }
+    }
 }
 
 /// Result of pattern detection - Copy, no allocations
@@ -492,10 +701,96 @@ impl Direction {
 pub struct PatternMatch {
     pub pattern_id: PatternId,
     pub direction: Direction,
-    /// Quality/confidence score 0.0..=1.0
+    /// Quality/confidence score 0.0..=1.0; engine output is always finite
+    /// and clamped into this range
     pub strength: f64,
     pub start_index: usize,
     pub end_index: usize,
+    /// Pattern-specific measurement, such as the run length of
+    /// `PRICE_LINES`; `None` for patterns that report none
+    pub metric: Option<f64>,
+}
+
+impl PatternMatch {
+    /// Factor applied when the match agrees with the pattern's typical direction
+    const AGREEMENT_BOOST: f64 = 1.1;
+    /// Factor applied when it contradicts the typical direction
+    const DISAGREEMENT_DAMP: f64 = 0.9;
+
+    /// Strength adjusted by agreement with `PatternId::typical_direction`.
+    ///
+    /// Boosted by 10% when the match direction equals the typical one, damped
+    /// by 10% when it contradicts it, and unchanged for bidirectional or
+    /// neutral patterns. Clamped to 0..=1.
+    pub fn adjusted_strength(&self) -> f64 {
+        let factor = match self.pattern_id.typical_direction() {
+            None | Some(Direction::Neutral) => 1.0,
+            Some(d) if d == self.direction => Self::AGREEMENT_BOOST,
+            Some(_) => Self::DISAGREEMENT_DAMP,
+        };
+        detectors::helpers::clamp_strength(self.strength * factor)
+    }
+
+    /// Bar within the match holding its protective extreme, for stop placement.
+    ///
+    /// The bar with the lowest low for bullish matches (a hammer, the star of
+    /// a morning star), the highest high for bearish ones; ties go to the
+    /// earlier bar. Neutral matches, and spans outside `bars`, use
+    /// `end_index`.
+    pub fn key_index<T: OHLCV>(&self, bars: &[T]) -> usize {
+        let Some(span) = bars.get(self.start_index..=self.end_index) else {
+            return self.end_index;
+        };
+        let lowest = match self.direction {
+            Direction::Bullish => true,
+            Direction::Bearish => false,
+            Direction::Neutral => return self.end_index,
+        };
+        let key = |bar: &T| if lowest { -bar.low() } else { bar.high() };
+        let mut best = 0;
+        for (offset, bar) in span.iter().enumerate().skip(1) {
+            if key(bar) > key(&span[best]) {
+                best = offset;
+            }
+        }
+        self.start_index + best
+    }
+
+    /// Flat single-line form for logging, same as `Display`:
+    /// `"CDL_HAMMER bullish strength=0.72 bars=18..18"`
+    pub fn to_log_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::fmt::Display for PatternMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} strength={:.2} bars={}..{}",
+            self.pattern_id.as_str(),
+            self.direction.as_str(),
+            self.strength,
+            self.start_index,
+            self.end_index
+        )
+    }
+}
+
+/// Speculative match from `PatternEngine::scan_forming`
+#[derive(Debug, Clone, Copy)]
+pub struct FormingMatch {
+    pub m: PatternMatch,
+    /// Fraction of the pattern's bars already formed, in `0.0..1.0`
+    pub completeness: f64,
+}
+
+/// Match tagged with a per-scan event id, see `PatternEngine::scan_events`
+#[derive(Debug, Clone, Copy)]
+pub struct DetectedEvent {
+    /// Increases by one per emitted match, starting at 0 for each scan
+    pub id: u64,
+    pub m: PatternMatch,
 }
 
 // ============================================================
@@ -525,6 +820,52 @@ impl Trend {
     }
 }
 
+/// How bars with `close == open` are colored by the builtin detectors
+///
+/// Every builtin detector reads candle color through `ctx.color`, so the
+/// convention applies to all of them alike. Under the default `TaLib`
+/// convention a flat bar is white, also in detectors that used to require a
+/// strictly bullish candle; select `Strict` to get the old behavior back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ColorConvention {
+    /// TA-Lib `TA_CANDLECOLOR`: `close >= open` is white, so a flat bar is white
+    #[default]
+    TaLib,
+    /// `close > open` is white, `close < open` is black, a flat bar is neither
+    Strict,
+}
+
+impl ColorConvention {
+    /// White (bullish) candle
+    #[inline]
+    pub fn is_white<T: OHLCV + ?Sized>(self, bar: &T) -> bool {
+        self.sign(bar) > 0
+    }
+
+    /// Black (bearish) candle
+    #[inline]
+    pub fn is_black<T: OHLCV + ?Sized>(self, bar: &T) -> bool {
+        self.sign(bar) < 0
+    }
+
+    /// `1` for white, `-1` for black, `0` for a flat bar under `Strict`
+    #[inline]
+    pub fn sign<T: OHLCV + ?Sized>(self, bar: &T) -> i32 {
+        match self {
+            ColorConvention::TaLib => i32::from(detectors::helpers::candle_color(bar)),
+            ColorConvention::Strict => {
+                if bar.close() > bar.open() {
+                    1
+                } else if bar.close() < bar.open() {
+                    -1
+                } else {
+                    0
+                }
+            }
+        }
+    }
+}
+
 /// Market context at a specific bar (TA-Lib compatible)
 #[derive(Debug, Clone, Copy, Default)]
 pub struct MarketContext {
@@ -545,20 +886,111 @@ pub struct MarketContext {
     /// Average range (high - low) over 5-bar trailing period.
     /// TA-Lib uses Period=5 for Near, Far, and Equal candle settings.
     pub avg_range_5: f64,
+    /// Candle color convention. The engine overwrites it with
+    /// `EngineConfig::color_convention` before detection, so providers can
+    /// leave the default.
+    pub color: ColorConvention,
 }
 
 /// Provider of market context - precomputes context for all bars
 pub trait ContextProvider: Send + Sync {
     fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext>;
+
+    /// Trailing bars, including the current one, that a context is computed
+    /// from, or `None` (the default) if unknown or unbounded, e.g. when state
+    /// is carried from every earlier bar. Sizes the window of
+    /// [`streaming::scan_streaming`] for providers without
+    /// [`incremental`](Self::incremental) support, which it rejects when this
+    /// returns `None`.
+    fn lookback(&self) -> Option<usize> {
+        None
+    }
+
+    /// Running state that yields the same contexts as `compute_all`, one bar
+    /// at a time, or `None` (the default) if the provider only works on whole
+    /// series. Lets [`streaming::scan_streaming`] compute each context once
+    /// as its bar arrives instead of recomputing a window of contexts.
+    fn incremental(&self) -> Option<Box<dyn IncrementalContext + '_>> {
+        None
+    }
+}
+
+/// Context computation advanced one bar at a time, see
+/// [`ContextProvider::incremental`]
+pub trait IncrementalContext: Send {
+    /// Context of `bar`, the next bar of the series
+    fn push(&mut self, bar: &dyn OHLCV) -> MarketContext;
+}
+
+/// How `DefaultContextProvider` derives the trend over `trend_period` bars
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TrendMethod {
+    /// Relative change between the first and the last close of the window
+    #[default]
+    EndpointChange,
+    /// Least-squares slope of the closes, projected over the window and taken
+    /// relative to the mean close. Robust to a single outlier at either end.
+    LinearRegression,
+}
+
+/// How `DefaultContextProvider` averages the candle fields over
+/// `candle_period` bars
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum AverageMethod {
+    /// Simple mean of the trailing window (TA-Lib)
+    #[default]
+    Sma,
+    /// Wilder's smoothing, as used by ATR: seeded with the simple mean of the
+    /// first `candle_period` bars, then `avg += (x - avg) / candle_period`
+    Wilder,
+}
+
+/// How `DefaultContextProvider` fills the `MarketContext` candle averages of
+/// the first bars, before a full trailing window is available
+///
+/// Only detectors that read these context averages are affected. Many TA-Lib
+/// compatible detectors compute their own per-candle trailing averages with
+/// `detectors::helpers::trailing_avg_*`, which always average the partial
+/// window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum WarmupPolicy {
+    /// Average whatever bars precede the current one (bar 0 uses itself)
+    #[default]
+    UsePartial,
+    /// Use the first full window for every earlier bar. Looks ahead, so
+    /// early thresholds match the settled ones instead of a handful of bars.
+    ClampToFirstFull,
+    /// Leave the averages NaN; threshold checks against a context average
+    /// then fail, so detectors relying on one skip the warmup bars
+    Nan,
 }
 
 /// Default context provider using simple moving averages (TA-Lib compatible)
-#[derive(Debug, Clone)]
+///
+/// Start from `Default::default()` and adjust it with the `with_*` methods;
+/// fields may be added in later releases.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
 pub struct DefaultContextProvider {
     pub trend_period: Period,
     pub volume_period: Period,
     /// TA-Lib uses 10 bars for candle averaging (TA_CANDLEAVGPERIOD)
     pub candle_period: Period,
+    #[serde(default)]
+    pub trend_method: TrendMethod,
+    /// Averaging of `avg_body`, `avg_upper_shadow`, `avg_lower_shadow`,
+    /// `avg_range` and `avg_shadow`
+    #[serde(default)]
+    pub average_method: AverageMethod,
+    /// Bars a new trend must persist before `MarketContext::trend` switches
+    /// to it; until then the previous confirmed trend is kept. 0 and 1 both
+    /// disable smoothing.
+    #[serde(default = "default_trend_confirm_bars")]
+    pub trend_confirm_bars: usize,
+    /// Candle averages for bars without a full trailing window: the first
+    /// `candle_period` bars, and the first 5 for `avg_range_5`
+    #[serde(default)]
+    pub warmup_policy: WarmupPolicy,
 }
 
 impl Default for DefaultContextProvider {
@@ -567,84 +999,305 @@ impl Default for DefaultContextProvider {
             trend_period: Period::new_const(14),
             volume_period: Period::new_const(20),
             candle_period: Period::new_const(10), // TA-Lib default
+            trend_method: TrendMethod::EndpointChange,
+            average_method: AverageMethod::Sma,
+            trend_confirm_bars: default_trend_confirm_bars(),
+            warmup_policy: WarmupPolicy::UsePartial,
         }
     }
 }
 
+fn default_trend_confirm_bars() -> usize {
+    1
+}
+
 impl ContextProvider for DefaultContextProvider {
     fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
-        let len = bars.len();
-        let mut contexts = Vec::with_capacity(len);
-
-        for i in 0..len {
-            // TA-Lib compatible: trailing average over bars BEFORE the current bar.
-            // At bar i, average is computed from bars[max(0, i-period)..i] (NOT including bar i).
-            // This matches TA-Lib's rolling sum which updates AFTER the pattern check.
-            let candle_period = self.candle_period.get();
-
-            let (avg_body, avg_upper_shadow, avg_lower_shadow, avg_range, avg_shadow) = if i == 0 {
-                // No trailing bars available; use current bar as fallback
-                let bar = &bars[0];
-                let body = bar.body();
-                let upper = bar.upper_shadow();
-                let lower = bar.lower_shadow();
-                let range = bar.range();
-                (body, upper, lower, range, (upper + lower) / 2.0)
-            } else {
-                let trail_start = i.saturating_sub(candle_period);
-                let trail_slice = &bars[trail_start..i]; // exclude bar i
-                let trail_count = trail_slice.len() as f64;
+        let mut contexts: Vec<MarketContext> = (0..bars.len())
+            .map(|i| self.window_context(bars, i))
+            .collect();
+        self.apply_sequential(bars, &mut contexts);
+        contexts
+    }
 
-                let (sum_body, sum_upper, sum_lower, sum_range, sum_shadow) = trail_slice
-                    .iter()
-                    .fold((0.0, 0.0, 0.0, 0.0, 0.0), |(b, u, l, r, s), bar| {
-                        let upper = bar.upper_shadow();
-                        let lower = bar.lower_shadow();
-                        (
-                            b + bar.body(),
-                            u + upper,
-                            l + lower,
-                            r + bar.range(),
-                            s + upper + lower,
-                        )
-                    });
+    /// The longest trailing window, or `None` when trend confirmation or
+    /// Wilder smoothing carry state from every earlier bar, or when
+    /// `WarmupPolicy::ClampToFirstFull` looks ahead.
+    fn lookback(&self) -> Option<usize> {
+        let stateful = self.trend_confirm_bars > 1
+            || self.average_method == AverageMethod::Wilder
+            || self.warmup_policy == WarmupPolicy::ClampToFirstFull;
+        if stateful {
+            return None;
+        }
+        Some(
+            (self.trend_period.get() + 1)
+                .max(self.volume_period.get())
+                .max(self.candle_period.get()),
+        )
+    }
 
-                (
-                    sum_body / trail_count,
-                    sum_upper / trail_count,
-                    sum_lower / trail_count,
-                    sum_range / trail_count,
-                    sum_shadow / trail_count / 2.0,
-                )
+    /// Available for every configuration except
+    /// `WarmupPolicy::ClampToFirstFull`, which looks ahead. Trend
+    /// confirmation and Wilder smoothing carry their state from bar to bar.
+    fn incremental(&self) -> Option<Box<dyn IncrementalContext + '_>> {
+        if self.warmup_policy == WarmupPolicy::ClampToFirstFull {
+            return None;
+        }
+        // Bars up to the longest period before the current one: every
+        // trailing window fits, so a full buffer takes the same branches as
+        // the whole series would
+        let capacity = self
+            .trend_period
+            .get()
+            .max(self.volume_period.get())
+            .max(self.candle_period.get())
+            .max(5)
+            + 1;
+        Some(Box::new(DefaultIncremental {
+            provider: self,
+            bars: std::collections::VecDeque::with_capacity(capacity + 1),
+            capacity,
+            index: 0,
+            confirmation: TrendConfirmation::default(),
+            previous: None,
+        }))
+    }
+}
+
+/// Trend confirmation state carried from bar to bar
+#[derive(Debug, Clone, Copy, Default)]
+struct TrendConfirmation {
+    confirmed: Trend,
+    last_raw: Trend,
+    run: usize,
+}
+
+/// [`IncrementalContext`] of [`DefaultContextProvider`]: the trailing bars
+/// its windows need, plus the state of the sequential pass
+struct DefaultIncremental<'a> {
+    provider: &'a DefaultContextProvider,
+    bars: std::collections::VecDeque<resample::OwnedBar>,
+    capacity: usize,
+    /// Position of the next bar in the series
+    index: usize,
+    confirmation: TrendConfirmation,
+    previous: Option<MarketContext>,
+}
+
+impl IncrementalContext for DefaultIncremental<'_> {
+    fn push(&mut self, bar: &dyn OHLCV) -> MarketContext {
+        self.bars.push_back(resample::OwnedBar::from_ohlcv(bar));
+        if self.bars.len() > self.capacity {
+            self.bars.pop_front();
+        }
+        let window = self.bars.make_contiguous();
+        let last = window.len() - 1;
+
+        let mut ctx = self.provider.window_context(window, last);
+        let previous = last
+            .checked_sub(1)
+            .and_then(|p| Some((&window[p], self.previous.as_ref()?)));
+        self.provider
+            .advance(&mut self.confirmation, self.index, previous, &mut ctx);
+
+        self.index += 1;
+        self.previous = Some(ctx);
+        ctx
+    }
+}
+
+impl DefaultContextProvider {
+    /// Set the trend lookback
+    pub fn with_trend_period(mut self, period: Period) -> Self {
+        self.trend_period = period;
+        self
+    }
+
+    /// Set the volume averaging period
+    pub fn with_volume_period(mut self, period: Period) -> Self {
+        self.volume_period = period;
+        self
+    }
+
+    /// Set the candle averaging period
+    pub fn with_candle_period(mut self, period: Period) -> Self {
+        self.candle_period = period;
+        self
+    }
+
+    /// Set how the trend is derived
+    pub fn with_trend_method(mut self, method: TrendMethod) -> Self {
+        self.trend_method = method;
+        self
+    }
+
+    /// Set how the candle averages are computed
+    pub fn with_average_method(mut self, method: AverageMethod) -> Self {
+        self.average_method = method;
+        self
+    }
+
+    /// Set the bars a new trend must persist
+    pub fn with_trend_confirm_bars(mut self, bars: usize) -> Self {
+        self.trend_confirm_bars = bars;
+        self
+    }
+
+    /// Set the warmup policy
+    pub fn with_warmup_policy(mut self, policy: WarmupPolicy) -> Self {
+        self.warmup_policy = policy;
+        self
+    }
+
+    /// Same result as `compute_all`, with the per-bar trailing windows
+    /// computed on the rayon thread pool.
+    ///
+    /// Only trend confirmation and Wilder smoothing, which depend on the
+    /// previous bar, run serially afterwards. Worth it for long series
+    /// (millions of bars); `compute_all` itself cannot parallelize because
+    /// `ContextProvider` does not require `T: Sync`. Engines using this
+    /// provider expose it as `PatternEngine::compute_contexts_parallel` and
+    /// `PatternEngine::scan_parallel_contexts`.
+    pub fn compute_all_parallel<T: OHLCV + Sync>(&self, bars: &[T]) -> Vec<MarketContext> {
+        let mut contexts: Vec<MarketContext> = (0..bars.len())
+            .into_par_iter()
+            .map(|i| self.window_context(bars, i))
+            .collect();
+        self.apply_sequential(bars, &mut contexts);
+        contexts
+    }
+
+    /// Context at `index` from its trailing windows only: SMA candle
+    /// averages and the unconfirmed trend
+    fn window_context<T: OHLCV>(&self, bars: &[T], i: usize) -> MarketContext {
+        // TA-Lib compatible: trailing average over bars BEFORE the current bar.
+        // At bar i, average is computed from bars[max(0, i-period)..i] (NOT including bar i).
+        // This matches TA-Lib's rolling sum which updates AFTER the pattern check.
+        let candle_period = self.candle_period.get();
+
+        let (avg_body, avg_upper_shadow, avg_lower_shadow, avg_range, avg_shadow) =
+            match self.trailing_window(bars.len(), i, candle_period) {
+                None => (f64::NAN, f64::NAN, f64::NAN, f64::NAN, f64::NAN),
+                Some(window) => {
+                    let trail_slice = &bars[window];
+                    let trail_count = trail_slice.len() as f64;
+
+                    let (sum_body, sum_upper, sum_lower, sum_range, sum_shadow) = trail_slice
+                        .iter()
+                        .fold((0.0, 0.0, 0.0, 0.0, 0.0), |(b, u, l, r, s), bar| {
+                            let upper = bar.upper_shadow();
+                            let lower = bar.lower_shadow();
+                            (
+                                b + bar.body(),
+                                u + upper,
+                                l + lower,
+                                r + bar.range(),
+                                s + upper + lower,
+                            )
+                        });
+
+                    (
+                        sum_body / trail_count,
+                        sum_upper / trail_count,
+                        sum_lower / trail_count,
+                        sum_range / trail_count,
+                        sum_shadow / trail_count / 2.0,
+                    )
+                }
             };
 
-            // Near/Far/Equal use Period=5
-            let avg_range_5 = if i == 0 {
-                bars[0].range()
-            } else {
-                let s5 = i.saturating_sub(5);
-                let slice5 = &bars[s5..i];
+        // Near/Far/Equal use Period=5
+        let avg_range_5 = match self.trailing_window(bars.len(), i, 5) {
+            None => f64::NAN,
+            Some(window) => {
+                let slice5 = &bars[window];
                 slice5.iter().map(|b| OHLCVExt::range(b)).sum::<f64>() / slice5.len() as f64
-            };
+            }
+        };
 
-            contexts.push(MarketContext {
-                trend: self.compute_trend(bars, i),
-                volatility: self.compute_volatility(bars, i),
-                avg_volume: self.compute_avg_volume(bars, i),
-                avg_body,
-                avg_upper_shadow,
-                avg_lower_shadow,
-                avg_range,
-                avg_shadow,
-                avg_range_5,
-            });
+        MarketContext {
+            trend: self.compute_trend(bars, i),
+            volatility: self.compute_volatility(bars, i),
+            avg_volume: self.compute_avg_volume(bars, i),
+            avg_body,
+            avg_upper_shadow,
+            avg_lower_shadow,
+            avg_range,
+            avg_shadow,
+            avg_range_5,
+            color: ColorConvention::default(),
         }
+    }
 
-        contexts
+    /// Bars averaged for the context at `i` over `period` trailing bars, or
+    /// `None` for NaN averages
+    fn trailing_window(
+        &self,
+        len: usize,
+        i: usize,
+        period: usize,
+    ) -> Option<std::ops::Range<usize>> {
+        if i >= period {
+            return Some(i - period..i); // exclude bar i
+        }
+        match self.warmup_policy {
+            // No trailing bars available at bar 0; use current bar as fallback
+            WarmupPolicy::UsePartial => Some(if i == 0 { 0..1 } else { 0..i }),
+            WarmupPolicy::ClampToFirstFull => Some(0..period.min(len)),
+            WarmupPolicy::Nan => None,
+        }
+    }
+
+    /// In-order pass over `window_context` results for the fields that depend
+    /// on the previous bar: trend confirmation and Wilder smoothing
+    fn apply_sequential<T: OHLCV>(&self, bars: &[T], contexts: &mut [MarketContext]) {
+        let mut confirmation = TrendConfirmation::default();
+        for i in 0..contexts.len() {
+            let (done, rest) = contexts.split_at_mut(i);
+            let previous = i.checked_sub(1).map(|p| (&bars[p], &done[p]));
+            self.advance(&mut confirmation, i, previous, &mut rest[0]);
+        }
+    }
+
+    /// One step of the sequential pass for the `window_context` result of bar
+    /// `i`, given the previous bar and its final context
+    fn advance<T: OHLCV>(
+        &self,
+        confirmation: &mut TrendConfirmation,
+        i: usize,
+        previous: Option<(&T, &MarketContext)>,
+        ctx: &mut MarketContext,
+    ) {
+        let raw = ctx.trend;
+        confirmation.run = if i > 0 && raw == confirmation.last_raw {
+            confirmation.run + 1
+        } else {
+            1
+        };
+        confirmation.last_raw = raw;
+        if confirmation.run >= self.trend_confirm_bars {
+            confirmation.confirmed = raw;
+        }
+        ctx.trend = confirmation.confirmed;
+
+        let candle_period = self.candle_period.get();
+        if self.average_method == AverageMethod::Wilder && i > candle_period {
+            // Wilder update with the bar just before i
+            let Some((bar, prev)) = previous else {
+                return;
+            };
+            let upper = bar.upper_shadow();
+            let lower = bar.lower_shadow();
+            let n = candle_period as f64;
+            ctx.avg_body = prev.avg_body + (bar.body() - prev.avg_body) / n;
+            ctx.avg_upper_shadow = prev.avg_upper_shadow + (upper - prev.avg_upper_shadow) / n;
+            ctx.avg_lower_shadow = prev.avg_lower_shadow + (lower - prev.avg_lower_shadow) / n;
+            ctx.avg_range = prev.avg_range + (bar.range() - prev.avg_range) / n;
+            ctx.avg_shadow = prev.avg_shadow + ((upper + lower) / 2.0 - prev.avg_shadow) / n;
+        }
     }
-}
 
-impl DefaultContextProvider {
     fn compute_trend<T: OHLCV>(&self, bars: &[T], index: usize) -> Trend {
         let period = self.trend_period.get();
         if index < period {
@@ -652,14 +1305,39 @@ impl DefaultContextProvider {
         }
 
         let start = index.saturating_sub(period);
-        let first_close = bars[start].close();
-        let last_close = bars[index].close();
+        let change = match self.trend_method {
+            TrendMethod::EndpointChange => {
+                let first_close = bars[start].close();
+                let last_close = bars[index].close();
 
-        if first_close <= f64::EPSILON {
-            return Trend::Sideways;
-        }
+                if first_close <= f64::EPSILON {
+                    return Trend::Sideways;
+                }
+
+                (last_close - first_close) / first_close
+            }
+            TrendMethod::LinearRegression => {
+                let window = &bars[start..=index];
+                let n = window.len() as f64;
+                let mean_x = (n - 1.0) / 2.0;
+                let mean_y = window.iter().map(|b| b.close()).sum::<f64>() / n;
+
+                if mean_y <= f64::EPSILON {
+                    return Trend::Sideways;
+                }
+
+                let (cov, var) = window
+                    .iter()
+                    .enumerate()
+                    .fold((0.0, 0.0), |(c, v), (x, b)| {
+                        let dx = x as f64 - mean_x;
+                        (c + dx * (b.close() - mean_y), v + dx * dx)
+                    });
+                let slope = cov / var;
 
-        let change = (last_close - first_close) / first_close;
+                slope * period as f64 / mean_y
+            }
+        };
 
         match change {
             c if c > 0.05 => Trend::StrongUp,
@@ -694,65 +1372,245 @@ impl DefaultContextProvider {
     }
 }
 
-// ============================================================
-// PATTERN DETECTOR TRAITS
-// ============================================================
-
-/// Category of pattern by number of bars
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PatternCategory {
-    SingleBar,
-    TwoBar,
-    ThreeBar,
-    MultiBar,
+/// Context provider wrapper that memoizes the last computed contexts.
+///
+/// The cache is keyed by a hash of the bars' OHLCV values and their count, so
+/// rescanning the same series (e.g. with different filters) reuses the
+/// contexts instead of recomputing them, while a series edited in place or a
+/// different series at a reused address misses the cache. Hashing is a single
+/// pass over the bars, much cheaper than computing the contexts. The lock is
+/// only held to read or replace the cached entry, so parallel scans compute
+/// concurrently.
+#[derive(Debug, Default)]
+pub struct CachingContextProvider<C: ContextProvider> {
+    inner: C,
+    cache: std::sync::Mutex<Option<(u64, Vec<MarketContext>)>>,
 }
 
-/// Additional metadata about a pattern
-#[derive(Debug, Clone)]
-pub struct PatternMetadata {
-    pub name: &'static str,
-    pub description: &'static str,
-    pub category: PatternCategory,
-}
+impl<C: ContextProvider> CachingContextProvider<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            cache: std::sync::Mutex::new(None),
+        }
+    }
 
-/// Generic pattern detector trait - for concrete types
-pub trait PatternDetector: Send + Sync {
-    fn id(&self) -> PatternId;
-    fn min_bars(&self) -> usize;
-    fn detect<T: OHLCV>(
-        &self,
-        bars: &[T],
-        index: usize,
-        ctx: &MarketContext,
-    ) -> Option<PatternMatch>;
+    /// The wrapped provider
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
 
-    fn validate_config(&self) -> Result<()> {
-        Ok(())
+    /// Drop the cached contexts
+    pub fn clear(&self) {
+        *self.lock() = None;
     }
 
-    fn metadata(&self) -> PatternMetadata {
-        PatternMetadata {
-            name: self.id().0,
-            description: "",
-            category: match self.min_bars() {
-                1 => PatternCategory::SingleBar,
-                2 => PatternCategory::TwoBar,
-                3 => PatternCategory::ThreeBar,
-                _ => PatternCategory::MultiBar,
-            },
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<(u64, Vec<MarketContext>)>> {
+        match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Cache key: hash of every bar's OHLCV bits and the bar count
+    fn key<T: OHLCV>(bars: &[T]) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        bars.len().hash(&mut hasher);
+        for bar in bars {
+            for value in [bar.open(), bar.high(), bar.low(), bar.close(), bar.volume()] {
+                value.to_bits().hash(&mut hasher);
+            }
         }
+        hasher.finish()
     }
 }
 
-/// Object-safe pattern detector trait - for custom detectors
-pub trait DynPatternDetector: Send + Sync {
-    fn id(&self) -> PatternId;
-    fn min_bars(&self) -> usize;
-    fn detect(
-        &self,
-        bars: &[&dyn OHLCV],
-        index: usize,
-        ctx: &MarketContext,
+impl<C: ContextProvider> ContextProvider for CachingContextProvider<C> {
+    fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
+        let key = Self::key(bars);
+        if let Some((cached, contexts)) = self.lock().as_ref() {
+            if *cached == key {
+                return contexts.clone();
+            }
+        }
+
+        let contexts = self.inner.compute_all(bars);
+        *self.lock() = Some((key, contexts.clone()));
+        contexts
+    }
+
+    fn lookback(&self) -> Option<usize> {
+        self.inner.lookback()
+    }
+
+    fn incremental(&self) -> Option<Box<dyn IncrementalContext + '_>> {
+        self.inner.incremental()
+    }
+}
+
+/// Context provider combining two others.
+///
+/// The candle and volume averages come from `base`; `trend` and `volatility`
+/// are taken from `regime`. Lets a custom trend source (e.g. an EMA slope) be
+/// plugged in without reimplementing the TA-Lib averaging.
+#[derive(Debug, Clone, Default)]
+pub struct CompositeContextProvider<B: ContextProvider, R: ContextProvider> {
+    base: B,
+    regime: R,
+}
+
+impl<B: ContextProvider, R: ContextProvider> CompositeContextProvider<B, R> {
+    pub fn new(base: B, regime: R) -> Self {
+        Self { base, regime }
+    }
+
+    /// Provider of the averaging fields
+    pub fn base(&self) -> &B {
+        &self.base
+    }
+
+    /// Provider of `trend` and `volatility`
+    pub fn regime(&self) -> &R {
+        &self.regime
+    }
+}
+
+impl<B: ContextProvider, R: ContextProvider> ContextProvider for CompositeContextProvider<B, R> {
+    fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
+        let mut contexts = self.base.compute_all(bars);
+        let regime = self.regime.compute_all(bars);
+        for (ctx, reg) in contexts.iter_mut().zip(&regime) {
+            ctx.trend = reg.trend;
+            ctx.volatility = reg.volatility;
+        }
+        contexts
+    }
+
+    fn lookback(&self) -> Option<usize> {
+        Some(self.base.lookback()?.max(self.regime.lookback()?))
+    }
+
+    fn incremental(&self) -> Option<Box<dyn IncrementalContext + '_>> {
+        Some(Box::new(CompositeIncremental {
+            base: self.base.incremental()?,
+            regime: self.regime.incremental()?,
+        }))
+    }
+}
+
+/// [`IncrementalContext`] of [`CompositeContextProvider`]
+struct CompositeIncremental<'a> {
+    base: Box<dyn IncrementalContext + 'a>,
+    regime: Box<dyn IncrementalContext + 'a>,
+}
+
+impl IncrementalContext for CompositeIncremental<'_> {
+    fn push(&mut self, bar: &dyn OHLCV) -> MarketContext {
+        let mut ctx = self.base.push(bar);
+        let regime = self.regime.push(bar);
+        ctx.trend = regime.trend;
+        ctx.volatility = regime.volatility;
+        ctx
+    }
+}
+
+/// Summarize the trend of a whole series.
+///
+/// Takes the per-bar trends from `provider` and ignores sideways bars. The
+/// series is up if more bars trend up than down (and vice versa); the
+/// strong/weak variant is whichever is more common on the winning side.
+/// Returns `Trend::Sideways` when neither side wins.
+pub fn overall_trend<T: OHLCV>(bars: &[T], provider: &impl ContextProvider) -> Trend {
+    let mut counts = [0usize; 4];
+    for ctx in provider.compute_all(bars) {
+        match ctx.trend {
+            Trend::StrongUp => counts[0] += 1,
+            Trend::WeakUp => counts[1] += 1,
+            Trend::WeakDown => counts[2] += 1,
+            Trend::StrongDown => counts[3] += 1,
+            Trend::Sideways => {}
+        }
+    }
+
+    let up = counts[0] + counts[1];
+    let down = counts[2] + counts[3];
+    if up > down {
+        if counts[0] >= counts[1] {
+            Trend::StrongUp
+        } else {
+            Trend::WeakUp
+        }
+    } else if down > up {
+        if counts[3] >= counts[2] {
+            Trend::StrongDown
+        } else {
+            Trend::WeakDown
+        }
+    } else {
+        Trend::Sideways
+    }
+}
+
+// ============================================================
+// PATTERN DETECTOR TRAITS
+// ============================================================
+
+/// Category of pattern by number of bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternCategory {
+    SingleBar,
+    TwoBar,
+    ThreeBar,
+    MultiBar,
+}
+
+/// Additional metadata about a pattern
+#[derive(Debug, Clone)]
+pub struct PatternMetadata {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub category: PatternCategory,
+}
+
+/// Generic pattern detector trait - for concrete types
+pub trait PatternDetector: Send + Sync {
+    fn id(&self) -> PatternId;
+    fn min_bars(&self) -> usize;
+    fn detect<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<PatternMatch>;
+
+    fn validate_config(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn metadata(&self) -> PatternMetadata {
+        PatternMetadata {
+            name: self.id().0,
+            description: "",
+            category: match self.min_bars() {
+                1 => PatternCategory::SingleBar,
+                2 => PatternCategory::TwoBar,
+                3 => PatternCategory::ThreeBar,
+                _ => PatternCategory::MultiBar,
+            },
+        }
+    }
+}
+
+/// Object-safe pattern detector trait - for custom detectors
+pub trait DynPatternDetector: Send + Sync {
+    fn id(&self) -> PatternId;
+    fn min_bars(&self) -> usize;
+    fn detect(
+        &self,
+        bars: &[&dyn OHLCV],
+        index: usize,
+        ctx: &MarketContext,
     ) -> Option<PatternMatch>;
     fn validate_config(&self) -> Result<()>;
 }
@@ -780,6 +1638,169 @@ impl<D: PatternDetector> DynPatternDetector for D {
     }
 }
 
+/// Diagnostic wrapper asserting that a custom detector's `min_bars()` covers
+/// the bars it actually reads.
+///
+/// In debug builds, every `detect` call records the lowest bar position whose
+/// prices the inner detector read and `debug_assert!`s that the span from
+/// that bar to `index` is no longer than `min_bars()`. Such a detector would
+/// otherwise be called too early by the engine and return `None` or panic on
+/// the first bars. Reads made by `helpers::trailing_avg_*` are not counted:
+/// trailing averages deliberately look past the pattern window and fall back
+/// to shorter history near the start of the series.
+///
+/// The inner detector is handed a slice of proxy bars that record their
+/// position when read, so reads through generic [`PatternDetector`] code and
+/// direct `DynPatternDetector` implementations are both tracked. Debug builds
+/// allocate one proxy per bar on every call; release builds pass the bars
+/// through and skip the check entirely.
+pub struct CheckedDetector<D> {
+    inner: D,
+}
+
+impl<D: DynPatternDetector> CheckedDetector<D> {
+    pub fn new(inner: D) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+use std::cell::Cell;
+
+/// Bar proxy handed to the inner detector of [`CheckedDetector`]; records
+/// the lowest position read
+struct ProbedBar<'a> {
+    bar: &'a dyn OHLCV,
+    position: usize,
+    lowest: &'a Cell<usize>,
+}
+
+impl ProbedBar<'_> {
+    #[inline]
+    fn touch(&self) {
+        if self.position < self.lowest.get() && !read_probe::is_suspended() {
+            self.lowest.set(self.position);
+        }
+    }
+}
+
+impl OHLCV for ProbedBar<'_> {
+    fn open(&self) -> f64 {
+        self.touch();
+        self.bar.open()
+    }
+
+    fn high(&self) -> f64 {
+        self.touch();
+        self.bar.high()
+    }
+
+    fn low(&self) -> f64 {
+        self.touch();
+        self.bar.low()
+    }
+
+    fn close(&self) -> f64 {
+        self.touch();
+        self.bar.close()
+    }
+
+    fn volume(&self) -> f64 {
+        self.touch();
+        self.bar.volume()
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        self.touch();
+        self.bar.timestamp()
+    }
+}
+
+/// Excludes the trailing-average helpers from [`CheckedDetector`]'s read
+/// tracking.
+pub(crate) mod read_probe {
+    use std::cell::Cell;
+
+    thread_local! {
+        static SUSPENDED: Cell<bool> = const { Cell::new(false) };
+    }
+
+    /// Whether reads are currently inside [`untracked`]
+    #[inline]
+    pub(crate) fn is_suspended() -> bool {
+        SUSPENDED.with(Cell::get)
+    }
+
+    /// Restores the previous state on drop, including on unwind
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SUSPENDED.with(|cell| cell.set(self.0));
+        }
+    }
+
+    /// Runs `f` without recording reads
+    #[inline]
+    pub(crate) fn untracked<R>(f: impl FnOnce() -> R) -> R {
+        if !cfg!(debug_assertions) {
+            return f();
+        }
+        let _restore = Restore(SUSPENDED.with(|cell| cell.replace(true)));
+        f()
+    }
+}
+
+impl<D: DynPatternDetector> DynPatternDetector for CheckedDetector<D> {
+    fn id(&self) -> PatternId {
+        self.inner.id()
+    }
+
+    fn min_bars(&self) -> usize {
+        self.inner.min_bars()
+    }
+
+    fn detect(
+        &self,
+        bars: &[&dyn OHLCV],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<PatternMatch> {
+        if !cfg!(debug_assertions) {
+            return self.inner.detect(bars, index, ctx);
+        }
+        let lowest = Cell::new(index);
+        let probed: Vec<ProbedBar> = bars
+            .iter()
+            .enumerate()
+            .map(|(position, &bar)| ProbedBar {
+                bar,
+                position,
+                lowest: &lowest,
+            })
+            .collect();
+        let views: Vec<&dyn OHLCV> = probed.iter().map(|b| b as &dyn OHLCV).collect();
+        let result = self.inner.detect(&views, index, ctx);
+        let lowest = lowest.get();
+
+        let span = index.saturating_sub(lowest) + 1;
+        let min_bars = self.inner.min_bars();
+        debug_assert!(
+            span <= min_bars,
+            "{}: detect at index {index} read bar {lowest}, spanning {span} bars, but min_bars() is {min_bars}",
+            self.inner.id().as_str(),
+        );
+        result
+    }
+
+    fn validate_config(&self) -> Result<()> {
+        self.inner.validate_config()
+    }
+}
+
 // ============================================================
 // BUILTIN DETECTORS - generated via macro
 // ============================================================
@@ -794,7 +1815,7 @@ macro_rules! define_builtin_detectors {
         ),* $(,)?
     ) => {
         /// All builtin detectors - fast path via enum dispatch
-        #[derive(Debug, Clone)]
+        #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
         pub enum BuiltinDetector {
             $($variant($detector)),*
         }
@@ -831,11 +1852,54 @@ macro_rules! define_builtin_detectors {
                     $(Self::$variant(d) => PatternDetector::validate_config(d)),*
                 }
             }
+
+            pub fn metadata(&self) -> PatternMetadata {
+                match self {
+                    $(Self::$variant(d) => PatternDetector::metadata(d)),*
+                }
+            }
+
+            /// One instance of every builtin detector with default configuration
+            pub fn all_defaults() -> Vec<Self> {
+                vec![$(Self::$variant(<$detector>::default())),*]
+            }
+
+            /// Default-configured builtin detector for a pattern id, if one exists
+            pub fn from_id(id: PatternId) -> Option<Self> {
+                Self::default_for(id.as_str())
+            }
+
+            fn default_for(id: &str) -> Option<Self> {
+                $(
+                    let detector = <$detector>::default();
+                    if PatternDetector::id(&detector).as_str() == id {
+                        return Some(Self::$variant(detector));
+                    }
+                )*
+                None
+            }
         }
+
+        // Adding or removing a variant must be reflected in the public count
+        const _: () = assert!(
+            BUILTIN_PATTERN_COUNT == [$(stringify!($variant)),*].len(),
+            "BUILTIN_PATTERN_COUNT does not match the BuiltinDetector variants"
+        );
     };
 }
 
-// Apply macro - all 61 TA-Lib patterns
+/// Number of `BuiltinDetector` variants.
+///
+/// `EngineBuilder::with_all_defaults` registers all of them except the
+/// opt-in `HIKKAKE_CONFIRMED`, which would repeat the confirmation bars that
+/// `CDL_HIKKAKE` already reports.
+///
+/// Part of the stable API: it only changes together with the set of builtin
+/// patterns, in a release that documents the change. Checked at compile time
+/// against the variant list.
+pub const BUILTIN_PATTERN_COUNT: usize = 106;
+
+// Apply macro - all 106 builtin patterns: 63 TA-Lib, 40 extended, 3 price action
 define_builtin_detectors! {
     // Single bar (17)
     Doji(DojiDetector),
@@ -856,7 +1920,7 @@ define_builtin_detectors! {
     HighWave(HighWaveDetector),
     BeltHold(BeltHoldDetector),
 
-    // Two bar (17)
+    // Two bar (18)
     Engulfing(EngulfingDetector),
     Harami(HaramiDetector),
     HaramiCross(HaramiCrossDetector),
@@ -876,7 +1940,7 @@ define_builtin_detectors! {
     TweezerTop(TweezerTopDetector),
     TweezerBottom(TweezerBottomDetector),
 
-    // Three bar (22)
+    // Three bar (20)
     ThreeWhiteSoldiers(ThreeWhiteSoldiersDetector),
     ThreeBlackCrows(ThreeBlackCrowsDetector),
     ThreeInside(ThreeInsideDetector),
@@ -908,7 +1972,7 @@ define_builtin_detectors! {
     RiseFallThreeMethods(RiseFallThreeMethodsDetector),
     XSideGapThreeMethods(XSideGapThreeMethodsDetector),
 
-    // Extended patterns (30+)
+    // Extended patterns (40)
     // Price Lines
     PriceLines(PriceLinesDetector),
 
@@ -917,6 +1981,8 @@ define_builtin_detectors! {
     RisingWindow(RisingWindowDetector),
     GappingDownDoji(GappingDownDojiDetector),
     GappingUpDoji(GappingUpDojiDetector),
+    ConfirmedWindow(ConfirmedWindowDetector),
+    ThreeGaps(ThreeGapsDetector),
 
     // Reversal
     AboveTheStomach(AboveTheStomachDetector),
@@ -928,6 +1994,7 @@ define_builtin_detectors! {
     TwoBlackGapping(TwoBlackGappingDetector),
     MeetingLinesBearish(MeetingLinesBearishDetector),
     MeetingLinesBullish(MeetingLinesBullishDetector),
+    HikkakeConfirmed(HikkakeConfirmedDetector),
 
     // Doji variants
     NorthernDoji(NorthernDojiDetector),
@@ -959,736 +2026,4266 @@ define_builtin_detectors! {
     // Tasuki Gap variants
     DownsideTasukiGap(DownsideTasukiGapDetector),
     UpsideTasukiGap(UpsideTasukiGapDetector),
-}
 
-// ============================================================
-// PATTERN ENGINE
-// ============================================================
+    // Tower reversals
+    TowerTop(TowerTopDetector),
+    TowerBottom(TowerBottomDetector),
 
-/// Engine configuration
-#[derive(Debug, Clone, Default)]
-pub struct EngineConfig {
-    pub min_strength: Option<f64>,
-    pub validate_data: bool,
-    pub pattern_filter: Option<Vec<PatternId>>,
-}
+    // Rounded reversals
+    FryPanBottom(FryPanBottomDetector),
 
-/// Main pattern detection engine
-pub struct PatternEngine<C: ContextProvider = DefaultContextProvider> {
-    builtin: Vec<BuiltinDetector>,
-    custom: Vec<Box<dyn DynPatternDetector>>,
-    context_provider: C,
-    config: EngineConfig,
+    // Consolidation
+    Consolidation(ConsolidationDetector),
+
+    // Price action, trend-independent (3)
+    Kicker(KickerDetector),
+    NewHigh(NewHighDetector),
+    NewLow(NewLowDetector),
 }
 
-impl<C: ContextProvider> PatternEngine<C> {
-    pub fn new(context_provider: C) -> Self {
-        Self {
-            builtin: Vec::new(),
-            custom: Vec::new(),
-            context_provider,
-            config: EngineConfig::default(),
+/// Builds the parameterized builtin detectors through
+/// [`ParameterizedDetector::with_params`](params::ParameterizedDetector::with_params)
+macro_rules! parameterized_builtins {
+    (
+        $(
+            $variant:ident($detector:ty)
+        ),* $(,)?
+    ) => {
+        impl BuiltinDetector {
+            fn parameterized(id: &str, params: &HashMap<&str, f64>) -> Option<Result<Self>> {
+                $(
+                    if id == <$detector as params::ParameterizedDetector>::pattern_id_str() {
+                        return Some(
+                            checked_with_params::<$detector>(id, params).map(Self::$variant),
+                        );
+                    }
+                )*
+                None
+            }
         }
-    }
-
-    // ===========================================
-    // LOW-LEVEL: Primitives
-    // ===========================================
-
-    /// Precompute contexts for all bars.
-    /// User stores and reuses the result.
-    #[inline]
-    pub fn compute_contexts<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
-        self.context_provider.compute_all(bars)
-    }
-
-    /// Compute context for a single bar.
-    /// For incremental/realtime scenarios.
-    #[inline]
-    pub fn compute_context_at<T: OHLCV>(&self, bars: &[T], index: usize) -> MarketContext {
-        let contexts = self.context_provider.compute_all(bars);
-        contexts.get(index).copied().unwrap_or_default()
-    }
-
-    // ===========================================
-    // MID-LEVEL: Single-bar / Range
-    // ===========================================
+    };
+}
 
-    /// Detect patterns at a single bar index.
-    pub fn scan_at<T: OHLCV>(
-        &self,
-        bars: &[T],
-        index: usize,
-        ctx: &MarketContext,
-    ) -> Vec<PatternMatch> {
-        if self.custom.is_empty() {
-            self.scan_at_internal(bars, &[], index, ctx)
-        } else {
-            let bar_refs: Vec<&dyn OHLCV> = bars.iter().map(|b| b as &dyn OHLCV).collect();
-            self.scan_at_internal(bars, &bar_refs, index, ctx)
+parameterized_builtins! {
+    RickshawMan(RickshawManDetector),
+    ShootingStar(ShootingStarDetector),
+    Engulfing(EngulfingDetector),
+    Harami(HaramiDetector),
+    HaramiCross(HaramiCrossDetector),
+    Piercing(PiercingDetector),
+    DarkCloudCover(DarkCloudCoverDetector),
+    DojiStar(DojiStarDetector),
+    Counterattack(CounterattackDetector),
+    InNeck(InNeckDetector),
+    OnNeck(OnNeckDetector),
+    Kicking(KickingDetector),
+    KickingByLength(KickingByLengthDetector),
+    MatchingLow(MatchingLowDetector),
+    SeparatingLines(SeparatingLinesDetector),
+    GapSideSideWhite(GapSideSideWhiteDetector),
+    TweezerTop(TweezerTopDetector),
+    TweezerBottom(TweezerBottomDetector),
+    ThreeWhiteSoldiers(ThreeWhiteSoldiersDetector),
+    ThreeBlackCrows(ThreeBlackCrowsDetector),
+    MorningStar(MorningStarDetector),
+    EveningStar(EveningStarDetector),
+    MorningDojiStar(MorningDojiStarDetector),
+    EveningDojiStar(EveningDojiStarDetector),
+    AbandonedBaby(AbandonedBabyDetector),
+    IdenticalThreeCrows(IdenticalThreeCrowsDetector),
+    StickSandwich(StickSandwichDetector),
+    ConcealingBabySwallow(ConcealingBabySwallowDetector),
+    LadderBottom(LadderBottomDetector),
+    XSideGapThreeMethods(XSideGapThreeMethodsDetector),
+    GappingDownDoji(GappingDownDojiDetector),
+    GappingUpDoji(GappingUpDojiDetector),
+    ThreeGaps(ThreeGapsDetector),
+    AboveTheStomach(AboveTheStomachDetector),
+    BelowTheStomach(BelowTheStomachDetector),
+    CollapsingDojiStar(CollapsingDojiStarDetector),
+    Deliberation(DeliberationDetector),
+    LastEngulfingBottom(LastEngulfingBottomDetector),
+    LastEngulfingTop(LastEngulfingTopDetector),
+    MeetingLinesBearish(MeetingLinesBearishDetector),
+    MeetingLinesBullish(MeetingLinesBullishDetector),
+    NorthernDoji(NorthernDojiDetector),
+    SouthernDoji(SouthernDojiDetector),
+    BlackMarubozu(BlackMarubozuDetector),
+    WhiteMarubozu(WhiteMarubozuDetector),
+    OpeningBlackMarubozu(OpeningBlackMarubozuDetector),
+    OpeningWhiteMarubozu(OpeningWhiteMarubozuDetector),
+    ShortBlack(ShortBlackDetector),
+    ShortWhite(ShortWhiteDetector),
+    LongBlackDay(LongBlackDayDetector),
+    LongWhiteDay(LongWhiteDayDetector),
+    BlackSpinningTop(BlackSpinningTopDetector),
+    WhiteSpinningTop(WhiteSpinningTopDetector),
+    ShootingStar2Lines(ShootingStar2LinesDetector),
+    DownsideTasukiGap(DownsideTasukiGapDetector),
+    UpsideTasukiGap(UpsideTasukiGapDetector),
+    TowerTop(TowerTopDetector),
+    TowerBottom(TowerBottomDetector),
+    FryPanBottom(FryPanBottomDetector),
+    Consolidation(ConsolidationDetector),
+    Kicker(KickerDetector),
+    NewHigh(NewHighDetector),
+    NewLow(NewLowDetector),
+}
+
+/// `D::with_params` with every name checked against `D::param_meta()`
+fn checked_with_params<D: params::ParameterizedDetector>(
+    id: &str,
+    params: &HashMap<&str, f64>,
+) -> Result<D> {
+    for (&name, &value) in params {
+        if !D::param_meta().iter().any(|meta| meta.name == name) {
+            return Err(PatternError::InvalidConfig(format!(
+                "unknown parameter `{name}` for `{id}`"
+            )));
+        }
+        if !(value.is_finite() && value >= 0.0) {
+            return Err(PatternError::InvalidConfig(format!(
+                "`{id}` parameter `{name}` = {value} must be non-negative and finite"
+            )));
         }
     }
+    D::with_params(params).map_err(|e| PatternError::InvalidConfig(format!("`{id}`: {e}")))
+}
 
-    /// Detect patterns in a range of bars.
-    pub fn scan_range<T: OHLCV>(
+impl BuiltinDetector {
+    /// Builtin detector for a pattern id, configured through
+    /// [`ParameterizedDetector::with_params`](params::ParameterizedDetector::with_params).
+    ///
+    /// Missing parameters keep their defaults. Unknown ids, parameters not
+    /// listed in the detector's `param_meta()` and values its `with_params`
+    /// rejects are `PatternError::InvalidConfig`, as is any parameter for a
+    /// detector without `ParameterizedDetector` support.
+    pub fn from_id_with_params(id: &str, params: &HashMap<&str, f64>) -> Result<Self> {
+        if let Some(detector) = Self::parameterized(id, params) {
+            return detector;
+        }
+        let detector = Self::default_for(id)
+            .ok_or_else(|| PatternError::InvalidConfig(format!("unknown pattern id `{id}`")))?;
+        match params.keys().next() {
+            None => Ok(detector),
+            Some(name) => Err(PatternError::InvalidConfig(format!(
+                "unknown parameter `{name}`: `{id}` does not take parameters"
+            ))),
+        }
+    }
+
+    /// The first condition that rules the pattern out at `index`, for
+    /// detectors that can explain their misses (currently Engulfing).
+    ///
+    /// Returns `None` if the pattern would match or the detector has no
+    /// explanation hook.
+    pub fn explain<T: OHLCV>(
         &self,
         bars: &[T],
-        range: std::ops::Range<usize>,
-        contexts: &[MarketContext],
-    ) -> Vec<PatternMatch> {
-        let mut results = Vec::new();
-
-        if self.custom.is_empty() {
-            for i in range {
-                if let Some(ctx) = contexts.get(i) {
-                    results.extend(self.scan_at_internal(bars, &[], i, ctx));
-                }
-            }
-        } else {
-            let bar_refs: Vec<&dyn OHLCV> = bars.iter().map(|b| b as &dyn OHLCV).collect();
-            for i in range {
-                if let Some(ctx) = contexts.get(i) {
-                    results.extend(self.scan_at_internal(bars, &bar_refs, i, ctx));
-                }
-            }
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Option<String> {
+        match self {
+            Self::Engulfing(d) => d.explain(bars, index, ctx),
+            _ => None,
         }
-
-        results
     }
+}
 
-    // ===========================================
-    // HIGH-LEVEL: Batch processing
-    // ===========================================
-
-    /// Scan all bars and return flat list of patterns.
-    pub fn scan<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<PatternMatch>> {
-        if self.config.validate_data {
-            self.validate_bars(bars)?;
-        }
+/// List every builtin pattern with its typical direction and category.
+///
+/// Useful for documentation generation and UIs; the list follows the order of
+/// the `BuiltinDetector` variants.
+pub fn pattern_catalog() -> Vec<(PatternId, Option<Direction>, PatternCategory)> {
+    BuiltinDetector::all_defaults()
+        .iter()
+        .map(|d| {
+            let id = d.id();
+            (id, id.typical_direction(), d.metadata().category)
+        })
+        .collect()
+}
 
-        let contexts = self.compute_contexts(bars);
-        Ok(self.scan_range(bars, 0..bars.len(), &contexts))
-    }
+// ============================================================
+// PATTERN ENGINE
+// ============================================================
 
-    /// Scan and return patterns grouped by bar index.
-    pub fn scan_grouped<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<Vec<PatternMatch>>> {
-        if self.config.validate_data {
-            self.validate_bars(bars)?;
-        }
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-        let contexts = self.compute_contexts(bars);
-        let mut grouped = vec![Vec::new(); bars.len()];
+/// User scoring function replacing detector strengths, see
+/// [`EngineConfig::strength_override`]
+pub type StrengthFn = Arc<dyn Fn(&PatternMatch, &MarketContext) -> f64 + Send + Sync>;
 
-        if self.custom.is_empty() {
-            for i in 0..bars.len() {
-                grouped[i] = self.scan_at_internal(bars, &[], i, &contexts[i]);
-            }
-        } else {
-            let bar_refs: Vec<&dyn OHLCV> = bars.iter().map(|b| b as &dyn OHLCV).collect();
-            for i in 0..bars.len() {
-                grouped[i] = self.scan_at_internal(bars, &bar_refs, i, &contexts[i]);
-            }
-        }
+/// Engine configuration
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct EngineConfig {
+    #[serde(default)]
+    pub min_strength: Option<f64>,
+    #[serde(default)]
+    pub validate_data: bool,
+    #[serde(default, with = "pattern_filter_serde")]
+    pub pattern_filter: Option<Vec<PatternId>>,
+    /// Emit a neutral `PatternId::NO_PATTERN` marker in `scan_grouped`
+    /// for bars that had no matches
+    #[serde(default)]
+    pub emit_empty_markers: bool,
+    /// Drop matches spanning fewer than this many bars
+    #[serde(default)]
+    pub min_span: Option<usize>,
+    /// Coloring of `close == open` bars in the TA-Lib compatible detectors
+    #[serde(default)]
+    pub color_convention: ColorConvention,
+    /// Drop matches whose last bar traded less than this volume
+    #[serde(default)]
+    pub min_volume: Option<f64>,
+    /// Drop `Direction::Neutral` matches (dojis, spinning tops, ...)
+    #[serde(default)]
+    pub directional_only: bool,
+    /// Report `WeakUp`/`WeakDown` to detectors as `Sideways`, so trend-gated
+    /// detectors only fire in `StrongUp`/`StrongDown` markets
+    #[serde(default)]
+    pub require_strong_trend: bool,
+    /// Label bidirectional reversal patterns (engulfing, harami, ...) as
+    /// reversals of the prevailing trend instead of by candle geometry:
+    /// bearish in an uptrend, bullish in a downtrend. Only ids for which
+    /// [`PatternId::is_trend_reversal`] holds are relabeled; continuations,
+    /// custom ids, sideways markets and neutral matches keep their detected
+    /// direction.
+    #[serde(default)]
+    pub direction_from_trend: bool,
+    /// Recompute the strength of every match after detection. The result is
+    /// clamped to 0..=1 and `min_strength` applies to it. Not serialized.
+    #[serde(skip)]
+    pub strength_override: Option<StrengthFn>,
+}
 
-        Ok(grouped)
+/// `pattern_filter` as a list of id strings. `PatternId` borrows a `'static`
+/// name, so names are resolved against the ids the builtin detectors emit,
+/// including optional ones like `CDL_*_PENDING`; custom detectors' ids cannot
+/// be restored and are rejected.
+mod pattern_filter_serde {
+    use super::{BuiltinDetector, PatternId};
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Ids emitted by builtin detectors besides their own `id()`
+    const SECONDARY_IDS: &[&str] = &[
+        "CDL_ENGULFING_PENDING",
+        "CDL_PIERCING_PENDING",
+        "CDL_HARAMICROSS_BULL",
+        "CDL_HARAMICROSS_BEAR",
+        "RISING_THREE_METHODS",
+        "FALLING_THREE_METHODS",
+    ];
+
+    fn resolve(name: &str) -> Option<PatternId> {
+        SECONDARY_IDS
+            .iter()
+            .find(|&&id| id == name)
+            .map(|&id| PatternId(id))
+            .or_else(|| BuiltinDetector::default_for(name).map(|d| d.id()))
     }
 
-    /// Create an iterator over bars with their patterns.
-    pub fn iter<'a, T: OHLCV>(&'a self, bars: &'a [T]) -> PatternIterator<'a, T, C> {
-        PatternIterator::new(self, bars)
+    pub fn serialize<S: Serializer>(
+        filter: &Option<Vec<PatternId>>,
+        s: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        filter
+            .as_ref()
+            .map(|ids| ids.iter().map(|id| id.as_str()).collect::<Vec<_>>())
+            .serialize(s)
     }
 
-    // ===========================================
-    // Internal helpers
-    // ===========================================
-
-    fn scan_at_internal<T: OHLCV>(
-        &self,
-        bars: &[T],
-        bar_refs: &[&dyn OHLCV],
-        index: usize,
-        ctx: &MarketContext,
-    ) -> Vec<PatternMatch> {
-        let mut results = Vec::new();
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> std::result::Result<Option<Vec<PatternId>>, D::Error> {
+        let Some(names) = Option::<Vec<String>>::deserialize(d)? else {
+            return Ok(None);
+        };
+        names
+            .iter()
+            .map(|name| {
+                resolve(name).ok_or_else(|| {
+                    D::Error::custom(format!("unknown pattern id `{name}` in pattern_filter"))
+                })
+            })
+            .collect::<std::result::Result<_, _>>()
+            .map(Some)
+    }
+}
 
-        // Fast path: builtin detectors (enum dispatch, no vtable)
-        for detector in &self.builtin {
-            if index + 1 >= detector.min_bars() {
-                if let Some(m) = detector.detect(bars, index, ctx) {
-                    if self.should_include(&m) {
-                        results.push(m);
-                    }
-                }
-            }
-        }
+/// Serializable description of an engine, for reproducible scans.
+///
+/// Holds the enabled builtin detectors with all their parameters, the
+/// engine configuration and the default context provider settings. Custom
+/// detectors, `strength_override` and per-detector context overrides cannot
+/// be serialized and are not captured; re-add them to the builder returned
+/// by [`EngineBuilder::from_snapshot`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EngineSnapshot {
+    pub detectors: Vec<DetectorSnapshot>,
+    pub config: EngineConfig,
+    pub context_provider: DefaultContextProvider,
+}
 
-        // Slow path: custom detectors (vtable)
-        if !self.custom.is_empty() && !bar_refs.is_empty() {
-            for detector in &self.custom {
-                if index + 1 >= detector.min_bars() {
-                    if let Some(m) = detector.detect(bar_refs, index, ctx) {
-                        if self.should_include(&m) {
-                            results.push(m);
-                        }
-                    }
-                }
-            }
-        }
+/// One builtin detector in an [`EngineSnapshot`]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DetectorSnapshot {
+    /// Pattern id, checked against `detector` when restoring
+    pub id: String,
+    /// The detector with its parameters
+    pub detector: BuiltinDetector,
+}
 
-        results
+impl std::fmt::Debug for EngineConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EngineConfig")
+            .field("min_strength", &self.min_strength)
+            .field("validate_data", &self.validate_data)
+            .field("pattern_filter", &self.pattern_filter)
+            .field("emit_empty_markers", &self.emit_empty_markers)
+            .field("min_span", &self.min_span)
+            .field("color_convention", &self.color_convention)
+            .field("min_volume", &self.min_volume)
+            .field("directional_only", &self.directional_only)
+            .field("require_strong_trend", &self.require_strong_trend)
+            .field("direction_from_trend", &self.direction_from_trend)
+            .field(
+                "strength_override",
+                &self.strength_override.as_ref().map(|_| "Fn"),
+            )
+            .finish()
     }
+}
 
-    fn should_include(&self, m: &PatternMatch) -> bool {
-        if let Some(min) = self.config.min_strength {
-            if m.strength < min {
-                return false;
-            }
-        }
-        if let Some(ref filter) = self.config.pattern_filter {
-            if !filter.contains(&m.pattern_id) {
-                return false;
-            }
-        }
-        true
+/// Per-pattern weights for [`PatternEngine::scan_score`]
+///
+/// Patterns without an explicit weight count with weight `1.0`.
+#[derive(Debug, Clone, Default)]
+pub struct ScoreModel {
+    pub weights: HashMap<PatternId, f64>,
+}
+
+impl ScoreModel {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn validate_bars<T: OHLCV>(&self, bars: &[T]) -> Result<()> {
-        for (i, bar) in bars.iter().enumerate() {
-            bar.validate().map_err(|e| match e {
-                PatternError::InvalidOHLCV { reason, .. } => {
-                    PatternError::InvalidOHLCV { index: i, reason }
-                }
-                other => other,
-            })?;
-        }
-        Ok(())
+    /// Set the weight for a pattern
+    pub fn with_weight(mut self, id: PatternId, weight: f64) -> Self {
+        self.weights.insert(id, weight);
+        self
     }
 
-    fn validate(&self) -> Result<()> {
-        for d in &self.builtin {
-            d.validate_config()?;
-        }
-        for d in &self.custom {
-            d.validate_config()?;
-        }
-        Ok(())
+    /// Weight for a pattern, `1.0` if not configured
+    #[inline]
+    pub fn weight(&self, id: PatternId) -> f64 {
+        self.weights.get(&id).copied().unwrap_or(1.0)
     }
 }
 
-// ============================================================
-// PATTERN ITERATOR
-// ============================================================
-
-/// Patterns found at a specific bar
-#[derive(Debug, Clone)]
-pub struct BarPatterns {
-    pub index: usize,
-    pub patterns: Vec<PatternMatch>,
+/// Per-pattern strength factors for [`PatternEngine::scan_calibrated`]
+///
+/// Typically derived from historical hit rates, e.g. the fraction of a
+/// pattern's matches followed by a move in its direction, divided by the
+/// average across patterns. Patterns without a rate keep factor `1.0`.
+#[derive(Debug, Clone, Default)]
+pub struct StrengthCalibration {
+    pub rates: HashMap<PatternId, f64>,
 }
 
-/// Iterator over bars with their patterns
-pub struct PatternIterator<'a, T: OHLCV, C: ContextProvider> {
-    engine: &'a PatternEngine<C>,
-    bars: &'a [T],
-    bar_refs: Vec<&'a dyn OHLCV>,
-    contexts: Vec<MarketContext>,
-    current: usize,
-}
+impl StrengthCalibration {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-impl<'a, T: OHLCV, C: ContextProvider> PatternIterator<'a, T, C> {
-    fn new(engine: &'a PatternEngine<C>, bars: &'a [T]) -> Self {
-        let bar_refs = if engine.custom.is_empty() {
-            Vec::new()
-        } else {
-            bars.iter().map(|b| b as &dyn OHLCV).collect()
-        };
-        let contexts = engine.compute_contexts(bars);
+    /// Set the calibration factor for a pattern
+    pub fn with_rate(mut self, id: PatternId, rate: f64) -> Self {
+        self.rates.insert(id, rate);
+        self
+    }
 
-        Self {
-            engine,
-            bars,
-            bar_refs,
-            contexts,
-            current: 0,
-        }
+    /// Factor for a pattern, `1.0` if not configured
+    #[inline]
+    pub fn factor(&self, id: PatternId) -> f64 {
+        self.rates.get(&id).copied().unwrap_or(1.0)
     }
 }
 
-impl<'a, T: OHLCV, C: ContextProvider> Iterator for PatternIterator<'a, T, C> {
-    type Item = BarPatterns;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.current >= self.bars.len() {
-            return None;
-        }
+/// Bars between two `scan_with_progress` callbacks
+pub const PROGRESS_INTERVAL: usize = 10_000;
 
-        let index = self.current;
-        let ctx = &self.contexts[index];
-        let patterns = self
-            .engine
-            .scan_at_internal(self.bars, &self.bar_refs, index, ctx);
+/// Object-safe form of [`ContextProvider`] for per-detector overrides
+trait DynContextProvider: Send + Sync {
+    fn compute_dyn(&self, bars: &[&dyn OHLCV]) -> Vec<MarketContext>;
 
-        self.current += 1;
+    fn lookback_dyn(&self) -> Option<usize>;
+}
 
-        Some(BarPatterns { index, patterns })
+impl<C: ContextProvider> DynContextProvider for C {
+    fn compute_dyn(&self, bars: &[&dyn OHLCV]) -> Vec<MarketContext> {
+        self.compute_all(bars)
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.bars.len().saturating_sub(self.current);
-        (remaining, Some(remaining))
+    fn lookback_dyn(&self) -> Option<usize> {
+        self.lookback()
     }
 }
 
-impl<'a, T: OHLCV, C: ContextProvider> ExactSizeIterator for PatternIterator<'a, T, C> {}
+/// Per-scan data shared by every bar: type-erased bars for custom detectors
+/// and override providers, and the contexts of each override provider
+struct ScanInputs<'a> {
+    bar_refs: Vec<&'a dyn OHLCV>,
+    overrides: std::borrow::Cow<'a, [Vec<MarketContext>]>,
+}
 
-// ============================================================
-// BUILDER
-// ============================================================
+/// Contexts of each `EngineBuilder::context_provider_for` override over one
+/// bar series, see [`PatternEngine::compute_override_contexts`]
+#[derive(Debug, Clone, Default)]
+pub struct OverrideContexts(Vec<Vec<MarketContext>>);
 
-/// Builder for creating PatternEngine instances
-pub struct EngineBuilder<C: ContextProvider = DefaultContextProvider> {
-    context_provider: C,
+/// Main pattern detection engine
+pub struct PatternEngine<C: ContextProvider = DefaultContextProvider> {
     builtin: Vec<BuiltinDetector>,
     custom: Vec<Box<dyn DynPatternDetector>>,
+    context_provider: C,
     config: EngineConfig,
+    /// Bit per detector (builtin first, then custom) set when disabled
+    disabled: Vec<u64>,
+    /// Detectors that take their context from another provider
+    context_overrides: Vec<(PatternId, Box<dyn DynContextProvider>)>,
 }
 
-impl Default for EngineBuilder<DefaultContextProvider> {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl EngineBuilder<DefaultContextProvider> {
-    pub fn new() -> Self {
+impl<C: ContextProvider> PatternEngine<C> {
+    pub fn new(context_provider: C) -> Self {
         Self {
-            context_provider: DefaultContextProvider::default(),
             builtin: Vec::new(),
             custom: Vec::new(),
+            context_provider,
             config: EngineConfig::default(),
+            disabled: Vec::new(),
+            context_overrides: Vec::new(),
         }
     }
-}
 
-/// Generate an array of `BuiltinDetector` variants using `Default::default()` for each inner type.
-macro_rules! builtin_defaults {
-  ($($variant:ident),* $(,)?) => {
-    [$(BuiltinDetector::$variant(Default::default())),*]
-  };
-}
+    // ===========================================
+    // RUNTIME TOGGLES
+    // ===========================================
 
-impl<C: ContextProvider> EngineBuilder<C> {
-    /// Change context provider
-    pub fn context_provider<C2: ContextProvider>(self, provider: C2) -> EngineBuilder<C2> {
-        EngineBuilder {
-            context_provider: provider,
-            builtin: self.builtin,
-            custom: self.custom,
-            config: self.config,
+    /// Enable or disable all registered detectors with this id.
+    ///
+    /// Disabled detectors stay registered and are skipped during scans, so
+    /// toggling does not require rebuilding the engine. Unknown ids are ignored.
+    pub fn set_enabled(&mut self, id: PatternId, enabled: bool) {
+        let builtin = self.builtin.iter().map(|d| d.id());
+        let custom = self.custom.iter().map(|d| d.id());
+        let slots: Vec<usize> = builtin
+            .chain(custom)
+            .enumerate()
+            .filter(|(_, d)| *d == id)
+            .map(|(slot, _)| slot)
+            .collect();
+
+        for slot in slots {
+            let (word, bit) = (slot / 64, slot % 64);
+            if enabled {
+                if let Some(w) = self.disabled.get_mut(word) {
+                    *w &= !(1 << bit);
+                }
+            } else {
+                if self.disabled.len() <= word {
+                    self.disabled.resize(word + 1, 0);
+                }
+                self.disabled[word] |= 1 << bit;
+            }
         }
     }
 
-    /// Add all builtin patterns with default configurations
-    pub fn with_all_defaults(self) -> Self {
-        self.with_single_bar_defaults()
-            .with_two_bar_defaults()
-            .with_three_bar_defaults()
-            .with_multi_bar_defaults()
-            .with_extended_defaults()
+    /// Whether a detector with this id is registered and enabled
+    pub fn is_enabled(&self, id: PatternId) -> bool {
+        let builtin = self.builtin.iter().map(|d| d.id());
+        let custom = self.custom.iter().map(|d| d.id());
+        builtin
+            .chain(custom)
+            .enumerate()
+            .any(|(slot, d)| d == id && !self.is_slot_disabled(slot))
     }
 
-    /// Add only extended patterns with defaults
-    pub fn with_extended_defaults(mut self) -> Self {
-        self.builtin.extend(builtin_defaults![
-            PriceLines,
-            FallingWindow,
-            RisingWindow,
-            GappingDownDoji,
-            GappingUpDoji,
-            AboveTheStomach,
-            BelowTheStomach,
-            CollapsingDojiStar,
-            Deliberation,
-            LastEngulfingBottom,
-            LastEngulfingTop,
-            TwoBlackGapping,
-            MeetingLinesBearish,
-            MeetingLinesBullish,
-            NorthernDoji,
-            SouthernDoji,
-            BlackMarubozu,
-            WhiteMarubozu,
-            OpeningBlackMarubozu,
-            OpeningWhiteMarubozu,
-            BlackCandle,
-            WhiteCandle,
-            ShortBlack,
-            ShortWhite,
-            LongBlackDay,
-            LongWhiteDay,
-            BlackSpinningTop,
-            WhiteSpinningTop,
-            ShootingStar2Lines,
-            DownsideGapThreeMethods,
-            UpsideGapThreeMethods,
-            DownsideTasukiGap,
-            UpsideTasukiGap,
-        ]);
-        self
+    /// Ids of the enabled detectors, builtin first, in registration order
+    pub fn detector_ids(&self) -> Vec<PatternId> {
+        let builtin = self.builtin.iter().map(|d| d.id());
+        let custom = self.custom.iter().map(|d| d.id());
+        builtin
+            .chain(custom)
+            .enumerate()
+            .filter(|(slot, _)| !self.is_slot_disabled(*slot))
+            .map(|(_, id)| id)
+            .collect()
     }
 
-    /// Add only single-bar patterns with defaults (17)
-    pub fn with_single_bar_defaults(mut self) -> Self {
-        self.builtin.extend(builtin_defaults![
-            Doji,
-            DragonflyDoji,
-            GravestoneDoji,
-            LongLeggedDoji,
-            RickshawMan,
-            Hammer,
-            HangingMan,
-            InvertedHammer,
-            ShootingStar,
-            Takuri,
-            Marubozu,
-            ClosingMarubozu,
-            LongLine,
-            ShortLine,
-            SpinningTop,
-            HighWave,
-            BeltHold,
-        ]);
-        self
+    /// Longest `min_bars` among the enabled detectors (0 if none)
+    pub fn max_min_bars(&self) -> usize {
+        let builtin = self.builtin.iter().map(|d| d.min_bars());
+        let custom = self.custom.iter().map(|d| d.min_bars());
+        builtin
+            .chain(custom)
+            .enumerate()
+            .filter(|(slot, _)| !self.is_slot_disabled(*slot))
+            .map(|(_, min_bars)| min_bars)
+            .max()
+            .unwrap_or(0)
     }
 
-    /// Add two-bar patterns with defaults (18)
-    pub fn with_two_bar_defaults(mut self) -> Self {
-        self.builtin.extend(builtin_defaults![
-            Engulfing,
-            Harami,
-            HaramiCross,
-            Piercing,
-            DarkCloudCover,
-            DojiStar,
-            Counterattack,
-            InNeck,
-            OnNeck,
-            Thrusting,
-            Kicking,
-            KickingByLength,
-            MatchingLow,
-            HomingPigeon,
-            SeparatingLines,
-            GapSideSideWhite,
-            TweezerTop,
-            TweezerBottom,
-        ]);
-        self
+    /// Bars of history a match on the newest bar can depend on: the longest
+    /// detector window plus the longest context provider lookback
+    /// (including per-detector overrides).
+    ///
+    /// `None` if any of those providers reports no bounded lookback.
+    pub fn history_len(&self) -> Option<usize> {
+        let mut context = self.context_provider.lookback()?;
+        for (_, provider) in &self.context_overrides {
+            context = context.max(provider.lookback_dyn()?);
+        }
+        Some(self.max_min_bars() + context)
     }
 
-    /// Add three-bar patterns with defaults (20)
-    pub fn with_three_bar_defaults(mut self) -> Self {
-        self.builtin.extend(builtin_defaults![
-            ThreeWhiteSoldiers,
-            ThreeBlackCrows,
-            ThreeInside,
-            ThreeOutside,
-            ThreeLineStrike,
-            ThreeStarsInSouth,
-            MorningStar,
-            EveningStar,
-            MorningDojiStar,
-            EveningDojiStar,
-            AbandonedBaby,
-            TwoCrows,
-            UpsideGapTwoCrows,
-            IdenticalThreeCrows,
-            AdvanceBlock,
-            StalledPattern,
-            StickSandwich,
-            TasukiGap,
-            Tristar,
-            Unique3River,
-        ]);
-        self
+    #[inline]
+    fn is_slot_disabled(&self, slot: usize) -> bool {
+        self.disabled
+            .get(slot / 64)
+            .is_some_and(|w| w & (1 << (slot % 64)) != 0)
     }
 
-    /// Add multi-bar patterns with defaults (8)
-    pub fn with_multi_bar_defaults(mut self) -> Self {
-        self.builtin.extend(builtin_defaults![
-            Breakaway,
-            ConcealingBabySwallow,
-            Hikkake,
-            HikkakeMod,
-            LadderBottom,
-            MatHold,
-            RiseFallThreeMethods,
-            XSideGapThreeMethods,
-        ]);
-        self
+    /// Enabled builtin detectors with their slots
+    #[inline]
+    fn enabled_builtin(&self) -> impl Iterator<Item = (usize, &BuiltinDetector)> {
+        self.builtin
+            .iter()
+            .enumerate()
+            .filter(|&(slot, _)| !self.is_slot_disabled(slot))
     }
 
-    /// Add a builtin detector
-    #[allow(clippy::should_implement_trait)]
-    pub fn add(mut self, detector: BuiltinDetector) -> Self {
-        self.builtin.push(detector);
-        self
+    #[inline]
+    fn override_index(&self, id: PatternId) -> Option<usize> {
+        self.context_overrides.iter().position(|(o, _)| *o == id)
     }
 
-    /// Add with config validation
-    pub fn add_checked(mut self, detector: BuiltinDetector) -> Result<Self> {
-        detector.validate_config()?;
-        self.builtin.push(detector);
-        Ok(self)
+    /// Collect what `for_each_match_at` needs beyond the bars themselves
+    fn prepare<'a, T: OHLCV>(&self, bars: &'a [T]) -> ScanInputs<'a> {
+        self.prepare_with(bars, None)
     }
 
-    /// Add a custom detector (slow path)
-    pub fn add_custom<D: DynPatternDetector + 'static>(mut self, detector: D) -> Self {
-        self.custom.push(Box::new(detector));
-        self
+    /// `prepare`, reusing `overrides` instead of computing them when given
+    fn prepare_with<'a, T: OHLCV>(
+        &self,
+        bars: &'a [T],
+        overrides: Option<&'a OverrideContexts>,
+    ) -> ScanInputs<'a> {
+        let bar_refs: Vec<&dyn OHLCV> = if self.custom.is_empty()
+            && (self.context_overrides.is_empty() || overrides.is_some())
+        {
+            Vec::new()
+        } else {
+            bars.iter().map(|b| b as &dyn OHLCV).collect()
+        };
+        let overrides = match overrides {
+            Some(o) => std::borrow::Cow::Borrowed(o.0.as_slice()),
+            None => std::borrow::Cow::Owned(
+                self.context_overrides
+                    .iter()
+                    .map(|(_, provider)| provider.compute_dyn(&bar_refs))
+                    .collect(),
+            ),
+        };
+        ScanInputs {
+            bar_refs,
+            overrides,
+        }
     }
 
-    /// Set minimum strength filter
-    pub fn min_strength(mut self, strength: f64) -> Self {
-        self.config.min_strength = Some(strength);
-        self
+    /// Context for a detector at `index`: its override if it has one,
+    /// otherwise `ctx`, with the engine's color convention applied
+    #[inline]
+    fn context_for(
+        &self,
+        context: Option<usize>,
+        inputs: &ScanInputs,
+        index: usize,
+        ctx: &MarketContext,
+    ) -> MarketContext {
+        let ctx = context
+            .and_then(|k| inputs.overrides.get(k)?.get(index))
+            .unwrap_or(ctx);
+        self.apply_config(ctx)
     }
 
-    /// Enable/disable data validation
-    pub fn validate_data(mut self, enable: bool) -> Self {
-        self.config.validate_data = enable;
-        self
+    // ===========================================
+    // LOW-LEVEL: Primitives
+    // ===========================================
+
+    /// Precompute contexts for all bars.
+    /// User stores and reuses the result.
+    #[inline]
+    pub fn compute_contexts<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
+        self.context_provider.compute_all(bars)
     }
 
-    /// Filter to specific patterns only
-    pub fn only_patterns(mut self, ids: impl IntoIterator<Item = PatternId>) -> Self {
-        self.config.pattern_filter = Some(ids.into_iter().collect());
-        self
+    /// Precompute the contexts of the per-detector provider overrides.
+    /// Pass the result to `scan_at_with_overrides` when calling it in a loop.
+    pub fn compute_override_contexts<T: OHLCV>(&self, bars: &[T]) -> OverrideContexts {
+        OverrideContexts(self.prepare(bars).overrides.into_owned())
     }
 
-    /// Build the engine
-    pub fn build(self) -> Result<PatternEngine<C>> {
-        let engine = PatternEngine {
-            builtin: self.builtin,
-            custom: self.custom,
-            context_provider: self.context_provider,
-            config: self.config,
-        };
-        engine.validate()?;
-        Ok(engine)
+    /// Compute context for a single bar.
+    /// For incremental/realtime scenarios.
+    #[inline]
+    pub fn compute_context_at<T: OHLCV>(&self, bars: &[T], index: usize) -> MarketContext {
+        let contexts = self.context_provider.compute_all(bars);
+        contexts.get(index).copied().unwrap_or_default()
     }
-}
 
-// ============================================================
-// PARALLEL SCANNING
-// ============================================================
+    // ===========================================
+    // MID-LEVEL: Single-bar / Range
+    // ===========================================
 
-use rayon::prelude::*;
+    /// Detect patterns at a single bar index.
+    ///
+    /// Detectors with a `context_provider_for` override get their context
+    /// from that provider, computed over `bars` on every call; use
+    /// `scan_at_with_overrides` to compute it once.
+    pub fn scan_at<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Vec<PatternMatch> {
+        self.scan_at_internal(bars, &self.prepare(bars), index, ctx)
+    }
 
-/// Result of scanning a single instrument
-#[derive(Debug)]
-pub struct ScanResult {
-    pub symbol: String,
-    pub patterns: Vec<PatternMatch>,
-}
+    /// `scan_at` with override contexts from `compute_override_contexts`.
+    pub fn scan_at_with_overrides<T: OHLCV>(
+        &self,
+        bars: &[T],
+        index: usize,
+        ctx: &MarketContext,
+        overrides: &OverrideContexts,
+    ) -> Vec<PatternMatch> {
+        self.scan_at_internal(bars, &self.prepare_with(bars, Some(overrides)), index, ctx)
+    }
+
+    /// Detect patterns in a range of bars.
+    pub fn scan_range<T: OHLCV>(
+        &self,
+        bars: &[T],
+        range: std::ops::Range<usize>,
+        contexts: &[MarketContext],
+    ) -> Vec<PatternMatch> {
+        let mut results = Vec::new();
+        self.scan_range_into(bars, range, contexts, &mut results);
+        results
+    }
+
+    /// Append the matches of `scan_range` to `out`
+    fn scan_range_into<T: OHLCV>(
+        &self,
+        bars: &[T],
+        range: std::ops::Range<usize>,
+        contexts: &[MarketContext],
+        out: &mut Vec<PatternMatch>,
+    ) {
+        let inputs = self.prepare(bars);
+        for i in range {
+            if let Some(ctx) = contexts.get(i) {
+                self.for_each_match_at(bars, &inputs, i, ctx, |_, m| out.push(m));
+            }
+        }
+    }
+
+    /// Detect patterns only at the given bar indices.
+    ///
+    /// Equivalent to calling `scan_at` for each index, in the given order,
+    /// without rescanning the bars in between. Indices without a context are
+    /// skipped.
+    pub fn scan_indices<T: OHLCV>(
+        &self,
+        bars: &[T],
+        indices: &[usize],
+        contexts: &[MarketContext],
+    ) -> Vec<PatternMatch> {
+        let mut results = Vec::new();
+        let inputs = self.prepare(bars);
+
+        for &i in indices {
+            if let Some(ctx) = contexts.get(i) {
+                self.for_each_match_at(bars, &inputs, i, ctx, |_, m| results.push(m));
+            }
+        }
+
+        results
+    }
+
+    // ===========================================
+    // HIGH-LEVEL: Batch processing
+    // ===========================================
+
+    /// Scan all bars and return flat list of patterns.
+    pub fn scan<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<PatternMatch>> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let contexts = self.compute_contexts(bars);
+        Ok(self.scan_range(bars, 0..bars.len(), &contexts))
+    }
+
+    /// Like `scan`, but writes into a caller-provided buffer.
+    ///
+    /// `out` is cleared first and keeps its capacity, so scanning many series
+    /// (or the same series repeatedly) with one buffer avoids reallocating
+    /// the result list each time.
+    pub fn scan_into<T: OHLCV>(&self, bars: &[T], out: &mut Vec<PatternMatch>) -> Result<()> {
+        out.clear();
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let contexts = self.compute_contexts(bars);
+        self.scan_range_into(bars, 0..bars.len(), &contexts, out);
+        Ok(())
+    }
+
+    /// Scan all bars but only emit matches ending at or after `start`.
+    ///
+    /// Contexts are still computed over the whole series, so this skips the
+    /// warmup region (where trend/averages are not yet meaningful) without
+    /// changing the context seen by the remaining bars.
+    pub fn scan_from<T: OHLCV>(&self, bars: &[T], start: usize) -> Result<Vec<PatternMatch>> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let contexts = self.compute_contexts(bars);
+        Ok(self.scan_range(bars, start.min(bars.len())..bars.len(), &contexts))
+    }
+
+    /// Scan a realtime series whose last bar is still forming.
+    ///
+    /// The last bar is ignored entirely (neither validated nor used for
+    /// contexts), so only matches ending at `bars.len() - 2` or earlier are
+    /// emitted.
+    pub fn scan_closed<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<PatternMatch>> {
+        let closed = bars.len().saturating_sub(1);
+        self.scan(&bars[..closed])
+    }
+
+    /// Early warnings for patterns that may complete on the next bar.
+    ///
+    /// Checks the setups of the enabled Engulfing and Piercing detectors on
+    /// the last bar and reports them as `CDL_ENGULFING_PENDING` /
+    /// `CDL_PIERCING_PENDING` one-bar matches, each with its completeness
+    /// (bars formed / bars needed). Their strength is
+    /// `helpers::FORMING_MAX_STRENGTH` scaled by that completeness, so they
+    /// rank below any completed match. Engine filters such as `min_strength`
+    /// are not applied.
+    pub fn scan_forming<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<FormingMatch>> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+        let Some(last) = bars.len().checked_sub(1) else {
+            return Ok(Vec::new());
+        };
+
+        let ctx = self.compute_context_at(bars, last);
+        let inputs = self.prepare(bars);
+        Ok(self
+            .enabled_builtin()
+            .filter_map(|(_, detector)| {
+                let context = self.override_index(detector.id());
+                let ctx = &self.context_for(context, &inputs, last, &ctx);
+                match detector {
+                    BuiltinDetector::Engulfing(d) => d.detect_forming(bars, last, ctx),
+                    BuiltinDetector::Piercing(d) => d.detect_forming(bars, last, ctx),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+
+    /// Scan all bars but keep only matches ending near a price level.
+    ///
+    /// A match is kept when the close of its last bar lies within
+    /// `tolerance` (relative to the level) of any of `levels`, e.g. to find
+    /// dojis and reversals at known support/resistance.
+    pub fn scan_near_levels<T: OHLCV>(
+        &self,
+        bars: &[T],
+        levels: &[f64],
+        tolerance: Ratio,
+    ) -> Result<Vec<PatternMatch>> {
+        let mut matches = self.scan(bars)?;
+        matches.retain(|m| {
+            let Some(bar) = bars.get(m.end_index) else {
+                return false;
+            };
+            let close = bar.close();
+            levels
+                .iter()
+                .any(|level| (close - level).abs() <= tolerance.get() * level.abs())
+        });
+        Ok(matches)
+    }
+
+    /// Scan all bars and tag each match with the single-bar shapes its last
+    /// bar also has.
+    ///
+    /// Tags are `"marubozu"`, `"closing_marubozu"`, `"belt_hold"` and
+    /// `"long_line"`, checked with default thresholds whether or not those
+    /// detectors are registered, e.g. an engulfing whose second bar is a
+    /// marubozu comes back tagged `["marubozu", "belt_hold", ...]`. A match is
+    /// never tagged with its own pattern.
+    pub fn scan_annotated<T: OHLCV>(
+        &self,
+        bars: &[T],
+    ) -> Result<Vec<(PatternMatch, Vec<&'static str>)>> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let contexts = self.compute_contexts(bars);
+        let shapes = [
+            (
+                "marubozu",
+                BuiltinDetector::Marubozu(MarubozuDetector::default()),
+            ),
+            (
+                "closing_marubozu",
+                BuiltinDetector::ClosingMarubozu(ClosingMarubozuDetector::default()),
+            ),
+            (
+                "belt_hold",
+                BuiltinDetector::BeltHold(BeltHoldDetector::default()),
+            ),
+            (
+                "long_line",
+                BuiltinDetector::LongLine(LongLineDetector::default()),
+            ),
+        ];
+        let inputs = self.prepare(bars);
+        let mut matches = Vec::new();
+        for (i, ctx) in contexts.iter().enumerate() {
+            self.for_each_match_at(bars, &inputs, i, ctx, |_, m| matches.push(m));
+        }
+        Ok(matches
+            .into_iter()
+            .map(|m| {
+                let tags = match contexts.get(m.end_index) {
+                    Some(ctx) => shapes
+                        .iter()
+                        .filter(|(_, d)| d.id() != m.pattern_id)
+                        .filter(|(_, d)| {
+                            // Same context the shape's own detector would see
+                            let context = self.override_index(d.id());
+                            let ctx = self.context_for(context, &inputs, m.end_index, ctx);
+                            d.detect(bars, m.end_index, &ctx).is_some()
+                        })
+                        .map(|(tag, _)| *tag)
+                        .collect(),
+                    None => Vec::new(),
+                };
+                (m, tags)
+            })
+            .collect())
+    }
+
+    /// Scan all bars and pair each match with the trend on its last bar.
+    ///
+    /// The trend is the one the detector saw, after per-detector context
+    /// overrides and `require_strong_trend`, so callers can filter by regime
+    /// without recomputing it.
+    pub fn scan_with_regime<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<(PatternMatch, Trend)>> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let contexts = self.compute_contexts(bars);
+        let inputs = self.prepare(bars);
+        let mut results = Vec::new();
+        for (i, ctx) in contexts.iter().enumerate() {
+            self.for_each_match_in_context_at(bars, &inputs, i, ctx, |_, m, ctx| {
+                results.push((m, ctx.trend))
+            });
+        }
+        Ok(results)
+    }
+
+    /// Scan all bars, calling `progress(done, total)` every
+    /// `PROGRESS_INTERVAL` (10k) bars and once more when finished.
+    ///
+    /// The final call always reports `done == total == bars.len()`, also for
+    /// an empty series.
+    pub fn scan_with_progress<T: OHLCV, F: FnMut(usize, usize)>(
+        &self,
+        bars: &[T],
+        mut progress: F,
+    ) -> Result<Vec<PatternMatch>> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let contexts = self.compute_contexts(bars);
+        let inputs = self.prepare(bars);
+
+        let total = bars.len();
+        let mut results = Vec::new();
+        for (i, ctx) in contexts.iter().enumerate() {
+            self.for_each_match_at(bars, &inputs, i, ctx, |_, m| results.push(m));
+            let done = i + 1;
+            if done % PROGRESS_INTERVAL == 0 && done < total {
+                progress(done, total);
+            }
+        }
+        progress(total, total);
+
+        Ok(results)
+    }
+
+    /// Count the matches `scan` would return, without collecting them.
+    ///
+    /// Runs the same detection pass but only counts, so callers can pre-size
+    /// buffers with `Vec::with_capacity`. Data validation is not performed.
+    pub fn estimate_matches<T: OHLCV>(&self, bars: &[T]) -> usize {
+        let contexts = self.compute_contexts(bars);
+        let inputs = self.prepare(bars);
+
+        let mut count = 0;
+        for (i, ctx) in contexts.iter().enumerate() {
+            self.for_each_match_at(bars, &inputs, i, ctx, |_, _| count += 1);
+        }
+        count
+    }
+
+    /// Scan and return patterns grouped by bar index.
+    pub fn scan_grouped<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<Vec<PatternMatch>>> {
+        let mut grouped = Vec::new();
+        self.scan_grouped_into(bars, &mut grouped)?;
+        Ok(grouped)
+    }
+
+    /// Like `scan_grouped`, but writes into a caller-provided buffer.
+    ///
+    /// `out` is resized to one entry per bar; inner vectors that already
+    /// exist are cleared and reused, so their capacity carries over between
+    /// calls.
+    pub fn scan_grouped_into<T: OHLCV>(
+        &self,
+        bars: &[T],
+        out: &mut Vec<Vec<PatternMatch>>,
+    ) -> Result<()> {
+        out.truncate(bars.len());
+        out.iter_mut().for_each(Vec::clear);
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+        out.resize_with(bars.len(), Vec::new);
+
+        let contexts = self.compute_contexts(bars);
+        let inputs = self.prepare(bars);
+        for (i, ctx) in contexts.iter().enumerate() {
+            let patterns = &mut out[i];
+            self.for_each_match_at(bars, &inputs, i, ctx, |_, m| patterns.push(m));
+        }
+
+        if self.config.emit_empty_markers {
+            for (i, patterns) in out.iter_mut().enumerate() {
+                if patterns.is_empty() {
+                    patterns.push(PatternMatch {
+                        pattern_id: PatternId::NO_PATTERN,
+                        direction: Direction::Neutral,
+                        strength: 0.0,
+                        start_index: i,
+                        end_index: i,
+                        metric: None,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Like `scan_grouped`, but only returns bars that have matches, each
+    /// paired with its bar index.
+    ///
+    /// Memory scales with the number of matching bars instead of the series
+    /// length. `emit_empty_markers` does not apply, since empty bars are
+    /// omitted.
+    pub fn scan_grouped_sparse<T: OHLCV>(
+        &self,
+        bars: &[T],
+    ) -> Result<Vec<(usize, Vec<PatternMatch>)>> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let contexts = self.compute_contexts(bars);
+        let inputs = self.prepare(bars);
+        let mut grouped = Vec::new();
+        let mut patterns = Vec::new();
+        for (i, ctx) in contexts.iter().enumerate() {
+            self.for_each_match_at(bars, &inputs, i, ctx, |_, m| patterns.push(m));
+            if !patterns.is_empty() {
+                grouped.push((i, std::mem::take(&mut patterns)));
+            }
+        }
+
+        Ok(grouped)
+    }
+
+    /// Strongest match ending on each bar, `None` where nothing matched.
+    ///
+    /// Ties keep the detector registered first.
+    pub fn scan_dominant<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<Option<PatternMatch>>> {
+        let mut dominant: Vec<Option<PatternMatch>> = vec![None; bars.len()];
+        for m in self.scan(bars)? {
+            if let Some(slot) = dominant.get_mut(m.end_index) {
+                if slot.map_or(true, |best| m.strength > best.strength) {
+                    *slot = Some(m);
+                }
+            }
+        }
+        Ok(dominant)
+    }
+
+    /// Composite per-bar score.
+    ///
+    /// Each match contributes `direction.sign() * strength * weight` to the bar
+    /// it ends on, so neutral patterns never move the score.
+    pub fn scan_score<T: OHLCV>(&self, bars: &[T], model: &ScoreModel) -> Result<Vec<f64>> {
+        let mut scores = vec![0.0; bars.len()];
+        for m in self.scan(bars)? {
+            if let Some(score) = scores.get_mut(m.end_index) {
+                *score += m.direction.sign() * m.strength * model.weight(m.pattern_id);
+            }
+        }
+        Ok(scores)
+    }
+
+    /// Scan with each match's strength multiplied by its calibration factor,
+    /// clamped to 0..=1.
+    ///
+    /// Calibration is applied after the engine config, so `min_strength`
+    /// filters on the raw geometric strength.
+    pub fn scan_calibrated<T: OHLCV>(
+        &self,
+        bars: &[T],
+        calibration: &StrengthCalibration,
+    ) -> Result<Vec<PatternMatch>> {
+        let mut matches = self.scan(bars)?;
+        for m in &mut matches {
+            m.strength = (m.strength * calibration.factor(m.pattern_id)).clamp(0.0, 1.0);
+        }
+        Ok(matches)
+    }
+
+    /// Per-bar feature rows for ML pipelines.
+    ///
+    /// Each row has one column per enabled detector, in `detector_ids()` order,
+    /// holding the strength of that detector's match ending on the bar (0.0 if
+    /// none). Matches rejected by the engine config count as absent.
+    pub fn scan_feature_matrix<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<Vec<f32>>> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let total = self.builtin.len() + self.custom.len();
+        let mut column = vec![None; total];
+        for (col, slot) in (0..total)
+            .filter(|&slot| !self.is_slot_disabled(slot))
+            .enumerate()
+        {
+            column[slot] = Some(col);
+        }
+        let width = column.iter().flatten().count();
+
+        let contexts = self.compute_contexts(bars);
+        let inputs = self.prepare(bars);
+
+        let mut rows = vec![vec![0.0_f32; width]; bars.len()];
+        for (i, ctx) in contexts.iter().enumerate() {
+            self.for_each_match_at(bars, &inputs, i, ctx, |slot, m| {
+                if let (Some(col), Some(row)) = (column[slot], rows.get_mut(m.end_index)) {
+                    row[col] = row[col].max(m.strength as f32);
+                }
+            });
+        }
+
+        Ok(rows)
+    }
+
+    /// Scan all bars and measure the time spent in each detector.
+    ///
+    /// Returns the same matches as `scan` together with the total detection
+    /// time per enabled detector (detectors sharing an id are summed). Context
+    /// computation and filtering are not attributed to any detector. Timing
+    /// every call adds overhead, so use this for diagnosis, not production.
+    pub fn scan_profile<T: OHLCV>(
+        &self,
+        bars: &[T],
+    ) -> Result<(Vec<PatternMatch>, HashMap<PatternId, Duration>)> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let mut timings: HashMap<PatternId, Duration> = self
+            .detector_ids()
+            .into_iter()
+            .map(|id| (id, Duration::ZERO))
+            .collect();
+        let contexts = self.compute_contexts(bars);
+        let inputs = self.prepare(bars);
+
+        let mut results = Vec::new();
+        let mut record = |id, elapsed, found: Option<PatternMatch>, ctx: &MarketContext| {
+            *timings.entry(id).or_default() += elapsed;
+            if let Some(mut m) = found {
+                self.rescore(&mut m, ctx);
+                if self.should_include(&m, bars) {
+                    results.push(m);
+                }
+            }
+        };
+
+        for (i, ctx) in contexts.iter().enumerate() {
+            for (_, detector) in self.enabled_builtin() {
+                if i + 1 >= detector.min_bars() {
+                    let context = self.override_index(detector.id());
+                    let ctx = &self.context_for(context, &inputs, i, ctx);
+                    let start = Instant::now();
+                    let found = detector.detect(bars, i, ctx);
+                    record(detector.id(), start.elapsed(), found, ctx);
+                }
+            }
+            for (slot, detector) in self.custom.iter().enumerate() {
+                if self.is_slot_disabled(self.builtin.len() + slot) {
+                    continue;
+                }
+                if i + 1 >= detector.min_bars() {
+                    let context = self.override_index(detector.id());
+                    let ctx = &self.context_for(context, &inputs, i, ctx);
+                    let start = Instant::now();
+                    let found = detector.detect(&inputs.bar_refs, i, ctx);
+                    record(detector.id(), start.elapsed(), found, ctx);
+                }
+            }
+        }
+
+        Ok((results, timings))
+    }
+
+    /// Scan all bars and tag each match with a monotonically increasing id.
+    ///
+    /// Ids follow the order of `scan` output and start at 0, so downstream
+    /// consumers can dedup and order events without hashing matches.
+    pub fn scan_events<T: OHLCV>(&self, bars: &[T]) -> Result<Vec<DetectedEvent>> {
+        Ok(self
+            .scan(bars)?
+            .into_iter()
+            .zip(0..)
+            .map(|(m, id)| DetectedEvent { id, m })
+            .collect())
+    }
+
+    /// Explain the outcome of one registered detector at `index`.
+    ///
+    /// Returns a human-readable report: whether the pattern matched, whether
+    /// an engine-level condition (bar count, `min_strength`, `min_span`,
+    /// `min_volume`, pattern filter) rejected it, and otherwise the failing
+    /// detector condition (for detectors with a [`BuiltinDetector::explain`]
+    /// hook) plus the per-bar body/range/shadow figures together with the
+    /// trailing-10 averages the detectors compare them to.
+    /// Returns `None` if no detector with this id is registered or `index`
+    /// is out of bounds. Intended for debugging, not for hot paths.
+    pub fn explain_at<T: OHLCV>(&self, bars: &[T], index: usize, id: PatternId) -> Option<String> {
+        use std::fmt::Write;
+
+        if index >= bars.len() {
+            return None;
+        }
+
+        let inputs = self.prepare(bars);
+        let ctx = self.context_for(
+            self.override_index(id),
+            &inputs,
+            index,
+            &self.compute_context_at(bars, index),
+        );
+        let (min_bars, result) = if let Some(d) = self.builtin.iter().find(|d| d.id() == id) {
+            let result = (index + 1 >= d.min_bars())
+                .then(|| d.detect(bars, index, &ctx))
+                .flatten();
+            (d.min_bars(), result)
+        } else {
+            // Custom detectors are registered, so `prepare` built the bar refs
+            let d = self.custom.iter().find(|d| d.id() == id)?;
+            let result = (index + 1 >= d.min_bars())
+                .then(|| d.detect(&inputs.bar_refs, index, &ctx))
+                .flatten();
+            (d.min_bars(), result)
+        };
+
+        let result = result.map(|mut m| {
+            self.rescore(&mut m, &ctx);
+            m
+        });
+        let miss = self
+            .builtin
+            .iter()
+            .find(|d| d.id() == id)
+            .and_then(|d| d.explain(bars, index, &ctx));
+
+        let mut out = format!("{} at {index}: ", id.as_str());
+        if index + 1 < min_bars {
+            let _ = write!(
+                out,
+                "not enough bars (needs {min_bars}, have {})",
+                index + 1
+            );
+            return Some(out);
+        }
+        if !self.is_enabled(id) {
+            out.push_str("disabled");
+            return Some(out);
+        }
+
+        match result {
+            Some(m) if self.should_include(&m, bars) => {
+                let _ = write!(
+                    out,
+                    "matched {:?} strength={:.2} bars={}..{}",
+                    m.direction, m.strength, m.start_index, m.end_index
+                );
+                return Some(out);
+            }
+            Some(m) => {
+                let span = m.end_index - m.start_index + 1;
+                let volume = bars.get(m.end_index).map_or(f64::NAN, |b| b.volume());
+                let reason = match (
+                    self.config.min_strength,
+                    self.config.min_span,
+                    self.config.min_volume,
+                ) {
+                    (Some(min), _, _) if m.strength < min => {
+                        format!("strength {:.2} below min_strength {min:.2}", m.strength)
+                    }
+                    (_, Some(min_span), _) if span < min_span => {
+                        format!("span {span} below min_span {min_span}")
+                    }
+                    (_, _, Some(min)) if volume < min || volume.is_nan() => {
+                        format!("volume {volume} below min_volume {min}")
+                    }
+                    _ => "excluded by pattern filter".to_string(),
+                };
+                let _ = write!(out, "matched but rejected: {reason}");
+                return Some(out);
+            }
+            None => match miss {
+                Some(reason) => {
+                    let _ = write!(out, "no match: {reason}");
+                }
+                None => out.push_str("no match (detector conditions not met)"),
+            },
+        }
+
+        let _ = write!(
+            out,
+            "\n  context: trend={:?} avg_body={:.4} avg_range={:.4}",
+            ctx.trend, ctx.avg_body, ctx.avg_range
+        );
+        for i in (index + 1 - min_bars.max(1))..=index {
+            let b = &bars[i];
+            let _ = write!(
+                out,
+                "\n  bar {i}: body={:.4} range={:.4} upper={:.4} lower={:.4} \
+                 avg_body={:.4} avg_range={:.4} avg_shadow={:.4}",
+                b.body(),
+                b.range(),
+                b.upper_shadow(),
+                b.lower_shadow(),
+                detectors::helpers::trailing_avg_body(bars, i, 10),
+                detectors::helpers::trailing_avg_range(bars, i, 10),
+                detectors::helpers::trailing_avg_shadow(bars, i, 10),
+            );
+        }
+        Some(out)
+    }
+
+    /// Create an iterator over bars with their patterns.
+    pub fn iter<'a, T: OHLCV>(&'a self, bars: &'a [T]) -> PatternIterator<'a, T, C> {
+        PatternIterator::new(self, bars)
+    }
+
+    // ===========================================
+    // Internal helpers
+    // ===========================================
+
+    fn scan_at_internal<T: OHLCV>(
+        &self,
+        bars: &[T],
+        inputs: &ScanInputs,
+        index: usize,
+        ctx: &MarketContext,
+    ) -> Vec<PatternMatch> {
+        let mut results = Vec::new();
+        self.for_each_match_at(bars, inputs, index, ctx, |_, m| results.push(m));
+        results
+    }
+
+    /// Run all detectors at `index`, passing each included match to `f`
+    fn for_each_match_at<T: OHLCV, F: FnMut(usize, PatternMatch)>(
+        &self,
+        bars: &[T],
+        inputs: &ScanInputs,
+        index: usize,
+        ctx: &MarketContext,
+        mut f: F,
+    ) {
+        self.for_each_match_in_context_at(bars, inputs, index, ctx, |slot, m, _| f(slot, m));
+    }
+
+    /// Like `for_each_match_at`, also passing the context the detector saw,
+    /// after overrides and `apply_config`
+    fn for_each_match_in_context_at<T: OHLCV, F: FnMut(usize, PatternMatch, &MarketContext)>(
+        &self,
+        bars: &[T],
+        inputs: &ScanInputs,
+        index: usize,
+        ctx: &MarketContext,
+        mut f: F,
+    ) {
+        let configured = &self.apply_config(ctx);
+
+        // Fast path: builtin detectors (enum dispatch, no vtable). A table of
+        // monomorphized detect fn pointers was measured no faster on the
+        // `scan_all_patterns_1m_bars` bench; the detector bodies dominate.
+        for (slot, detector) in self.enabled_builtin() {
+            if index + 1 >= detector.min_bars() {
+                let context = self.override_index(detector.id());
+                let overridden;
+                let ctx = match context {
+                    None => configured,
+                    Some(_) => {
+                        overridden = self.context_for(context, inputs, index, ctx);
+                        &overridden
+                    }
+                };
+                if let Some(mut m) = detector.detect(bars, index, ctx) {
+                    self.rescore(&mut m, ctx);
+                    if self.should_include(&m, bars) {
+                        f(slot, m, ctx);
+                    }
+                }
+            }
+        }
+
+        // Slow path: custom detectors (vtable)
+        if !self.custom.is_empty() && !inputs.bar_refs.is_empty() {
+            for (slot, detector) in self.custom.iter().enumerate() {
+                if self.is_slot_disabled(self.builtin.len() + slot) {
+                    continue;
+                }
+                if index + 1 >= detector.min_bars() {
+                    let context = self.override_index(detector.id());
+                    let ctx = &self.context_for(context, inputs, index, ctx);
+                    if let Some(mut m) = detector.detect(&inputs.bar_refs, index, ctx) {
+                        self.rescore(&mut m, ctx);
+                        if self.should_include(&m, bars) {
+                            f(self.builtin.len() + slot, m, ctx);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Apply `direction_from_trend` and `strength_override` if configured
+    /// and clamp the strength to 0..=1
+    #[inline]
+    fn rescore(&self, m: &mut PatternMatch, ctx: &MarketContext) {
+        if self.config.direction_from_trend
+            && m.direction != Direction::Neutral
+            && m.pattern_id.is_trend_reversal()
+        {
+            if ctx.trend.is_up() {
+                m.direction = Direction::Bearish;
+            } else if ctx.trend.is_down() {
+                m.direction = Direction::Bullish;
+            }
+        }
+        if let Some(ref f) = self.config.strength_override {
+            m.strength = f(m, ctx);
+        }
+        m.strength = detectors::helpers::clamp_strength(m.strength);
+    }
+
+    #[inline]
+    fn apply_config(&self, ctx: &MarketContext) -> MarketContext {
+        let trend = match ctx.trend {
+            Trend::WeakUp | Trend::WeakDown if self.config.require_strong_trend => Trend::Sideways,
+            trend => trend,
+        };
+        MarketContext {
+            trend,
+            color: self.config.color_convention,
+            ..*ctx
+        }
+    }
+
+    fn should_include<T: OHLCV>(&self, m: &PatternMatch, bars: &[T]) -> bool {
+        if let Some(min) = self.config.min_strength {
+            if m.strength < min {
+                return false;
+            }
+        }
+        if let Some(ref filter) = self.config.pattern_filter {
+            if !filter.contains(&m.pattern_id) {
+                return false;
+            }
+        }
+        if self.config.directional_only && m.direction == Direction::Neutral {
+            return false;
+        }
+        if let Some(min_span) = self.config.min_span {
+            if m.end_index - m.start_index + 1 < min_span {
+                return false;
+            }
+        }
+        if let Some(min_volume) = self.config.min_volume {
+            if !bars
+                .get(m.end_index)
+                .is_some_and(|b| b.volume() >= min_volume)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn validate_bars<T: OHLCV>(&self, bars: &[T]) -> Result<()> {
+        for (i, bar) in bars.iter().enumerate() {
+            bar.validate().map_err(|e| match e {
+                PatternError::InvalidOHLCV { reason, .. } => {
+                    PatternError::InvalidOHLCV { index: i, reason }
+                }
+                other => other,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<()> {
+        for d in &self.builtin {
+            d.validate_config()?;
+        }
+        for d in &self.custom {
+            d.validate_config()?;
+        }
+        Ok(())
+    }
+}
+
+impl PatternEngine<DefaultContextProvider> {
+    /// `compute_contexts` with the trailing windows computed on the rayon
+    /// thread pool, see [`DefaultContextProvider::compute_all_parallel`]
+    pub fn compute_contexts_parallel<T: OHLCV + Sync>(&self, bars: &[T]) -> Vec<MarketContext> {
+        self.context_provider.compute_all_parallel(bars)
+    }
+
+    /// Same matches as `scan`, with the contexts from
+    /// `compute_contexts_parallel`. For very long series (millions of bars).
+    pub fn scan_parallel_contexts<T: OHLCV + Sync>(&self, bars: &[T]) -> Result<Vec<PatternMatch>> {
+        if self.config.validate_data {
+            self.validate_bars(bars)?;
+        }
+
+        let contexts = self.compute_contexts_parallel(bars);
+        Ok(self.scan_range(bars, 0..bars.len(), &contexts))
+    }
+
+    /// Capture the enabled builtin detectors, config and context provider,
+    /// see [`EngineSnapshot`]
+    pub fn snapshot(&self) -> EngineSnapshot {
+        EngineSnapshot {
+            detectors: self
+                .enabled_builtin()
+                .map(|(_, detector)| {
+                    let detector = detector.clone();
+                    DetectorSnapshot {
+                        id: detector.id().as_str().to_string(),
+                        detector,
+                    }
+                })
+                .collect(),
+            config: self.config.clone(),
+            context_provider: self.context_provider.clone(),
+        }
+    }
+}
+
+// ============================================================
+// PATTERN ITERATOR
+// ============================================================
+
+/// Patterns found at a specific bar
+#[derive(Debug, Clone)]
+pub struct BarPatterns {
+    pub index: usize,
+    pub patterns: Vec<PatternMatch>,
+}
+
+/// Iterator over bars with their patterns
+pub struct PatternIterator<'a, T: OHLCV, C: ContextProvider> {
+    engine: &'a PatternEngine<C>,
+    bars: &'a [T],
+    inputs: ScanInputs<'a>,
+    contexts: Vec<MarketContext>,
+    current: usize,
+}
+
+impl<'a, T: OHLCV, C: ContextProvider> PatternIterator<'a, T, C> {
+    fn new(engine: &'a PatternEngine<C>, bars: &'a [T]) -> Self {
+        let inputs = engine.prepare(bars);
+        let contexts = engine.compute_contexts(bars);
+
+        Self {
+            engine,
+            bars,
+            inputs,
+            contexts,
+            current: 0,
+        }
+    }
+}
+
+impl<'a, T: OHLCV, C: ContextProvider> Iterator for PatternIterator<'a, T, C> {
+    type Item = BarPatterns;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current >= self.bars.len() {
+            return None;
+        }
+
+        let index = self.current;
+        let ctx = &self.contexts[index];
+        let patterns = self
+            .engine
+            .scan_at_internal(self.bars, &self.inputs, index, ctx);
+
+        self.current += 1;
+
+        Some(BarPatterns { index, patterns })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.bars.len().saturating_sub(self.current);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: OHLCV, C: ContextProvider> ExactSizeIterator for PatternIterator<'a, T, C> {}
+
+// ============================================================
+// BUILDER
+// ============================================================
+
+use std::collections::HashSet;
+
+/// Builder for creating PatternEngine instances
+pub struct EngineBuilder<C: ContextProvider = DefaultContextProvider> {
+    context_provider: C,
+    builtin: Vec<BuiltinDetector>,
+    custom: Vec<Box<dyn DynPatternDetector>>,
+    config: EngineConfig,
+    context_overrides: Vec<(PatternId, Box<dyn DynContextProvider>)>,
+    bias: Option<Direction>,
+}
+
+impl Default for EngineBuilder<DefaultContextProvider> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Strategy spec read by [`EngineBuilder::from_json`]
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EngineSpec {
+    patterns: Vec<PatternSpec>,
+    #[serde(default)]
+    min_strength: Option<f64>,
+}
+
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatternSpec {
+    id: String,
+    #[serde(default)]
+    params: serde_json::Map<String, serde_json::Value>,
+}
+
+impl EngineBuilder<DefaultContextProvider> {
+    /// Builder reproducing the engine captured by [`PatternEngine::snapshot`].
+    ///
+    /// Fails if a detector's `id` does not match its parameters' variant.
+    pub fn from_snapshot(snapshot: EngineSnapshot) -> Result<Self> {
+        let mut builder = Self::new().context_provider(snapshot.context_provider);
+        for DetectorSnapshot { id, detector } in snapshot.detectors {
+            if detector.id().as_str() != id {
+                return Err(PatternError::InvalidConfig(format!(
+                    "snapshot id `{id}` does not match detector `{}`",
+                    detector.id().as_str()
+                )));
+            }
+            builder = builder.add(detector);
+        }
+        builder.config = snapshot.config;
+        Ok(builder)
+    }
+
+    /// Engine from a JSON strategy spec (requires the `json` feature):
+    ///
+    /// ```json
+    /// {"patterns": [{"id": "CDL_MORNINGSTAR", "params": {"penetration": 0.4}}],
+    ///  "min_strength": 0.6}
+    /// ```
+    ///
+    /// Each pattern is built by [`BuiltinDetector::from_id_with_params`], so
+    /// `params` use the names from the detector's `param_meta()` and are
+    /// validated by its `with_params`; switches may be given as `true`/`false`.
+    /// Unknown ids, unknown or invalid parameters and malformed specs are
+    /// `PatternError::InvalidConfig`.
+    #[cfg(feature = "json")]
+    pub fn from_json(spec: &str) -> Result<PatternEngine<DefaultContextProvider>> {
+        let spec: EngineSpec =
+            serde_json::from_str(spec).map_err(|e| PatternError::InvalidConfig(e.to_string()))?;
+
+        let mut builder = Self::new();
+        for pattern in &spec.patterns {
+            let mut params = HashMap::with_capacity(pattern.params.len());
+            for (name, value) in &pattern.params {
+                let value = match value {
+                    serde_json::Value::Bool(flag) => f64::from(u8::from(*flag)),
+                    value => value.as_f64().ok_or_else(|| {
+                        PatternError::InvalidConfig(format!(
+                            "parameter `{name}` for `{}` must be a number",
+                            pattern.id
+                        ))
+                    })?,
+                };
+                params.insert(name.as_str(), value);
+            }
+            builder = builder.add(BuiltinDetector::from_id_with_params(&pattern.id, &params)?);
+        }
+        if let Some(min) = spec.min_strength {
+            builder = builder.min_strength(min);
+        }
+        builder.build()
+    }
+
+    pub fn new() -> Self {
+        Self {
+            context_provider: DefaultContextProvider::default(),
+            builtin: Vec::new(),
+            custom: Vec::new(),
+            config: EngineConfig::default(),
+            context_overrides: Vec::new(),
+            bias: None,
+        }
+    }
+
+    /// Set the trend window of the default context provider
+    ///
+    /// Call this before [`context_provider`](Self::context_provider):
+    /// swapping providers discards the setting.
+    pub fn trend_period(mut self, period: Period) -> Self {
+        self.context_provider.trend_period = period;
+        self
+    }
+
+    /// Set the volume averaging window of the default context provider
+    ///
+    /// Call this before [`context_provider`](Self::context_provider):
+    /// swapping providers discards the setting.
+    pub fn volume_period(mut self, period: Period) -> Self {
+        self.context_provider.volume_period = period;
+        self
+    }
+
+    /// Set the candle averaging window of the default context provider
+    ///
+    /// Call this before [`context_provider`](Self::context_provider):
+    /// swapping providers discards the setting.
+    pub fn candle_period(mut self, period: Period) -> Self {
+        self.context_provider.candle_period = period;
+        self
+    }
+}
+
+/// Generate an array of `BuiltinDetector` variants using `Default::default()` for each inner type.
+macro_rules! builtin_defaults {
+  ($($variant:ident),* $(,)?) => {
+    [$(BuiltinDetector::$variant(Default::default())),*]
+  };
+}
+
+impl<C: ContextProvider> EngineBuilder<C> {
+    /// Change context provider
+    pub fn context_provider<C2: ContextProvider>(self, provider: C2) -> EngineBuilder<C2> {
+        EngineBuilder {
+            context_provider: provider,
+            builtin: self.builtin,
+            custom: self.custom,
+            config: self.config,
+            context_overrides: self.context_overrides,
+            bias: self.bias,
+        }
+    }
+
+    /// Give the detectors with this id their own context provider.
+    ///
+    /// They see `provider`'s contexts (e.g. an EMA trend) while all other
+    /// detectors keep the engine's provider. Both are computed once per scan;
+    /// `scan_at`, which receives a single context, recomputes the override
+    /// contexts on every call unless they are passed in through
+    /// `PatternEngine::scan_at_with_overrides`. A later call for the same id
+    /// replaces the earlier one.
+    pub fn context_provider_for(
+        mut self,
+        id: PatternId,
+        provider: impl ContextProvider + 'static,
+    ) -> Self {
+        self.context_overrides.retain(|(o, _)| *o != id);
+        self.context_overrides.push((id, Box::new(provider)));
+        self
+    }
+
+    /// Add all builtin patterns with default configurations, except the
+    /// opt-in `HIKKAKE_CONFIRMED`
+    pub fn with_all_defaults(self) -> Self {
+        self.with_single_bar_defaults()
+            .with_two_bar_defaults()
+            .with_three_bar_defaults()
+            .with_multi_bar_defaults()
+            .with_extended_defaults()
+            .with_price_action_defaults()
+    }
+
+    /// Add only extended patterns with defaults.
+    ///
+    /// `HIKKAKE_CONFIRMED` is left out: with `CDL_HIKKAKE` registered it
+    /// would report the same confirmed setup twice. Add it explicitly when
+    /// scanning without `CDL_HIKKAKE` or with a wider `confirm_within`.
+    pub fn with_extended_defaults(mut self) -> Self {
+        self.builtin.extend(builtin_defaults![
+            PriceLines,
+            FallingWindow,
+            RisingWindow,
+            GappingDownDoji,
+            GappingUpDoji,
+            ConfirmedWindow,
+            ThreeGaps,
+            AboveTheStomach,
+            BelowTheStomach,
+            CollapsingDojiStar,
+            Deliberation,
+            LastEngulfingBottom,
+            LastEngulfingTop,
+            TwoBlackGapping,
+            MeetingLinesBearish,
+            MeetingLinesBullish,
+            NorthernDoji,
+            SouthernDoji,
+            BlackMarubozu,
+            WhiteMarubozu,
+            OpeningBlackMarubozu,
+            OpeningWhiteMarubozu,
+            BlackCandle,
+            WhiteCandle,
+            ShortBlack,
+            ShortWhite,
+            LongBlackDay,
+            LongWhiteDay,
+            BlackSpinningTop,
+            WhiteSpinningTop,
+            ShootingStar2Lines,
+            DownsideGapThreeMethods,
+            UpsideGapThreeMethods,
+            DownsideTasukiGap,
+            UpsideTasukiGap,
+            TowerTop,
+            TowerBottom,
+            FryPanBottom,
+            Consolidation,
+        ]);
+        self
+    }
+
+    /// Add trend-independent price action patterns with defaults.
+    ///
+    /// These fire on bar shapes alone and never consult the market context
+    /// trend (`KICKER`, and the `NEW_HIGH`/`NEW_LOW` breakouts).
+    pub fn with_price_action_defaults(mut self) -> Self {
+        self.builtin
+            .extend(builtin_defaults![Kicker, NewHigh, NewLow]);
+        self
+    }
+
+    /// Add only single-bar patterns with defaults (17)
+    pub fn with_single_bar_defaults(mut self) -> Self {
+        self.builtin.extend(builtin_defaults![
+            Doji,
+            DragonflyDoji,
+            GravestoneDoji,
+            LongLeggedDoji,
+            RickshawMan,
+            Hammer,
+            HangingMan,
+            InvertedHammer,
+            ShootingStar,
+            Takuri,
+            Marubozu,
+            ClosingMarubozu,
+            LongLine,
+            ShortLine,
+            SpinningTop,
+            HighWave,
+            BeltHold,
+        ]);
+        self
+    }
+
+    /// Add two-bar patterns with defaults (18)
+    pub fn with_two_bar_defaults(mut self) -> Self {
+        self.builtin.extend(builtin_defaults![
+            Engulfing,
+            Harami,
+            HaramiCross,
+            Piercing,
+            DarkCloudCover,
+            DojiStar,
+            Counterattack,
+            InNeck,
+            OnNeck,
+            Thrusting,
+            Kicking,
+            KickingByLength,
+            MatchingLow,
+            HomingPigeon,
+            SeparatingLines,
+            GapSideSideWhite,
+            TweezerTop,
+            TweezerBottom,
+        ]);
+        self
+    }
+
+    /// Add three-bar patterns with defaults (20)
+    pub fn with_three_bar_defaults(mut self) -> Self {
+        self.builtin.extend(builtin_defaults![
+            ThreeWhiteSoldiers,
+            ThreeBlackCrows,
+            ThreeInside,
+            ThreeOutside,
+            ThreeLineStrike,
+            ThreeStarsInSouth,
+            MorningStar,
+            EveningStar,
+            MorningDojiStar,
+            EveningDojiStar,
+            AbandonedBaby,
+            TwoCrows,
+            UpsideGapTwoCrows,
+            IdenticalThreeCrows,
+            AdvanceBlock,
+            StalledPattern,
+            StickSandwich,
+            TasukiGap,
+            Tristar,
+            Unique3River,
+        ]);
+        self
+    }
+
+    /// Add multi-bar patterns with defaults (8)
+    pub fn with_multi_bar_defaults(mut self) -> Self {
+        self.builtin.extend(builtin_defaults![
+            Breakaway,
+            ConcealingBabySwallow,
+            Hikkake,
+            HikkakeMod,
+            LadderBottom,
+            MatHold,
+            RiseFallThreeMethods,
+            XSideGapThreeMethods,
+        ]);
+        self
+    }
+
+    /// Add a builtin detector
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, detector: BuiltinDetector) -> Self {
+        self.builtin.push(detector);
+        self
+    }
+
+    /// Add several builtin detectors
+    pub fn add_all(mut self, detectors: impl IntoIterator<Item = BuiltinDetector>) -> Self {
+        self.builtin.extend(detectors);
+        self
+    }
+
+    /// Add with config validation
+    pub fn add_checked(mut self, detector: BuiltinDetector) -> Result<Self> {
+        detector.validate_config()?;
+        self.builtin.push(detector);
+        Ok(self)
+    }
+
+    /// Add a custom detector (slow path)
+    pub fn add_custom<D: DynPatternDetector + 'static>(mut self, detector: D) -> Self {
+        self.custom.push(Box::new(detector));
+        self
+    }
+
+    /// Set minimum strength filter
+    pub fn min_strength(mut self, strength: f64) -> Self {
+        self.config.min_strength = Some(strength);
+        self
+    }
+
+    /// Set how `close == open` bars are colored
+    pub fn color_convention(mut self, convention: ColorConvention) -> Self {
+        self.config.color_convention = convention;
+        self
+    }
+
+    /// Drop matches spanning fewer than `n` bars
+    pub fn min_span(mut self, n: usize) -> Self {
+        self.config.min_span = Some(n);
+        self
+    }
+
+    /// Drop matches whose last bar has volume below `volume`
+    pub fn min_volume(mut self, volume: f64) -> Self {
+        self.config.min_volume = Some(volume);
+        self
+    }
+
+    /// Drop neutral matches, keeping only bullish and bearish signals
+    pub fn directional_only(mut self) -> Self {
+        self.config.directional_only = true;
+        self
+    }
+
+    /// Only let trend-gated detectors fire on strong trends
+    pub fn require_strong_trend(mut self, enable: bool) -> Self {
+        self.config.require_strong_trend = enable;
+        self
+    }
+
+    /// Label reversal patterns by the trend they reverse, see
+    /// [`EngineConfig::direction_from_trend`]
+    pub fn direction_from_trend(mut self, enable: bool) -> Self {
+        self.config.direction_from_trend = enable;
+        self
+    }
+
+    /// Replace detector strengths with `f(match, context)`, clamped to 0..=1
+    pub fn strength_override(
+        mut self,
+        f: impl Fn(&PatternMatch, &MarketContext) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        self.config.strength_override = Some(Arc::new(f));
+        self
+    }
+
+    /// Enable/disable data validation
+    pub fn validate_data(mut self, enable: bool) -> Self {
+        self.config.validate_data = enable;
+        self
+    }
+
+    /// Emit `PatternId::NO_PATTERN` markers for empty bars in `scan_grouped`
+    pub fn emit_empty_markers(mut self, enable: bool) -> Self {
+        self.config.emit_empty_markers = enable;
+        self
+    }
+
+    /// Filter to specific patterns only
+    pub fn only_patterns(mut self, ids: impl IntoIterator<Item = PatternId>) -> Self {
+        self.config.pattern_filter = Some(ids.into_iter().collect());
+        self
+    }
+
+    /// Only register detectors that can signal `direction`.
+    ///
+    /// Applied in `build`: detectors whose `PatternId::typical_direction` is
+    /// another direction are dropped, so they cost nothing at scan time.
+    /// Bidirectional patterns and unknown (custom) ids are kept, as they may
+    /// still produce a match in the wanted direction; neutral patterns only
+    /// survive `bias(Direction::Neutral)`.
+    pub fn bias(mut self, direction: Direction) -> Self {
+        self.bias = Some(direction);
+        self
+    }
+
+    /// Build the engine.
+    ///
+    /// This is lenient: an engine without any detectors is valid and simply
+    /// scans nothing. Use [`EngineBuilder::build_strict`] to reject that case.
+    ///
+    /// Detectors are deduplicated by `PatternId`: if the same pattern was
+    /// registered more than once (e.g. `with_all_defaults()` followed by
+    /// `add(BuiltinDetector::Doji(..))`), only the first registration is kept.
+    /// Builtin detectors take precedence over custom ones.
+    pub fn build(self) -> Result<PatternEngine<C>> {
+        self.build_inner(false)
+    }
+
+    /// Build the engine, failing if no detectors remain after deduplication
+    /// and the [`bias`](EngineBuilder::bias) filter
+    pub fn build_strict(self) -> Result<PatternEngine<C>> {
+        self.build_inner(true)
+    }
+
+    fn build_inner(self, strict: bool) -> Result<PatternEngine<C>> {
+        let mut seen = HashSet::new();
+        let mut builtin = self.builtin;
+        builtin.retain(|d| seen.insert(d.id()));
+        let mut custom = self.custom;
+        custom.retain(|d| seen.insert(d.id()));
+        if let Some(bias) = self.bias {
+            let fits = |id: PatternId| id.typical_direction().map_or(true, |d| d == bias);
+            builtin.retain(|d| fits(d.id()));
+            custom.retain(|d| fits(d.id()));
+        }
+        if strict && builtin.is_empty() && custom.is_empty() {
+            return Err(PatternError::InvalidConfig(
+                "no detectors registered".to_string(),
+            ));
+        }
+
+        let engine = PatternEngine {
+            builtin,
+            custom,
+            context_provider: self.context_provider,
+            config: self.config,
+            disabled: Vec::new(),
+            context_overrides: self.context_overrides,
+        };
+        engine.validate()?;
+        Ok(engine)
+    }
+}
+
+// ============================================================
+// PARALLEL SCANNING
+// ============================================================
+
+use rayon::prelude::*;
+
+/// Result of scanning a single instrument
+#[derive(Debug)]
+pub struct ScanResult {
+    pub symbol: String,
+    pub patterns: Vec<PatternMatch>,
+}
+
+/// Error from scanning a single instrument
+#[derive(Debug)]
+pub struct ScanError {
+    pub symbol: String,
+    pub error: PatternError,
+}
+
+/// Parallel scanning of multiple instruments
+pub fn scan_parallel<'a, T, I, C>(
+    engine: &PatternEngine<C>,
+    instruments: I,
+) -> (Vec<ScanResult>, Vec<ScanError>)
+where
+    T: OHLCV + Sync + 'a,
+    I: IntoParallelIterator<Item = (&'a str, &'a [T])>,
+    C: ContextProvider + Sync,
+{
+    let results: Vec<_> = instruments
+        .into_par_iter()
+        .map(|(symbol, bars)| {
+            engine
+                .scan(bars)
+                .map(|patterns| ScanResult {
+                    symbol: symbol.to_string(),
+                    patterns,
+                })
+                .map_err(|error| ScanError {
+                    symbol: symbol.to_string(),
+                    error,
+                })
+        })
+        .collect();
+
+    let mut successes = Vec::new();
+    let mut errors = Vec::new();
+
+    for result in results {
+        match result {
+            Ok(r) => successes.push(r),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    (successes, errors)
+}
+
+/// Frequency of one pattern across a universe of scans
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PatternStats {
+    /// Total matches over all symbols
+    pub count: usize,
+    /// Mean strength of those matches
+    pub mean_strength: f64,
+    /// Number of symbols with at least one match
+    pub per_symbol: usize,
+}
+
+/// Summarize the results of [`scan_parallel`] per pattern
+pub fn aggregate_stats(results: &[ScanResult]) -> HashMap<PatternId, PatternStats> {
+    let mut stats: HashMap<PatternId, PatternStats> = HashMap::new();
+    for result in results {
+        let mut seen = Vec::new();
+        for m in &result.patterns {
+            let entry = stats.entry(m.pattern_id).or_default();
+            entry.count += 1;
+            entry.mean_strength += m.strength;
+            if !seen.contains(&m.pattern_id) {
+                seen.push(m.pattern_id);
+                entry.per_symbol += 1;
+            }
+        }
+    }
+    for entry in stats.values_mut() {
+        entry.mean_strength /= entry.count as f64;
+    }
+    stats
+}
+
+// ============================================================
+// NDJSON EXPORT
+// ============================================================
+
+/// One line of [`scan_to_ndjson`] output
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct NdjsonRecord<'a> {
+    symbol: &'a str,
+    pattern_id: &'static str,
+    /// Lowercase, as in `Direction::as_str` and `PatternMatch`'s `Display`
+    direction: &'static str,
+    strength: f64,
+    start_index: usize,
+    end_index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metric: Option<f64>,
+}
+
+/// Scan `bars` and write one JSON object per match to `out`, e.g.
+/// `{"symbol":"AAPL","pattern_id":"CDL_DOJI","direction":"neutral","strength":0.8,"start_index":41,"end_index":41}`.
+///
+/// Matches that report a [`metric`](PatternMatch::metric) carry it as a
+/// trailing `"metric"` key. Glue for shell pipelines (`jq`, `grep`, ...);
+/// requires the `json` feature.
+#[cfg(feature = "json")]
+pub fn scan_to_ndjson<T, C, W>(
+    engine: &PatternEngine<C>,
+    symbol: &str,
+    bars: &[T],
+    mut out: W,
+) -> Result<()>
+where
+    T: OHLCV,
+    C: ContextProvider,
+    W: std::io::Write,
+{
+    for m in engine.scan(bars)? {
+        let record = NdjsonRecord {
+            symbol,
+            pattern_id: m.pattern_id.as_str(),
+            direction: m.direction.as_str(),
+            strength: m.strength,
+            start_index: m.start_index,
+            end_index: m.end_index,
+            metric: m.metric,
+        };
+        serde_json::to_writer(&mut out, &record).map_err(|e| PatternError::Io(e.to_string()))?;
+        writeln!(out).map_err(|e| PatternError::Io(e.to_string()))?;
+    }
+    out.flush().map_err(|e| PatternError::Io(e.to_string()))
+}
+
+// ============================================================
+// TYPE ALIASES
+// ============================================================
+
+/// Default engine with DefaultContextProvider
+pub type DefaultEngine = PatternEngine<DefaultContextProvider>;
+
+// ============================================================
+// TESTS
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test OHLCV bar
+    #[derive(Debug, Clone)]
+    struct Bar {
+        o: f64,
+        h: f64,
+        l: f64,
+        c: f64,
+        v: f64,
+    }
+
+    impl Bar {
+        fn new(o: f64, h: f64, l: f64, c: f64) -> Self {
+            Self {
+                o,
+                h,
+                l,
+                c,
+                v: 1000.0,
+            }
+        }
+    }
+
+    impl OHLCV for Bar {
+        fn open(&self) -> f64 {
+            self.o
+        }
+
+        fn high(&self) -> f64 {
+            self.h
+        }
+
+        fn low(&self) -> f64 {
+            self.l
+        }
+
+        fn close(&self) -> f64 {
+            self.c
+        }
+
+        fn volume(&self) -> f64 {
+            self.v
+        }
+    }
+
+    fn make_downtrend_bars() -> Vec<Bar> {
+        (0..20)
+            .map(|i| {
+                let base = 100.0 - i as f64 * 2.0;
+                Bar::new(base, base + 1.0, base - 1.0, base - 0.5)
+            })
+            .collect()
+    }
+
+    fn make_uptrend_bars() -> Vec<Bar> {
+        (0..20)
+            .map(|i| {
+                let base = 100.0 + i as f64 * 2.0;
+                Bar::new(base, base + 1.0, base - 1.0, base + 0.5)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ratio_validation() {
+        assert!(Ratio::new(0.0).is_ok());
+        assert!(Ratio::new(1.0).is_ok());
+        assert!(Ratio::new(0.5).is_ok());
+        assert!(Ratio::new(-0.1).is_err());
+        assert!(Ratio::new(1.1).is_err());
+        assert!(Ratio::new(f64::NAN).is_err());
+        assert!(Ratio::new(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_period_validation() {
+        assert!(Period::new(1).is_ok());
+        assert!(Period::new(100).is_ok());
+        assert!(Period::new(0).is_err());
+    }
+
+    #[test]
+    fn test_ohlcv_ext() {
+        let bar = Bar::new(100.0, 110.0, 90.0, 105.0);
+        assert_eq!(bar.body(), 5.0);
+        assert_eq!(bar.range(), 20.0);
+        assert!(bar.is_bullish());
+        assert!(!bar.is_bearish());
+        assert!((bar.body_ratio().unwrap() - 0.25).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_ohlcv_ext_prices() {
+        let bar = Bar::new(100.0, 110.0, 90.0, 104.0);
+        assert!((bar.typical_price() - 101.333_333).abs() < 1e-6);
+        assert_eq!(bar.weighted_close(), 102.0);
+        assert_eq!(bar.median_price(), 100.0);
+    }
+
+    #[test]
+    fn test_trend_method_spiky_endpoint() {
+        // Steady decline, then a single spike on the last bar
+        let mut bars: Vec<Bar> = (0..14)
+            .map(|i| {
+                let c = 100.0 - i as f64 * 0.5;
+                Bar::new(c, c + 0.5, c - 0.5, c)
+            })
+            .collect();
+        bars.push(Bar::new(106.0, 106.5, 105.5, 106.0));
+        let last = bars.len() - 1;
+
+        let endpoint = DefaultContextProvider::default();
+        assert_eq!(endpoint.compute_all(&bars)[last].trend, Trend::StrongUp);
+
+        let regression = DefaultContextProvider {
+            trend_method: TrendMethod::LinearRegression,
+            ..Default::default()
+        };
+        assert!(regression.compute_all(&bars)[last].trend.is_down());
+    }
+
+    #[test]
+    fn test_trend_confirm_bars_ignores_one_bar_flip() {
+        // Steady decline with a single spike bar that flips the raw trend up
+        let mut bars: Vec<Bar> = (0..20)
+            .map(|i| {
+                let c = 100.0 - i as f64;
+                Bar::new(c, c + 0.5, c - 0.5, c)
+            })
+            .collect();
+        bars[17] = Bar::new(110.0, 110.5, 109.5, 110.0);
+
+        let raw = DefaultContextProvider::default().compute_all(&bars);
+        assert!(raw[16].trend.is_down());
+        assert!(raw[17].trend.is_up());
+        assert!(raw[18].trend.is_down());
+
+        let smoothed = DefaultContextProvider {
+            trend_confirm_bars: 2,
+            ..Default::default()
+        }
+        .compute_all(&bars);
+        assert!(smoothed[15..].iter().all(|c| c.trend.is_down()));
+        assert_eq!(smoothed[14].trend, Trend::Sideways);
+    }
+
+    #[test]
+    fn test_wilder_average_lags_sma_on_ramp() {
+        // Body and range of bar i are both i + 1
+        let bars: Vec<Bar> = (0..20)
+            .map(|i| {
+                let size = (i + 1) as f64;
+                Bar::new(100.0, 100.0 + size, 100.0, 100.0 + size)
+            })
+            .collect();
+
+        let sma = DefaultContextProvider::default().compute_all(&bars);
+        let wilder = DefaultContextProvider {
+            average_method: AverageMethod::Wilder,
+            ..Default::default()
+        }
+        .compute_all(&bars);
+
+        // Identical through the seed window
+        for i in 0..=10 {
+            assert_eq!(sma[i].avg_body, wilder[i].avg_body);
+        }
+        // SMA of bodies 2..=11 vs 5.5 + (11 - 5.5) / 10
+        assert!((sma[11].avg_body - 6.5).abs() < 1e-9);
+        assert!((wilder[11].avg_body - 6.05).abs() < 1e-9);
+        assert!((wilder[11].avg_range - 6.05).abs() < 1e-9);
+        for i in 11..20 {
+            assert!(wilder[i].avg_body < sma[i].avg_body);
+        }
+    }
+
+    #[test]
+    fn test_compute_all_parallel_matches_serial() {
+        let bars: Vec<Bar> = (0..500)
+            .map(|i| {
+                let x = i as f64;
+                let base = 100.0 + 10.0 * (x / 15.0).sin();
+                let body = 0.5 + (x * 0.7).cos().abs();
+                Bar::new(
+                    base,
+                    base + body + 0.3,
+                    base - body - 0.2,
+                    base + body * (x * 1.3).sin(),
+                )
+            })
+            .collect();
+
+        let providers = [
+            DefaultContextProvider::default(),
+            DefaultContextProvider {
+                average_method: AverageMethod::Wilder,
+                trend_confirm_bars: 3,
+                ..Default::default()
+            },
+        ];
+        for provider in &providers {
+            let serial = provider.compute_all(&bars);
+            let parallel = provider.compute_all_parallel(&bars);
+            assert_eq!(serial.len(), parallel.len());
+            for (s, p) in serial.iter().zip(&parallel) {
+                assert_eq!(s.trend, p.trend);
+                assert_eq!(s.volatility.to_bits(), p.volatility.to_bits());
+                assert_eq!(s.avg_volume.to_bits(), p.avg_volume.to_bits());
+                assert_eq!(s.avg_body.to_bits(), p.avg_body.to_bits());
+                assert_eq!(s.avg_upper_shadow.to_bits(), p.avg_upper_shadow.to_bits());
+                assert_eq!(s.avg_lower_shadow.to_bits(), p.avg_lower_shadow.to_bits());
+                assert_eq!(s.avg_range.to_bits(), p.avg_range.to_bits());
+                assert_eq!(s.avg_shadow.to_bits(), p.avg_shadow.to_bits());
+                assert_eq!(s.avg_range_5.to_bits(), p.avg_range_5.to_bits());
+            }
+        }
+        assert!(providers[0].compute_all_parallel::<Bar>(&[]).is_empty());
+
+        let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        let key = |m: &PatternMatch| (m.pattern_id, m.strength.to_bits(), m.end_index);
+        let serial: Vec<_> = engine.scan(&bars).unwrap().iter().map(key).collect();
+        let parallel: Vec<_> = engine
+            .scan_parallel_contexts(&bars)
+            .unwrap()
+            .iter()
+            .map(key)
+            .collect();
+        assert!(!serial.is_empty());
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn test_warmup_policy_clamp_suppresses_early_marubozu() {
+        // Two tiny bars make a medium bar look long against a partial window
+        let mut bars = vec![
+            Bar::new(100.0, 100.1, 100.0, 100.1),
+            Bar::new(100.1, 100.2, 100.1, 100.2),
+            Bar::new(100.2, 101.2, 100.2, 101.2),
+        ];
+        bars.extend((0..15).map(|i| {
+            let open = 101.0 + i as f64;
+            Bar::new(open, open + 3.0, open, open + 3.0)
+        }));
+
+        let scan_with = |warmup_policy| {
+            EngineBuilder::new()
+                .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+                .context_provider(DefaultContextProvider {
+                    warmup_policy,
+                    ..Default::default()
+                })
+                .build()
+                .unwrap()
+                .scan(&bars)
+                .unwrap()
+        };
+
+        let partial = scan_with(WarmupPolicy::UsePartial);
+        assert!(partial.iter().any(|m| m.end_index == 2));
+
+        let clamped = scan_with(WarmupPolicy::ClampToFirstFull);
+        assert!(clamped.iter().all(|m| m.end_index != 2));
+        assert!(clamped.iter().any(|m| m.end_index >= 10));
+
+        let nan = scan_with(WarmupPolicy::Nan);
+        assert!(nan.iter().all(|m| m.end_index >= 10));
+
+        // NaN averages reject in the direct context comparisons too
+        let nan_ctx = MarketContext {
+            avg_body: f64::NAN,
+            avg_range: f64::NAN,
+            ..Default::default()
+        };
+        let engulf = [
+            Bar::new(101.0, 101.2, 99.8, 100.0),
+            Bar::new(99.5, 102.0, 99.4, 101.8),
+        ];
+        let strict = EngulfingDetector {
+            require_above_avg_body: true,
+            ..Default::default()
+        };
+        let detect = |ctx: &MarketContext| PatternDetector::detect(&strict, &engulf, 1, ctx);
+        assert!(detect(&MarketContext::default()).is_some());
+        assert!(detect(&nan_ctx).is_none());
+        assert!(strict.explain(&engulf, 1, &nan_ctx).is_some());
+        assert!(ShadowMode::TrailingAvg.is_short(0.0, 1.0, 0.1, &MarketContext::default()));
+        assert!(!ShadowMode::TrailingAvg.is_short(0.0, 1.0, 0.1, &nan_ctx));
+
+        // Full windows are unaffected by the policy
+        let contexts = DefaultContextProvider {
+            warmup_policy: WarmupPolicy::ClampToFirstFull,
+            ..Default::default()
+        }
+        .compute_all(&bars);
+        let sma = DefaultContextProvider::default().compute_all(&bars);
+        assert_eq!(contexts[2].avg_body, sma[10].avg_body);
+        for i in 10..bars.len() {
+            assert_eq!(contexts[i].avg_body, sma[i].avg_body);
+        }
+    }
+
+    #[test]
+    fn test_incremental_contexts_match_compute_all() {
+        // Zig-zagging series with varying bodies, shadows and volumes
+        let mut seed = 7u64;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as f64 / (1u64 << 31) as f64
+        };
+        let mut close = 100.0;
+        let bars: Vec<Bar> = (0..300)
+            .map(|i| {
+                let open = close;
+                close = open + (next() - 0.5) * 4.0 + if (i / 40) % 2 == 0 { 0.4 } else { -0.4 };
+                let high = open.max(close) + next() * 2.0;
+                let low = open.min(close) - next() * 2.0;
+                Bar {
+                    v: 1000.0 + next() * 500.0,
+                    ..Bar::new(open, high, low, close)
+                }
+            })
+            .collect();
+
+        let providers = [
+            DefaultContextProvider::default(),
+            DefaultContextProvider::default()
+                .with_trend_method(TrendMethod::LinearRegression)
+                .with_trend_confirm_bars(3),
+            DefaultContextProvider::default()
+                .with_average_method(AverageMethod::Wilder)
+                .with_warmup_policy(WarmupPolicy::Nan),
+            DefaultContextProvider::default().with_candle_period(Period::new(3).unwrap()),
+        ];
+        for provider in providers {
+            let mut state = provider.incremental().unwrap();
+            let streamed: Vec<_> = bars.iter().map(|b| state.push(b)).collect();
+            // Debug output compares NaN averages too
+            assert_eq!(
+                format!("{streamed:?}"),
+                format!("{:?}", provider.compute_all(&bars))
+            );
+        }
+
+        let clamped =
+            DefaultContextProvider::default().with_warmup_policy(WarmupPolicy::ClampToFirstFull);
+        assert!(clamped.incremental().is_none());
+    }
+
+    #[test]
+    fn test_default_context_provider_legacy_deserialize() {
+        // Providers serialized before the trend/averaging options existed
+        let legacy = r#"{"trend_period":14,"volume_period":20,"candle_period":10}"#;
+        let provider: DefaultContextProvider = serde_json::from_str(legacy).unwrap();
+        assert_eq!(provider.trend_method, TrendMethod::EndpointChange);
+        assert_eq!(provider.average_method, AverageMethod::Sma);
+        assert_eq!(provider.trend_confirm_bars, 1);
+        assert_eq!(provider.warmup_policy, WarmupPolicy::UsePartial);
+
+        let built = DefaultContextProvider::default()
+            .with_trend_method(TrendMethod::LinearRegression)
+            .with_trend_confirm_bars(3);
+        assert_eq!(built.trend_method, TrendMethod::LinearRegression);
+        assert_eq!(built.trend_confirm_bars, 3);
+        assert_eq!(built.candle_period.get(), 10);
+    }
+
+    #[test]
+    fn test_caching_context_provider() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct CountingProvider {
+            calls: AtomicUsize,
+        }
+
+        impl ContextProvider for CountingProvider {
+            fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                DefaultContextProvider::default().compute_all(bars)
+            }
+        }
+
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .context_provider(CachingContextProvider::new(CountingProvider::default()))
+            .build()
+            .unwrap();
+
+        let bars = make_downtrend_bars();
+        let first = engine.scan(&bars).unwrap();
+        let second = engine.scan(&bars).unwrap();
+        assert_eq!(first.len(), second.len());
+
+        let provider = engine.context_provider.inner();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        let other = make_uptrend_bars();
+        engine.scan(&other).unwrap();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+
+        // Editing a bar in place (same address and length) misses the cache
+        let mut edited = other;
+        engine.scan(&edited).unwrap();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+        edited[5].c += 0.25;
+        engine.scan(&edited).unwrap();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_composite_context_provider() {
+        struct StubTrend;
+
+        impl ContextProvider for StubTrend {
+            fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
+                vec![
+                    MarketContext {
+                        trend: Trend::StrongUp,
+                        volatility: 0.5,
+                        ..Default::default()
+                    };
+                    bars.len()
+                ]
+            }
+        }
+
+        let bars = make_downtrend_bars();
+        let default = DefaultContextProvider::default().compute_all(&bars);
+        let composite = CompositeContextProvider::new(DefaultContextProvider::default(), StubTrend)
+            .compute_all(&bars);
+
+        assert_eq!(composite.len(), bars.len());
+        for (c, d) in composite.iter().zip(&default) {
+            assert_eq!(c.trend, Trend::StrongUp);
+            assert_eq!(c.volatility, 0.5);
+            assert_eq!(c.avg_body, d.avg_body);
+            assert_eq!(c.avg_range, d.avg_range);
+            assert_eq!(c.avg_volume, d.avg_volume);
+        }
+        assert!(default[15..].iter().all(|c| c.trend.is_down()));
+    }
+
+    #[test]
+    fn test_require_strong_trend_suppresses_weak_down_southern_doji() {
+        struct Fixed(Trend);
+
+        impl ContextProvider for Fixed {
+            fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
+                vec![
+                    MarketContext {
+                        trend: self.0,
+                        ..Default::default()
+                    };
+                    bars.len()
+                ]
+            }
+        }
+
+        let bars = vec![Bar::new(100.0, 102.0, 98.0, 100.1)];
+        let scan = |trend, strict| {
+            EngineBuilder::new()
+                .add(BuiltinDetector::SouthernDoji(
+                    SouthernDojiDetector::with_defaults(),
+                ))
+                .context_provider(Fixed(trend))
+                .require_strong_trend(strict)
+                .build()
+                .unwrap()
+                .scan(&bars)
+                .unwrap()
+                .len()
+        };
+
+        assert_eq!(scan(Trend::WeakDown, false), 1);
+        assert_eq!(scan(Trend::WeakDown, true), 0);
+        assert_eq!(scan(Trend::StrongDown, true), 1);
+    }
+
+    #[test]
+    fn test_direction_from_trend_relabels_engulfing() {
+        // Bullish engulfing geometry at the top of an uptrend
+        let mut bars = make_uptrend_bars();
+        bars.push(Bar::new(139.0, 139.5, 138.0, 138.5));
+        bars.push(Bar::new(138.0, 141.0, 137.5, 140.5));
+        let last = bars.len() - 1;
+
+        let direction = |from_trend| {
+            EngineBuilder::new()
+                .add(BuiltinDetector::Engulfing(
+                    EngulfingDetector::with_defaults(),
+                ))
+                .add(BuiltinDetector::LongLine(LongLineDetector::with_defaults()))
+                .direction_from_trend(from_trend)
+                .build()
+                .unwrap()
+                .scan(&bars)
+                .unwrap()
+                .into_iter()
+                .filter(|m| m.end_index == last)
+                .map(|m| (m.pattern_id, m.direction))
+                .collect::<Vec<_>>()
+        };
+
+        let geometric = direction(false);
+        assert!(geometric.contains(&(PatternId("CDL_ENGULFING"), Direction::Bullish)));
+        let relabeled = direction(true);
+        assert!(relabeled.contains(&(PatternId("CDL_ENGULFING"), Direction::Bearish)));
+        // Patterns with a typical direction (the neutral long line) are untouched
+        let long_line = |ms: &[(PatternId, Direction)]| {
+            ms.iter()
+                .find(|(id, _)| *id == PatternId("CDL_LONGLINE"))
+                .copied()
+        };
+        assert!(long_line(&geometric).is_some());
+        assert_eq!(long_line(&relabeled), long_line(&geometric));
+    }
+
+    #[test]
+    fn test_direction_from_trend_keeps_continuations() {
+        // Bullish separating line continuing an uptrend
+        let mut bars = make_uptrend_bars();
+        bars.push(Bar::new(141.0, 141.5, 137.0, 138.0));
+        bars.push(Bar::new(141.0, 147.0, 140.9, 146.8));
+        let last = bars.len() - 1;
+
+        let directions = EngineBuilder::new()
+            .add(BuiltinDetector::SeparatingLines(
+                SeparatingLinesDetector::with_defaults(),
+            ))
+            .direction_from_trend(true)
+            .build()
+            .unwrap()
+            .scan(&bars)
+            .unwrap()
+            .into_iter()
+            .filter(|m| m.end_index == last)
+            .map(|m| m.direction)
+            .collect::<Vec<_>>();
+        assert_eq!(directions, vec![Direction::Bullish]);
+        assert!(!PatternId("CDL_SEPARATINGLINES").is_trend_reversal());
+        assert!(!PatternId("CUSTOM").is_trend_reversal());
+    }
+
+    #[test]
+    fn test_context_provider_for_overrides_trend_per_detector() {
+        struct StubDown;
+
+        impl ContextProvider for StubDown {
+            fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
+                vec![
+                    MarketContext {
+                        trend: Trend::StrongDown,
+                        ..Default::default()
+                    };
+                    bars.len()
+                ]
+            }
+        }
+
+        // Flat series ending in a hammer shape: no trend for the default provider
+        let mut bars: Vec<Bar> = (0..20)
+            .map(|_| Bar::new(100.0, 102.0, 98.0, 101.0))
+            .collect();
+        bars.push(Bar::new(100.0, 100.05, 99.95, 100.0));
+        bars.push(Bar::new(100.0, 100.1, 97.0, 100.05));
+        let last = bars.len() - 1;
+
+        let builder = || {
+            EngineBuilder::new()
+                .add(BuiltinDetector::Hammer(HammerDetector::default()))
+                .add(BuiltinDetector::HangingMan(HangingManDetector::default()))
+        };
+        let ids_at_last = |engine: &PatternEngine| -> Vec<&'static str> {
+            engine
+                .scan(&bars)
+                .unwrap()
+                .iter()
+                .filter(|m| m.end_index == last)
+                .map(|m| m.pattern_id.as_str())
+                .collect()
+        };
+
+        let default = builder().build().unwrap();
+        assert!(ids_at_last(&default).is_empty());
+
+        let overridden = builder()
+            .context_provider_for(
+                PatternId("CDL_HAMMER"),
+                CompositeContextProvider::new(DefaultContextProvider::default(), StubDown),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(ids_at_last(&overridden), ["CDL_HAMMER"]);
+
+        let contexts = overridden.compute_contexts(&bars);
+        let at_last = overridden.scan_at(&bars, last, &contexts[last]);
+        assert_eq!(at_last.len(), 1);
+        let overrides = overridden.compute_override_contexts(&bars);
+        let per_bar: Vec<PatternMatch> = (0..bars.len())
+            .flat_map(|i| overridden.scan_at_with_overrides(&bars, i, &contexts[i], &overrides))
+            .collect();
+        assert_eq!(per_bar.len(), overridden.scan(&bars).unwrap().len());
+        assert_eq!(per_bar.last().unwrap().pattern_id, PatternId("CDL_HAMMER"));
+        assert!(overridden
+            .explain_at(&bars, last, PatternId("CDL_HAMMER"))
+            .unwrap()
+            .contains("matched"));
+    }
+
+    #[test]
+    fn test_window_stats_known_values() {
+        let bars = vec![
+            Bar::new(1.0, 1.5, 0.5, 1.0),
+            Bar::new(1.0, 2.5, 0.9, 2.0),
+            Bar::new(2.0, 3.2, 1.8, 3.0),
+            Bar::new(3.0, 4.0, 2.5, 4.0),
+        ];
+        let stats = window_stats(&bars);
+        assert_eq!(stats.mean_close, 2.5);
+        assert!((stats.std_close - 1.25f64.sqrt()).abs() < 1e-12);
+        assert_eq!(stats.max_high, 4.0);
+        assert_eq!(stats.min_low, 0.5);
+        assert_eq!(stats.total_volume, 4000.0);
+
+        assert_eq!(window_stats::<Bar>(&[]), WindowStats::default());
+    }
+
+    #[test]
+    fn test_nan_close_handling() {
+        let bar = Bar::new(100.0, 110.0, 90.0, f64::NAN);
+        assert_eq!(bar.body_ratio(), None);
+        assert_eq!(bar.upper_shadow_ratio(), None);
+        assert_eq!(bar.lower_shadow_ratio(), None);
+
+        let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        let mut bars = make_downtrend_bars();
+        bars[10].c = f64::NAN;
+        bars.push(Bar::new(60.0, 61.0, 59.0, f64::NAN));
+        assert!(engine.scan(&bars).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "min_bars() is 2")]
+    fn test_checked_detector_catches_short_min_bars() {
+        /// Reads two bars back while claiming a two-bar window
+        struct Lookback;
+
+        impl PatternDetector for Lookback {
+            fn id(&self) -> PatternId {
+                PatternId("LOOKBACK")
+            }
+
+            fn min_bars(&self) -> usize {
+                2
+            }
+
+            fn detect<T: OHLCV>(
+                &self,
+                bars: &[T],
+                index: usize,
+                _ctx: &MarketContext,
+            ) -> Option<PatternMatch> {
+                let _ = bars.get(index.checked_sub(2)?)?.close();
+                None
+            }
+        }
+
+        let engine = EngineBuilder::new()
+            .add_custom(CheckedDetector::new(Lookback))
+            .build()
+            .unwrap();
+        let _ = engine.scan(&make_uptrend_bars());
+    }
+
+    #[test]
+    #[should_panic(expected = "min_bars() is 1")]
+    fn test_checked_detector_catches_dyn_detector_reads() {
+        /// Implements the object-safe trait directly, reading the previous bar
+        struct PreviousClose;
+
+        impl DynPatternDetector for PreviousClose {
+            fn id(&self) -> PatternId {
+                PatternId("PREVIOUS_CLOSE")
+            }
+
+            fn min_bars(&self) -> usize {
+                1
+            }
+
+            fn detect(
+                &self,
+                bars: &[&dyn OHLCV],
+                index: usize,
+                _ctx: &MarketContext,
+            ) -> Option<PatternMatch> {
+                let _ = bars[index.checked_sub(1)?].close();
+                None
+            }
+
+            fn validate_config(&self) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        let engine = EngineBuilder::new()
+            .add_custom(CheckedDetector::new(PreviousClose))
+            .build()
+            .unwrap();
+        let _ = engine.scan(&make_uptrend_bars());
+    }
+
+    #[test]
+    fn test_checked_detector_ignores_trailing_averages() {
+        // InNeck reads trailing averages before its two-bar window
+        let engine = EngineBuilder::new()
+            .add_custom(CheckedDetector::new(InNeckDetector::default()))
+            .build()
+            .unwrap();
+        let plain = EngineBuilder::new()
+            .add_custom(InNeckDetector::default())
+            .build()
+            .unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(61.0, 61.5, 54.5, 55.0));
+        bars.push(Bar::new(53.0, 55.5, 52.5, 55.05));
+        let expected = plain.scan(&bars).unwrap();
+        assert!(!expected.is_empty());
+        assert_eq!(engine.scan(&bars).unwrap().len(), expected.len());
+    }
+
+    #[test]
+    fn test_checked_detector_passes_consistent_detector() {
+        let engine = EngineBuilder::new()
+            .add_custom(CheckedDetector::new(EngulfingDetector::with_defaults()))
+            .build()
+            .unwrap();
+        let plain = EngineBuilder::new()
+            .add_custom(EngulfingDetector::with_defaults())
+            .build()
+            .unwrap();
+        let bars = make_downtrend_bars();
+        assert_eq!(
+            engine.scan(&bars).unwrap().len(),
+            plain.scan(&bars).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_bias_skips_opposite_detectors() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Hammer(HammerDetector::with_defaults()))
+            .add(BuiltinDetector::ShootingStar(
+                ShootingStarDetector::with_defaults(),
+            ))
+            .add(BuiltinDetector::Engulfing(
+                EngulfingDetector::with_defaults(),
+            ))
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .bias(Direction::Bearish)
+            .build()
+            .unwrap();
+        assert_eq!(
+            engine.detector_ids(),
+            vec![PatternId("CDL_SHOOTINGSTAR"), PatternId("CDL_ENGULFING")]
+        );
+
+        let all = EngineBuilder::new()
+            .with_all_defaults()
+            .bias(Direction::Bullish)
+            .build()
+            .unwrap();
+        assert!(all
+            .detector_ids()
+            .iter()
+            .all(|id| !id.is_typically_bearish() && !id.is_neutral()));
+        assert!(all.detector_ids().contains(&PatternId("CDL_HAMMER")));
+    }
+
+    #[test]
+    fn test_engine_builder() {
+        let engine = EngineBuilder::new().with_all_defaults().build();
+        assert!(engine.is_ok());
+    }
+
+    #[test]
+    fn test_build_strict_requires_detectors() {
+        assert!(EngineBuilder::new().build().is_ok());
+
+        let err = EngineBuilder::new().build_strict().err().unwrap();
+        match err {
+            PatternError::InvalidConfig(msg) => assert_eq!(msg, "no detectors registered"),
+            other => panic!("unexpected error: {other}"),
+        }
+
+        assert!(EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .build_strict()
+            .is_ok());
+
+        // A bias that filters out every detector leaves nothing to scan
+        assert!(EngineBuilder::new()
+            .add(BuiltinDetector::Hammer(HammerDetector::default()))
+            .bias(Direction::Bearish)
+            .build_strict()
+            .is_err());
+        assert!(EngineBuilder::new()
+            .add(BuiltinDetector::Hammer(HammerDetector::default()))
+            .bias(Direction::Bearish)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_empty_scan() {
+        let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        let bars: Vec<Bar> = vec![];
+        let patterns = engine.scan(&bars).unwrap();
+        assert!(patterns.is_empty());
+    }
+
+    #[test]
+    fn test_doji_detection() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .build()
+            .unwrap();
+
+        let bars = vec![
+            Bar::new(100.0, 110.0, 90.0, 100.5), // Doji
+        ];
+
+        let patterns = engine.scan(&bars).unwrap();
+        assert!(!patterns.is_empty());
+        assert_eq!(patterns[0].pattern_id, PatternId("CDL_DOJI"));
+    }
+
+    #[test]
+    fn test_scan_grouped() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+            .build()
+            .unwrap();
 
-/// Error from scanning a single instrument
-#[derive(Debug)]
-pub struct ScanError {
-    pub symbol: String,
-    pub error: PatternError,
-}
+        let bars = vec![
+            Bar::new(100.0, 110.0, 90.0, 100.5),  // Doji
+            Bar::new(100.0, 105.0, 100.0, 105.0), // Marubozu (close = high)
+            Bar::new(100.0, 100.0, 95.0, 95.0),   // Bearish Marubozu (close = low)
+        ];
 
-/// Parallel scanning of multiple instruments
-pub fn scan_parallel<'a, T, I, C>(
-    engine: &PatternEngine<C>,
-    instruments: I,
-) -> (Vec<ScanResult>, Vec<ScanError>)
-where
-    T: OHLCV + Sync + 'a,
-    I: IntoParallelIterator<Item = (&'a str, &'a [T])>,
-    C: ContextProvider + Sync,
-{
-    let results: Vec<_> = instruments
-        .into_par_iter()
-        .map(|(symbol, bars)| {
-            engine
-                .scan(bars)
-                .map(|patterns| ScanResult {
-                    symbol: symbol.to_string(),
-                    patterns,
-                })
-                .map_err(|error| ScanError {
-                    symbol: symbol.to_string(),
-                    error,
-                })
-        })
-        .collect();
+        let grouped = engine.scan_grouped(&bars).unwrap();
+        assert_eq!(grouped.len(), bars.len());
+    }
 
-    let mut successes = Vec::new();
-    let mut errors = Vec::new();
+    #[test]
+    fn test_scan_dominant_keeps_strongest() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::LongLine(LongLineDetector::with_defaults()))
+            .add(BuiltinDetector::BeltHold(BeltHoldDetector::with_defaults()))
+            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+            .build()
+            .unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(59.5, 64.0, 59.5, 64.0));
+        let last = bars.len() - 1;
 
-    for result in results {
-        match result {
-            Ok(r) => successes.push(r),
-            Err(e) => errors.push(e),
+        let grouped = engine.scan_grouped(&bars).unwrap();
+        assert!(grouped[last].len() > 1);
+        let strongest = grouped[last]
+            .iter()
+            .map(|m| m.strength)
+            .fold(f64::MIN, f64::max);
+
+        let dominant = engine.scan_dominant(&bars).unwrap();
+        assert_eq!(dominant.len(), bars.len());
+        assert_eq!(dominant[last].unwrap().strength, strongest);
+        for (slot, patterns) in dominant.iter().zip(&grouped) {
+            assert_eq!(slot.is_some(), !patterns.is_empty());
         }
     }
 
-    (successes, errors)
-}
+    #[test]
+    fn test_directional_only_drops_neutral_matches() {
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0)); // doji
+        bars.push(Bar::new(61.0, 61.2, 59.8, 60.0));
+        bars.push(Bar::new(59.5, 64.0, 59.5, 64.0)); // bullish engulfing
+        let builder = || {
+            EngineBuilder::new()
+                .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+                .add(BuiltinDetector::Engulfing(
+                    EngulfingDetector::with_defaults(),
+                ))
+        };
 
-// ============================================================
-// TYPE ALIASES
-// ============================================================
+        let all = builder().build().unwrap().scan(&bars).unwrap();
+        assert!(all.iter().any(|m| m.direction == Direction::Neutral));
+
+        let directional = builder()
+            .directional_only()
+            .build()
+            .unwrap()
+            .scan(&bars)
+            .unwrap();
+        assert!(directional
+            .iter()
+            .all(|m| m.direction != Direction::Neutral));
+        assert!(directional
+            .iter()
+            .any(|m| m.pattern_id.as_str() == "CDL_ENGULFING" && m.end_index == bars.len() - 1));
+    }
+
+    #[test]
+    fn test_scan_grouped_empty_markers() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .emit_empty_markers(true)
+            .build()
+            .unwrap();
+
+        // Flat series: bullish bars with long bodies, nothing matches
+        let bars: Vec<Bar> = (0..5)
+            .map(|_| Bar::new(100.0, 110.5, 99.5, 110.0))
+            .collect();
+
+        let grouped = engine.scan_grouped(&bars).unwrap();
+        assert_eq!(grouped.len(), bars.len());
+        for (i, patterns) in grouped.iter().enumerate() {
+            assert_eq!(patterns.len(), 1);
+            assert_eq!(patterns[0].pattern_id, PatternId::NO_PATTERN);
+            assert_eq!(patterns[0].direction, Direction::Neutral);
+            assert_eq!(patterns[0].start_index, i);
+        }
+    }
+
+    #[test]
+    fn test_scan_grouped_sparse_skips_empty_bars() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .build()
+            .unwrap();
+
+        // Long-bodied bars with a doji every 25th bar
+        let bars: Vec<Bar> = (0..100)
+            .map(|i| {
+                if i % 25 == 24 {
+                    Bar::new(100.0, 110.0, 90.0, 100.5)
+                } else {
+                    Bar::new(100.0, 110.5, 99.5, 110.0)
+                }
+            })
+            .collect();
+
+        let grouped = engine.scan_grouped(&bars).unwrap();
+        let sparse = engine.scan_grouped_sparse(&bars).unwrap();
+        let non_empty = grouped.iter().filter(|g| !g.is_empty()).count();
+        assert!(non_empty > 0 && non_empty < bars.len() / 10);
+        assert_eq!(sparse.len(), non_empty);
+        for (i, patterns) in &sparse {
+            assert_eq!(patterns.len(), grouped[*i].len());
+        }
+    }
+
+    #[test]
+    fn test_scan_score_weights() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .add(BuiltinDetector::DragonflyDoji(
+                DragonflyDojiDetector::with_defaults(),
+            ))
+            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+            .build()
+            .unwrap();
+
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 60.0, 50.0, 60.0)); // Dragonfly doji
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0)); // Bullish marubozu
+        let doji_idx = bars.len() - 2;
+        let maru_idx = bars.len() - 1;
+
+        let scores = engine.scan_score(&bars, &ScoreModel::new()).unwrap();
+        assert_eq!(scores.len(), bars.len());
+        assert!(scores[doji_idx] > 0.0);
+        assert!(scores[maru_idx] > 0.0);
+
+        // Both reweighted patterns are directional, so the weights show up
+        let model = ScoreModel::new()
+            .with_weight(PatternId("CDL_DRAGONFLYDOJI"), 0.0)
+            .with_weight(PatternId("CDL_MARUBOZU"), 2.0);
+        let weighted = engine.scan_score(&bars, &model).unwrap();
+        assert_eq!(weighted[doji_idx], 0.0);
+        assert_eq!(weighted[maru_idx], 2.0 * scores[maru_idx]);
+    }
+
+    #[test]
+    fn test_scan_calibrated_halves_doji() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+            .build()
+            .unwrap();
+
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 62.0, 58.0, 60.0)); // Doji
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0)); // Bullish marubozu
+
+        let raw = engine.scan(&bars).unwrap();
+        let calibration = StrengthCalibration::new()
+            .with_rate(PatternId("CDL_DOJI"), 0.5)
+            .with_rate(PatternId("CDL_MARUBOZU"), 3.0);
+        let calibrated = engine.scan_calibrated(&bars, &calibration).unwrap();
+        assert_eq!(calibrated.len(), raw.len());
+
+        let doji = |ms: &[PatternMatch]| {
+            *ms.iter()
+                .find(|m| m.pattern_id == PatternId("CDL_DOJI"))
+                .unwrap()
+        };
+        assert!((doji(&calibrated).strength - doji(&raw).strength * 0.5).abs() < 1e-12);
+        assert!(calibrated
+            .iter()
+            .filter(|m| m.pattern_id == PatternId("CDL_MARUBOZU"))
+            .all(|m| m.strength <= 1.0));
+    }
+
+    #[test]
+    fn test_explain_at_engulfing_near_miss() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Engulfing(
+                EngulfingDetector::with_defaults(),
+            ))
+            .build()
+            .unwrap();
+
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(62.0, 62.5, 59.5, 60.0)); // Bearish
+        bars.push(Bar::new(60.5, 62.0, 60.0, 61.5)); // Bullish, body inside previous
+        let idx = bars.len() - 1;
+
+        let text = engine
+            .explain_at(&bars, idx, PatternId("CDL_ENGULFING"))
+            .unwrap();
+        assert!(text.starts_with("CDL_ENGULFING at"));
+        assert!(
+            text.contains("no match: body 1.0000 below 1 x previous body 2.0000"),
+            "{text}"
+        );
+        assert!(text.contains(&format!("bar {}:", idx - 1)));
+        assert!(text.contains("avg_body="));
+
+        // Real engulfing for comparison
+        bars[idx] = Bar::new(59.5, 63.0, 59.0, 62.5);
+        let text = engine
+            .explain_at(&bars, idx, PatternId("CDL_ENGULFING"))
+            .unwrap();
+        assert!(text.contains("matched Bullish"), "{text}");
+
+        assert!(engine
+            .explain_at(&bars, idx, PatternId("CDL_DOJI"))
+            .is_none());
+        assert!(engine
+            .explain_at(&bars, 0, PatternId("CDL_ENGULFING"))
+            .unwrap()
+            .contains("not enough bars"));
+    }
+
+    #[test]
+    fn test_min_span_drops_single_bar_matches() {
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(62.0, 62.5, 59.5, 60.0)); // Bearish
+        bars.push(Bar::new(59.5, 63.0, 59.0, 62.5)); // Bullish engulfing
+        bars.push(Bar::new(62.5, 67.5, 57.5, 62.5)); // Doji
+
+        let all = EngineBuilder::new().with_all_defaults().build().unwrap();
+        let patterns = all.scan(&bars).unwrap();
+        assert!(patterns.iter().any(|p| p.start_index == p.end_index));
+        assert!(patterns.iter().any(|p| p.start_index < p.end_index));
+
+        let engine = EngineBuilder::new()
+            .with_all_defaults()
+            .min_span(2)
+            .build()
+            .unwrap();
+        let filtered = engine.scan(&bars).unwrap();
+        assert!(!filtered.is_empty());
+        assert!(filtered.iter().all(|p| p.end_index > p.start_index));
+        assert_eq!(
+            filtered.len(),
+            patterns
+                .iter()
+                .filter(|p| p.end_index > p.start_index)
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_pattern_match_log_string() {
+        let m = PatternMatch {
+            pattern_id: PatternId("CDL_HAMMER"),
+            direction: Direction::Bullish,
+            strength: 0.7234,
+            start_index: 18,
+            end_index: 18,
+            metric: None,
+        };
+        assert_eq!(
+            m.to_log_string(),
+            "CDL_HAMMER bullish strength=0.72 bars=18..18"
+        );
+        assert_eq!(format!("{m}"), m.to_log_string());
+    }
+
+    #[test]
+    fn test_scan_from_skips_warmup() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .build()
+            .unwrap();
+
+        let mut bars = make_downtrend_bars();
+        bars[3] = Bar::new(92.0, 95.0, 89.0, 92.0); // Doji in warmup
+        bars[15] = Bar::new(70.0, 73.0, 67.0, 70.0); // Doji after warmup
+
+        let all = engine.scan(&bars).unwrap();
+        assert!(all.iter().any(|p| p.end_index == 3));
+
+        let from = engine.scan_from(&bars, 10).unwrap();
+        assert!(from.iter().all(|p| p.end_index >= 10));
+        assert!(from.iter().any(|p| p.end_index == 15));
+        assert_eq!(from.len(), all.iter().filter(|p| p.end_index >= 10).count());
+        assert!(engine.scan_from(&bars, 100).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_color_convention_flat_bar() {
+        let flat = Bar::new(100.0, 103.0, 97.0, 100.0);
+        assert!(ColorConvention::TaLib.is_white(&flat));
+        assert!(!ColorConvention::TaLib.is_black(&flat));
+        assert!(!ColorConvention::Strict.is_white(&flat));
+        assert!(!ColorConvention::Strict.is_black(&flat));
+        assert_eq!(ColorConvention::TaLib.sign(&flat), 1);
+        assert_eq!(ColorConvention::Strict.sign(&flat), 0);
+        assert_eq!(candle_color(&flat), 1);
+        assert_eq!(candle_color(&Bar::new(100.0, 103.0, 97.0, 99.99)), -1);
+        assert_eq!(candle_color(&Bar::new(100.0, 103.0, 97.0, 100.01)), 1);
+
+        let mut bars = make_downtrend_bars();
+        bars.push(flat);
+        let direction = |convention| {
+            let engine = EngineBuilder::new()
+                .add(BuiltinDetector::SpinningTop(
+                    SpinningTopDetector::with_defaults(),
+                ))
+                .color_convention(convention)
+                .build()
+                .unwrap();
+            let patterns = engine.scan(&bars).unwrap();
+            let last = patterns.iter().find(|p| p.end_index == bars.len() - 1);
+            last.unwrap().direction
+        };
+        assert_eq!(direction(ColorConvention::TaLib), Direction::Bullish);
+        assert_eq!(direction(ColorConvention::Strict), Direction::Neutral);
+
+        // Extended detectors follow the same convention
+        let ids = |convention| {
+            EngineBuilder::new()
+                .add(BuiltinDetector::WhiteSpinningTop(
+                    WhiteSpinningTopDetector::with_defaults(),
+                ))
+                .add(BuiltinDetector::BlackSpinningTop(
+                    BlackSpinningTopDetector::with_defaults(),
+                ))
+                .color_convention(convention)
+                .build()
+                .unwrap()
+                .scan(&bars)
+                .unwrap()
+                .into_iter()
+                .filter(|p| p.end_index == bars.len() - 1)
+                .map(|p| p.pattern_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(ColorConvention::TaLib),
+            vec![PatternId("WHITE_SPINNING_TOP")]
+        );
+        assert!(ids(ColorConvention::Strict).is_empty());
+    }
+
+    #[test]
+    fn test_scan_with_progress() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .build()
+            .unwrap();
+
+        let bars: Vec<Bar> = (0..25_000)
+            .map(|i| {
+                let base = 100.0 + (i % 7) as f64;
+                Bar::new(base, base + 2.0, base - 2.0, base + 1.0)
+            })
+            .collect();
+
+        let mut calls = Vec::new();
+        let patterns = engine
+            .scan_with_progress(&bars, |done, total| calls.push((done, total)))
+            .unwrap();
+        assert_eq!(
+            calls,
+            vec![(10_000, 25_000), (20_000, 25_000), (25_000, 25_000)]
+        );
+        assert_eq!(patterns.len(), engine.scan(&bars).unwrap().len());
+
+        let mut last = None;
+        engine
+            .scan_with_progress(&bars[..0], |done, total| last = Some((done, total)))
+            .unwrap();
+        assert_eq!(last, Some((0, 0)));
+    }
+
+    #[test]
+    fn test_estimate_matches_equals_scan() {
+        let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+
+        let mut bars = make_downtrend_bars();
+        bars.extend(make_uptrend_bars());
+        bars.push(Bar::new(140.0, 145.0, 135.0, 140.0));
+
+        let actual = engine.scan(&bars).unwrap().len();
+        assert!(actual > 0);
+        assert_eq!(engine.estimate_matches(&bars), actual);
+        assert_eq!(engine.estimate_matches::<Bar>(&[]), 0);
+    }
+
+    #[test]
+    fn test_add_all_from_ids() {
+        let ids = [
+            PatternId("CDL_DOJI"),
+            PatternId("CDL_ENGULFING"),
+            PatternId("NOT_A_PATTERN"),
+            PatternId("CDL_MORNINGSTAR"),
+        ];
+        assert!(BuiltinDetector::from_id(PatternId("NOT_A_PATTERN")).is_none());
+
+        let engine = EngineBuilder::new()
+            .add_all(ids.iter().filter_map(|&id| BuiltinDetector::from_id(id)))
+            .build()
+            .unwrap();
+
+        let registered: Vec<PatternId> = engine.builtin.iter().map(|d| d.id()).collect();
+        assert_eq!(
+            registered,
+            vec![
+                PatternId("CDL_DOJI"),
+                PatternId("CDL_ENGULFING"),
+                PatternId("CDL_MORNINGSTAR"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overall_trend() {
+        let provider = DefaultContextProvider::default();
+        assert!(overall_trend(&make_uptrend_bars(), &provider).is_up());
+        assert!(overall_trend(&make_downtrend_bars(), &provider).is_down());
+
+        let flat: Vec<Bar> = (0..20)
+            .map(|_| Bar::new(100.0, 101.0, 99.0, 100.5))
+            .collect();
+        assert_eq!(overall_trend(&flat, &provider), Trend::Sideways);
+        assert_eq!(overall_trend::<Bar>(&[], &provider), Trend::Sideways);
+    }
+
+    #[test]
+    fn test_set_enabled_toggles_detector() {
+        let mut engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+            .build()
+            .unwrap();
+
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0)); // Doji
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0)); // Marubozu
+
+        let count = |engine: &PatternEngine, id| {
+            let patterns = engine.scan(&bars).unwrap();
+            patterns.iter().filter(|p| p.pattern_id == id).count()
+        };
+        let doji = PatternId("CDL_DOJI");
+        let marubozu = PatternId("CDL_MARUBOZU");
+        let dojis = count(&engine, doji);
+        let marubozus = count(&engine, marubozu);
+        assert!(dojis > 0 && marubozus > 0);
+
+        engine.set_enabled(doji, false);
+        assert!(!engine.is_enabled(doji));
+        assert_eq!(count(&engine, doji), 0);
+        assert_eq!(count(&engine, marubozu), marubozus);
+
+        engine.set_enabled(doji, true);
+        assert!(engine.is_enabled(doji));
+        assert_eq!(count(&engine, doji), dojis);
+    }
+
+    #[test]
+    fn test_validate_open_close_within_range() {
+        let reason = |bar: Bar| match bar.validate() {
+            Err(PatternError::InvalidOHLCV { reason, .. }) => Some(reason),
+            _ => None,
+        };
+        assert_eq!(reason(Bar::new(100.0, 105.0, 95.0, 102.0)), None);
+        assert_eq!(reason(Bar::new(105.0, 105.0, 95.0, 95.0)), None);
+        assert_eq!(
+            reason(Bar::new(106.0, 105.0, 95.0, 102.0)),
+            Some("open/close outside high/low")
+        );
+        assert_eq!(
+            reason(Bar::new(100.0, 105.0, 95.0, 94.0)),
+            Some("open/close outside high/low")
+        );
+
+        let engine = EngineBuilder::new()
+            .with_all_defaults()
+            .validate_data(true)
+            .build()
+            .unwrap();
+        let mut bars = make_uptrend_bars();
+        bars[7].o = bars[7].h + 1.0;
+        assert!(matches!(
+            engine.scan(&bars),
+            Err(PatternError::InvalidOHLCV {
+                index: 7,
+                reason: "open/close outside high/low"
+            })
+        ));
+    }
+
+    #[test]
+    fn test_scan_matches_direct_detection() {
+        let mut engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.extend(make_uptrend_bars());
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+
+        let direct = |engine: &PatternEngine| {
+            let contexts = engine.compute_contexts(&bars);
+            let mut out = Vec::new();
+            for (i, ctx) in contexts.iter().enumerate() {
+                for (slot, d) in engine.builtin.iter().enumerate() {
+                    if engine.is_slot_disabled(slot) || i + 1 < d.min_bars() {
+                        continue;
+                    }
+                    if let Some(mut m) = d.detect(&bars, i, &engine.apply_config(ctx)) {
+                        m.strength = detectors::helpers::clamp_strength(m.strength);
+                        out.push(m);
+                    }
+                }
+            }
+            out
+        };
+        let key = |m: &PatternMatch| {
+            (
+                m.pattern_id,
+                m.strength.to_bits(),
+                m.start_index,
+                m.end_index,
+            )
+        };
+        let keys = |ms: Vec<PatternMatch>| ms.iter().map(key).collect::<Vec<_>>();
+
+        assert_eq!(keys(engine.scan(&bars).unwrap()), keys(direct(&engine)));
+        engine.set_enabled(PatternId("CDL_DOJI"), false);
+        assert_eq!(keys(engine.scan(&bars).unwrap()), keys(direct(&engine)));
+    }
+
+    #[test]
+    fn test_scan_indices_equals_scan_at() {
+        let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0));
+        let contexts = engine.compute_contexts(&bars);
+
+        let indices = [21, 3, 20, 99];
+        let key = |m: &PatternMatch| (m.pattern_id, m.start_index, m.end_index);
+        let expected: Vec<_> = indices
+            .iter()
+            .filter(|&&i| i < bars.len())
+            .flat_map(|&i| engine.scan_at(&bars, i, &contexts[i]))
+            .map(|m| key(&m))
+            .collect();
+        let found: Vec<_> = engine
+            .scan_indices(&bars, &indices, &contexts)
+            .iter()
+            .map(key)
+            .collect();
+
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_scan_closed_ignores_forming_bar() {
+        let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+        // Forming bar, completing a pattern with the doji
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0));
+        let key = |m: &PatternMatch| (m.pattern_id, m.start_index, m.end_index);
+
+        let closed: Vec<_> = engine.scan_closed(&bars).unwrap().iter().map(key).collect();
+        let expected: Vec<_> = engine
+            .scan(&bars[..bars.len() - 1])
+            .unwrap()
+            .iter()
+            .map(key)
+            .collect();
+        assert_eq!(closed, expected);
+        assert!(closed.iter().all(|&(_, _, end)| end <= bars.len() - 2));
+
+        let full = engine.scan(&bars).unwrap();
+        assert!(full.iter().any(|m| m.end_index == bars.len() - 1));
+        assert!(engine.scan_closed(&[] as &[Bar]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scan_profile_times_every_detector() {
+        let mut engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        engine.set_enabled(PatternId("CDL_DOJI"), false);
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0));
+
+        let (matches, timings) = engine.scan_profile(&bars).unwrap();
+        let key = |m: &PatternMatch| (m.pattern_id, m.start_index, m.end_index);
+        let expected: Vec<_> = engine.scan(&bars).unwrap().iter().map(key).collect();
+        assert_eq!(matches.iter().map(key).collect::<Vec<_>>(), expected);
+
+        let ids = engine.detector_ids();
+        assert_eq!(timings.len(), ids.len());
+        assert!(ids.iter().all(|id| timings.contains_key(id)));
+        assert!(!timings.contains_key(&PatternId("CDL_DOJI")));
+    }
 
-/// Default engine with DefaultContextProvider
-pub type DefaultEngine = PatternEngine<DefaultContextProvider>;
+    #[test]
+    fn test_strength_override_forces_full_strength() {
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0));
 
-// ============================================================
-// TESTS
-// ============================================================
+        let engine = EngineBuilder::new()
+            .with_all_defaults()
+            .strength_override(|_, _| 1.0)
+            .build()
+            .unwrap();
+        let matches = engine.scan(&bars).unwrap();
+        assert!(!matches.is_empty());
+        assert!(matches.iter().all(|m| m.strength == 1.0));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let clamped = EngineBuilder::new()
+            .with_all_defaults()
+            .strength_override(|m, _| m.strength * 10.0)
+            .build()
+            .unwrap();
+        assert!(clamped
+            .scan(&bars)
+            .unwrap()
+            .iter()
+            .all(|m| (0.0..=1.0).contains(&m.strength)));
+    }
 
-    /// Test OHLCV bar
-    #[derive(Debug, Clone)]
-    struct Bar {
-        o: f64,
-        h: f64,
-        l: f64,
-        c: f64,
-        v: f64,
+    #[test]
+    fn test_scan_near_levels_keeps_doji_at_level() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .build()
+            .unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+        let last = bars.len() - 1;
+        assert!(engine
+            .scan(&bars)
+            .unwrap()
+            .iter()
+            .any(|m| m.end_index == last));
+
+        let tolerance = Ratio::new(0.001).unwrap();
+        let near = engine.scan_near_levels(&bars, &[60.0], tolerance).unwrap();
+        assert!(!near.is_empty());
+        assert!(near.iter().all(|m| m.end_index == last));
+
+        let far = engine.scan_near_levels(&bars, &[75.0], tolerance).unwrap();
+        assert!(far.iter().all(|m| m.end_index != last));
+        assert!(engine
+            .scan_near_levels(&bars, &[], tolerance)
+            .unwrap()
+            .is_empty());
     }
 
-    impl Bar {
-        fn new(o: f64, h: f64, l: f64, c: f64) -> Self {
-            Self {
-                o,
-                h,
-                l,
-                c,
-                v: 1000.0,
+    #[test]
+    fn test_scan_with_regime_reports_strong_up() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+            .build()
+            .unwrap();
+        let mut bars = make_uptrend_bars();
+        // Shaveless white bar extending the advance
+        bars.push(Bar::new(140.0, 144.0, 140.0, 144.0));
+        let last = bars.len() - 1;
+
+        let with_regime = engine.scan_with_regime(&bars).unwrap();
+        assert_eq!(with_regime.len(), engine.scan(&bars).unwrap().len());
+        let (_, trend) = with_regime
+            .iter()
+            .find(|(m, _)| m.end_index == last)
+            .unwrap();
+        assert_eq!(*trend, Trend::StrongUp);
+    }
+
+    #[test]
+    fn test_scan_with_regime_reports_overridden_trend() {
+        struct StubDown;
+
+        impl ContextProvider for StubDown {
+            fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
+                vec![
+                    MarketContext {
+                        trend: Trend::StrongDown,
+                        ..Default::default()
+                    };
+                    bars.len()
+                ]
             }
         }
+
+        let mut bars = make_uptrend_bars();
+        bars.push(Bar::new(140.0, 144.0, 140.0, 144.0));
+        let last = bars.len() - 1;
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+            .context_provider_for(
+                PatternId("CDL_MARUBOZU"),
+                CompositeContextProvider::new(DefaultContextProvider::default(), StubDown),
+            )
+            .build()
+            .unwrap();
+
+        let with_regime = engine.scan_with_regime(&bars).unwrap();
+        let (_, trend) = with_regime
+            .iter()
+            .find(|(m, _)| m.end_index == last)
+            .unwrap();
+        assert_eq!(*trend, Trend::StrongDown);
     }
 
-    impl OHLCV for Bar {
-        fn open(&self) -> f64 {
-            self.o
-        }
+    #[test]
+    fn test_scan_annotated_tags_marubozu_engulfing() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Engulfing(
+                EngulfingDetector::with_defaults(),
+            ))
+            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+            .build()
+            .unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(61.0, 61.2, 59.8, 60.0));
+        // Shaveless white bar engulfing the black one
+        bars.push(Bar::new(59.5, 64.0, 59.5, 64.0));
+        let last = bars.len() - 1;
 
-        fn high(&self) -> f64 {
-            self.h
-        }
+        let annotated = engine.scan_annotated(&bars).unwrap();
+        let (_, tags) = annotated
+            .iter()
+            .find(|(m, _)| m.end_index == last && m.pattern_id.as_str() == "CDL_ENGULFING")
+            .unwrap();
+        assert!(tags.contains(&"marubozu"));
+        assert!(tags.contains(&"belt_hold"));
 
-        fn low(&self) -> f64 {
-            self.l
-        }
+        // The marubozu match itself is not tagged as a marubozu
+        let (_, tags) = annotated
+            .iter()
+            .find(|(m, _)| m.end_index == last && m.pattern_id.as_str() == "CDL_MARUBOZU")
+            .unwrap();
+        assert!(!tags.contains(&"marubozu"));
+        assert!(tags.contains(&"belt_hold"));
+    }
 
-        fn close(&self) -> f64 {
-            self.c
+    #[test]
+    fn test_scan_forming_flags_engulfing_before_completion() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Engulfing(
+                EngulfingDetector::with_defaults(),
+            ))
+            .add(BuiltinDetector::Piercing(PiercingDetector::with_defaults()))
+            .build()
+            .unwrap();
+        let mut bars = make_downtrend_bars();
+        // Long black bar: the setup of a bullish engulfing / piercing
+        bars.push(Bar::new(62.0, 62.5, 57.5, 58.0));
+
+        let forming = engine.scan_forming(&bars).unwrap();
+        let ids: Vec<_> = forming.iter().map(|f| f.m.pattern_id).collect();
+        assert_eq!(
+            ids,
+            [
+                PatternId("CDL_ENGULFING_PENDING"),
+                PatternId("CDL_PIERCING_PENDING")
+            ]
+        );
+        for FormingMatch { m, completeness } in &forming {
+            assert_eq!(*completeness, 0.5);
+            assert_eq!(m.direction, Direction::Bullish);
+            assert_eq!((m.start_index, m.end_index), (20, 20));
+            assert_eq!(m.strength, detectors::helpers::FORMING_MAX_STRENGTH / 2.0);
         }
+        assert!(engine.scan(&bars).unwrap().iter().all(|m| m.end_index < 20));
 
-        fn volume(&self) -> f64 {
-            self.v
-        }
+        // The next bar completes the engulfing
+        bars.push(Bar::new(57.5, 63.0, 57.0, 62.5));
+        let done = engine.scan(&bars).unwrap();
+        assert!(done
+            .iter()
+            .any(|m| m.pattern_id == PatternId("CDL_ENGULFING") && m.end_index == 21));
+        assert!(engine.scan_forming(&[] as &[Bar]).unwrap().is_empty());
     }
 
-    fn make_downtrend_bars() -> Vec<Bar> {
-        (0..20)
-            .map(|i| {
-                let base = 100.0 - i as f64 * 2.0;
-                Bar::new(base, base + 1.0, base - 1.0, base - 0.5)
-            })
-            .collect()
+    #[test]
+    fn test_snapshot_round_trip_reproduces_scan() {
+        let mut engine = EngineBuilder::new()
+            .trend_period(Period::new(10).unwrap())
+            .add(BuiltinDetector::Engulfing(EngulfingDetector {
+                min_engulf_factor: 1.2,
+                ..Default::default()
+            }))
+            .with_all_defaults()
+            .min_strength(0.3)
+            .build()
+            .unwrap();
+        engine.set_enabled(PatternId("CDL_DOJI"), false);
+
+        let json = serde_json::to_string(&engine.snapshot()).unwrap();
+        let snapshot: EngineSnapshot = serde_json::from_str(&json).unwrap();
+        // All defaults minus the opt-in HIKKAKE_CONFIRMED and the disabled doji
+        assert_eq!(snapshot.detectors.len(), BUILTIN_PATTERN_COUNT - 2);
+        assert!(snapshot.detectors.iter().all(|d| d.id != "CDL_DOJI"));
+        let restored = EngineBuilder::from_snapshot(snapshot)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0));
+        bars.extend(make_uptrend_bars());
+        let key = |m: &PatternMatch| (m.pattern_id, m.start_index, m.end_index, m.strength);
+        let expected: Vec<_> = engine.scan(&bars).unwrap().iter().map(key).collect();
+        let found: Vec<_> = restored.scan(&bars).unwrap().iter().map(key).collect();
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+
+        let filtered = EngineBuilder::new()
+            .with_all_defaults()
+            .only_patterns([PatternId("CDL_HAMMER")])
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&filtered.snapshot()).unwrap();
+        let restored: EngineSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            restored.config.pattern_filter,
+            Some(vec![PatternId("CDL_HAMMER")])
+        );
+        // Unknown (custom) ids are rejected rather than leaked
+        let custom = json.replace("\"CDL_HAMMER\"]", "\"MY_PATTERN\"]");
+        assert!(serde_json::from_str::<EngineSnapshot>(&custom).is_err());
+        let split = json.replace("\"CDL_HAMMER\"]", "\"CDL_HARAMICROSS_BULL\"]");
+        let restored: EngineSnapshot = serde_json::from_str(&split).unwrap();
+        assert_eq!(
+            restored.config.pattern_filter,
+            Some(vec![PatternId("CDL_HARAMICROSS_BULL")])
+        );
+
+        // Fields added after the first release may be missing
+        let config: EngineConfig =
+            serde_json::from_str(r#"{"pattern_filter":["CDL_ENGULFING_PENDING"]}"#).unwrap();
+        assert_eq!(
+            config.pattern_filter,
+            Some(vec![PatternId("CDL_ENGULFING_PENDING")])
+        );
+        assert!(!config.validate_data && config.min_strength.is_none());
     }
 
-    fn make_uptrend_bars() -> Vec<Bar> {
-        (0..20)
-            .map(|i| {
-                let base = 100.0 + i as f64 * 2.0;
-                Bar::new(base, base + 1.0, base - 1.0, base + 0.5)
-            })
-            .collect()
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_scan_to_ndjson_round_trip() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .build()
+            .unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+        let matches = engine.scan(&bars).unwrap();
+        assert!(!matches.is_empty());
+
+        let mut out = Vec::new();
+        scan_to_ndjson(&engine, "TEST", &bars, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), matches.len());
+
+        for (line, m) in lines.iter().zip(&matches) {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["symbol"], "TEST");
+            assert_eq!(value["pattern_id"], m.pattern_id.as_str());
+            assert_eq!(value["direction"], m.direction.as_str());
+            assert_eq!(value["strength"].as_f64().unwrap(), m.strength);
+            assert_eq!(value["start_index"].as_u64().unwrap(), m.start_index as u64);
+            assert_eq!(value["end_index"].as_u64().unwrap(), m.end_index as u64);
+        }
+        assert!(lines[0].contains(r#""direction":"neutral""#));
     }
 
+    #[cfg(feature = "json")]
     #[test]
-    fn test_ratio_validation() {
-        assert!(Ratio::new(0.0).is_ok());
-        assert!(Ratio::new(1.0).is_ok());
-        assert!(Ratio::new(0.5).is_ok());
-        assert!(Ratio::new(-0.1).is_err());
-        assert!(Ratio::new(1.1).is_err());
-        assert!(Ratio::new(f64::NAN).is_err());
-        assert!(Ratio::new(f64::INFINITY).is_err());
+    fn test_engine_from_json_spec() {
+        let engine = EngineBuilder::from_json(
+            r#"{"patterns": [
+                    {"id": "CDL_MORNINGSTAR", "params": {"penetration": 0.4}},
+                    {"id": "CDL_DOJI"}
+                ],
+                "min_strength": 0.6}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            engine.detector_ids(),
+            vec![PatternId("CDL_MORNINGSTAR"), PatternId("CDL_DOJI")]
+        );
+        assert_eq!(engine.config.min_strength, Some(0.6));
+        match &engine.builtin[0] {
+            BuiltinDetector::MorningStar(d) => assert_eq!(d.penetration, 0.4),
+            other => panic!("unexpected detector {other:?}"),
+        }
+
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+        let doji = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .min_strength(0.6)
+            .build()
+            .unwrap();
+        assert_eq!(
+            engine.scan(&bars).unwrap().len(),
+            doji.scan(&bars).unwrap().len()
+        );
+
+        for spec in [
+            "not json",
+            r#"{"patterns": [{"id": "CDL_NOPE"}]}"#,
+            r#"{"patterns": [{"id": "CDL_MORNINGSTAR", "params": {"nope": 1.0}}]}"#,
+            r#"{"patterns": [{"id": "RISING_WINDOW", "params": {"gap": 1.0}}]}"#,
+            r#"{"patterns": [{"id": "KICKER", "params": {"shadow_max_ratio": 2.0}}]}"#,
+            r#"{"patterns": [{"id": "CDL_MORNINGSTAR", "params": {"penetration": -0.1}}]}"#,
+            r#"{"patterns": [{"id": "CDL_MORNINGSTAR", "params": {"penetration": "x"}}]}"#,
+            r#"{"patterns": [{"id": "CDL_ENGULFING", "params": {"min_engulf_factor": 0.0}}]}"#,
+            r#"{"patterns": [{"id": "CDL_ENGULFING", "params": {"min_engulf_ratio": 1.2}}]}"#,
+            r#"{"patterns": [{"id": "CDL_DOJI", "params": {"doji_factor": 0.1}}]}"#,
+            r#"{"patterns": [], "unknown": 1}"#,
+        ] {
+            assert!(
+                matches!(
+                    EngineBuilder::from_json(spec),
+                    Err(PatternError::InvalidConfig(_))
+                ),
+                "{spec}"
+            );
+        }
     }
 
     #[test]
-    fn test_period_validation() {
-        assert!(Period::new(1).is_ok());
-        assert!(Period::new(100).is_ok());
-        assert!(Period::new(0).is_err());
+    fn test_from_id_with_params_covers_every_builtin() {
+        let none = HashMap::new();
+        for detector in BuiltinDetector::all_defaults() {
+            let id = detector.id();
+            let built = BuiltinDetector::from_id_with_params(id.as_str(), &none).unwrap();
+            assert_eq!(built.id(), id);
+        }
+
+        let params = HashMap::from([("min_engulf_factor", 1.2), ("require_above_avg_body", 1.0)]);
+        match BuiltinDetector::from_id_with_params("CDL_ENGULFING", &params).unwrap() {
+            BuiltinDetector::Engulfing(d) => {
+                assert_eq!(d.min_engulf_factor, 1.2);
+                assert!(d.require_above_avg_body);
+            }
+            other => panic!("unexpected detector {other:?}"),
+        }
     }
 
     #[test]
-    fn test_ohlcv_ext() {
-        let bar = Bar::new(100.0, 110.0, 90.0, 105.0);
-        assert_eq!(bar.body(), 5.0);
-        assert_eq!(bar.range(), 20.0);
-        assert!(bar.is_bullish());
-        assert!(!bar.is_bearish());
-        assert!((bar.body_ratio().unwrap() - 0.25).abs() < 0.001);
+    fn test_key_index_points_at_morning_star() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::MorningStar(
+                MorningStarDetector::with_defaults(),
+            ))
+            .build()
+            .unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(61.0, 61.5, 55.5, 56.0)); // long black
+        bars.push(Bar::new(55.5, 55.8, 54.0, 55.2)); // star
+        bars.push(Bar::new(55.5, 59.5, 55.3, 59.0)); // long white
+        let last = bars.len() - 1;
+
+        let m = engine
+            .scan(&bars)
+            .unwrap()
+            .into_iter()
+            .find(|m| m.end_index == last)
+            .unwrap();
+        assert_eq!(m.start_index, last - 2);
+        assert_eq!(m.key_index(&bars), last - 1);
+
+        let bearish = PatternMatch {
+            direction: Direction::Bearish,
+            ..m
+        };
+        assert_eq!(bearish.key_index(&bars), last - 2);
+        let neutral = PatternMatch {
+            direction: Direction::Neutral,
+            ..m
+        };
+        assert_eq!(neutral.key_index(&bars), last);
+        assert_eq!(m.key_index(&bars[..last]), last);
     }
 
     #[test]
-    fn test_engine_builder() {
-        let engine = EngineBuilder::new().with_all_defaults().build();
-        assert!(engine.is_ok());
+    fn test_scan_into_matches_scan() {
+        let engine = EngineBuilder::new()
+            .with_extended_defaults()
+            .add_custom(HammerDetector::with_defaults())
+            .build()
+            .unwrap();
+        let mut bars = make_downtrend_bars();
+        bars.extend(make_uptrend_bars());
+        let sig = |ms: &[PatternMatch]| -> Vec<(PatternId, usize, usize, u64)> {
+            ms.iter()
+                .map(|m| {
+                    (
+                        m.pattern_id,
+                        m.start_index,
+                        m.end_index,
+                        m.strength.to_bits(),
+                    )
+                })
+                .collect()
+        };
+
+        let mut out = vec![PatternMatch {
+            pattern_id: PatternId("STALE"),
+            direction: Direction::Neutral,
+            strength: 0.0,
+            start_index: 0,
+            end_index: 0,
+            metric: None,
+        }];
+        engine.scan_into(&bars, &mut out).unwrap();
+        assert_eq!(sig(&out), sig(&engine.scan(&bars).unwrap()));
+
+        // Reusing the buffer for a shorter series keeps its capacity
+        let capacity = out.capacity();
+        engine.scan_into(&bars[..10], &mut out).unwrap();
+        assert_eq!(sig(&out), sig(&engine.scan(&bars[..10]).unwrap()));
+        assert_eq!(out.capacity(), capacity);
+
+        let mut grouped = Vec::new();
+        for len in [bars.len(), 10] {
+            engine
+                .scan_grouped_into(&bars[..len], &mut grouped)
+                .unwrap();
+            let expected = engine.scan_grouped(&bars[..len]).unwrap();
+            assert_eq!(grouped.len(), len);
+            assert!(grouped.iter().zip(&expected).all(|(a, b)| sig(a) == sig(b)));
+        }
     }
 
     #[test]
-    fn test_empty_scan() {
+    fn test_scan_events_ids_increase() {
         let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
-        let bars: Vec<Bar> = vec![];
+        let mut bars = make_downtrend_bars();
+        bars.extend(make_uptrend_bars());
+
+        let events = engine.scan_events(&bars).unwrap();
         let patterns = engine.scan(&bars).unwrap();
-        assert!(patterns.is_empty());
+        assert_eq!(events.len(), patterns.len());
+        assert!(events.len() > 1);
+        assert_eq!(events[0].id, 0);
+        assert!(events.windows(2).all(|w| w[1].id == w[0].id + 1));
+        assert!(events
+            .iter()
+            .zip(&patterns)
+            .all(|(e, p)| e.m.pattern_id == p.pattern_id && e.m.end_index == p.end_index));
     }
 
     #[test]
-    fn test_doji_detection() {
+    fn test_min_volume_skips_illiquid_bars() {
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0)); // Doji
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0)); // Marubozu
+
+        let all = EngineBuilder::new().with_all_defaults().build().unwrap();
+        assert!(!all.scan(&bars).unwrap().is_empty());
+
         let engine = EngineBuilder::new()
-            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .with_all_defaults()
+            .min_volume(1.0)
             .build()
             .unwrap();
+        assert_eq!(
+            engine.scan(&bars).unwrap().len(),
+            all.scan(&bars).unwrap().len()
+        );
 
-        let bars = vec![
-            Bar::new(100.0, 110.0, 90.0, 100.5), // Doji
-        ];
+        for bar in &mut bars {
+            bar.v = 0.0;
+        }
+        assert!(engine.scan(&bars).unwrap().is_empty());
+        assert!(!all.scan(&bars).unwrap().is_empty());
+    }
 
-        let patterns = engine.scan(&bars).unwrap();
-        assert!(!patterns.is_empty());
-        assert_eq!(patterns[0].pattern_id, PatternId("CDL_DOJI"));
+    #[test]
+    fn test_builder_trend_period_shortens_warmup() {
+        let bars = make_uptrend_bars();
+        let trends = |engine: PatternEngine| -> Vec<Trend> {
+            engine
+                .compute_contexts(&bars)
+                .iter()
+                .map(|c| c.trend)
+                .collect()
+        };
+
+        let default = trends(EngineBuilder::new().build().unwrap());
+        assert!(default[..14].iter().all(|t| *t == Trend::Sideways));
+
+        let short = trends(
+            EngineBuilder::new()
+                .trend_period(Period::new(5).unwrap())
+                .volume_period(Period::new(5).unwrap())
+                .candle_period(Period::new(5).unwrap())
+                .build()
+                .unwrap(),
+        );
+        assert!(short[..5].iter().all(|t| *t == Trend::Sideways));
+        assert!(short[5..14].iter().all(|t| t.is_up()));
     }
 
     #[test]
-    fn test_scan_grouped() {
-        let engine = EngineBuilder::new()
-            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
-            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
-            .build()
+    fn test_scan_feature_matrix_dimensions() {
+        let mut engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        engine.set_enabled(PatternId("CDL_DOJI"), false);
+        let mut bars = make_downtrend_bars();
+        bars.push(Bar::new(60.0, 65.0, 55.0, 60.0));
+        bars.push(Bar::new(60.0, 70.0, 60.0, 70.0));
+
+        let ids = engine.detector_ids();
+        assert!(!ids.contains(&PatternId("CDL_DOJI")));
+        let matrix = engine.scan_feature_matrix(&bars).unwrap();
+        assert_eq!(matrix.len(), bars.len());
+        assert!(matrix.iter().all(|row| row.len() == ids.len()));
+
+        let marubozu = ids
+            .iter()
+            .position(|id| *id == PatternId("CDL_MARUBOZU"))
             .unwrap();
+        assert!(matrix[21][marubozu] > 0.0);
+        assert_eq!(
+            matrix.iter().flatten().filter(|v| **v > 0.0).count(),
+            engine.scan(&bars).unwrap().len()
+        );
+    }
 
-        let bars = vec![
-            Bar::new(100.0, 110.0, 90.0, 100.5),  // Doji
-            Bar::new(100.0, 105.0, 100.0, 105.0), // Marubozu (close = high)
-            Bar::new(100.0, 100.0, 95.0, 95.0),   // Bearish Marubozu (close = low)
+    #[test]
+    fn test_adjusted_strength() {
+        let m = |id, direction, strength| PatternMatch {
+            pattern_id: PatternId(id),
+            direction,
+            strength,
+            start_index: 0,
+            end_index: 0,
+            metric: None,
+        };
+
+        let agree = m("CDL_HAMMER", Direction::Bullish, 0.5);
+        assert!((agree.adjusted_strength() - 0.55).abs() < 1e-9);
+        assert_eq!(
+            m("CDL_HAMMER", Direction::Bullish, 0.95).adjusted_strength(),
+            1.0
+        );
+
+        let disagree = m("CDL_HAMMER", Direction::Bearish, 0.5);
+        assert!((disagree.adjusted_strength() - 0.45).abs() < 1e-9);
+
+        let bidirectional = m("CDL_ENGULFING", Direction::Bearish, 0.5);
+        assert_eq!(bidirectional.adjusted_strength(), 0.5);
+    }
+
+    #[test]
+    fn test_aggregate_stats() {
+        let m = |id, strength| PatternMatch {
+            pattern_id: PatternId(id),
+            direction: Direction::Neutral,
+            strength,
+            start_index: 0,
+            end_index: 0,
+            metric: None,
+        };
+        let results = [
+            ScanResult {
+                symbol: "AAA".to_string(),
+                patterns: vec![m("CDL_DOJI", 0.4), m("CDL_DOJI", 0.6), m("CDL_HAMMER", 0.9)],
+            },
+            ScanResult {
+                symbol: "BBB".to_string(),
+                patterns: vec![m("CDL_DOJI", 0.8)],
+            },
         ];
 
-        let grouped = engine.scan_grouped(&bars).unwrap();
-        assert_eq!(grouped.len(), bars.len());
+        let stats = aggregate_stats(&results);
+        assert_eq!(stats.len(), 2);
+
+        let doji = &stats[&PatternId("CDL_DOJI")];
+        assert_eq!(doji.count, 3);
+        assert_eq!(doji.per_symbol, 2);
+        assert!((doji.mean_strength - 0.6).abs() < 1e-9);
+
+        let hammer = &stats[&PatternId("CDL_HAMMER")];
+        assert_eq!(hammer.count, 1);
+        assert_eq!(hammer.per_symbol, 1);
+        assert_eq!(hammer.mean_strength, 0.9);
     }
 
     #[test]
@@ -1751,6 +6348,30 @@ mod tests {
         assert!(patterns.is_empty()); // Doji filtered out
     }
 
+    #[test]
+    fn test_builder_dedupes_detectors() {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .add(BuiltinDetector::Marubozu(MarubozuDetector::with_defaults()))
+            .add(BuiltinDetector::Doji(DojiDetector::with_defaults()))
+            .build()
+            .unwrap();
+        assert_eq!(engine.builtin.len(), 2);
+
+        let bars = vec![
+            Bar::new(100.0, 110.0, 90.0, 100.5), // Doji
+            Bar::new(100.0, 110.0, 90.0, 100.5), // Doji
+        ];
+        let grouped = engine.scan_grouped(&bars).unwrap();
+        for patterns in &grouped {
+            let dojis = patterns
+                .iter()
+                .filter(|p| p.pattern_id == PatternId("CDL_DOJI"))
+                .count();
+            assert_eq!(dojis, 1);
+        }
+    }
+
     #[test]
     fn test_single_bar_defaults() {
         let engine = EngineBuilder::new()
@@ -1783,6 +6404,33 @@ mod tests {
         let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
         // Total: 17 + 18 + 20 + 8 = 63 (some overlap due to TweezerTop/Bottom in different counts)
         assert!(engine.builtin.len() >= 60);
+        // Every variant except the opt-in HIKKAKE_CONFIRMED
+        assert_eq!(engine.builtin.len(), BUILTIN_PATTERN_COUNT - 1);
+        assert!(!engine.is_enabled(PatternId("HIKKAKE_CONFIRMED")));
+    }
+
+    #[test]
+    fn test_builtin_pattern_count_matches_variants() {
+        assert_eq!(BuiltinDetector::all_defaults().len(), BUILTIN_PATTERN_COUNT);
+        assert_eq!(pattern_catalog().len(), BUILTIN_PATTERN_COUNT);
+    }
+
+    #[test]
+    fn test_pattern_catalog() {
+        let catalog = pattern_catalog();
+        let all = EngineBuilder::new().with_all_defaults().build().unwrap();
+        // The catalog also lists the opt-in HIKKAKE_CONFIRMED
+        assert_eq!(catalog.len(), all.builtin.len() + 1);
+
+        let ids: HashSet<_> = catalog.iter().map(|(id, _, _)| *id).collect();
+        assert_eq!(ids.len(), catalog.len());
+
+        let doji = catalog
+            .iter()
+            .find(|(id, _, _)| *id == PatternId("CDL_DOJI"))
+            .unwrap();
+        assert_eq!(doji.1, Some(Direction::Neutral));
+        assert_eq!(doji.2, PatternCategory::SingleBar);
     }
 
     #[test]
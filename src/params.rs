@@ -20,7 +20,7 @@
 
 use std::collections::HashMap;
 
-use crate::{PatternError, Period, Ratio, Result};
+use crate::{PatternError, Period, Ratio, Result, OHLCV};
 
 // ============================================================
 // PARAMETER TYPES
@@ -29,10 +29,14 @@ use crate::{PatternError, Period, Ratio, Result};
 /// Type of parameter value
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParamType {
-    /// Ratio value (0.0..=1.0 typically, but can exceed 1.0 for some params like shadow_ratio)
+    /// Ratio value (0.0..=1.0)
     Ratio,
+    /// Positive multiplier, which may exceed 1.0 (e.g. a shadow to body ratio)
+    Factor,
     /// Period value (positive integer)
     Period,
+    /// On/off switch, passed as `0.0` (off) or `1.0` (on)
+    Bool,
 }
 
 /// Metadata for a single detector parameter
@@ -40,7 +44,7 @@ pub enum ParamType {
 pub struct ParamMeta {
     /// Parameter name (e.g., "min_engulf_ratio")
     pub name: &'static str,
-    /// Parameter type (Ratio or Period)
+    /// Parameter type (Ratio, Factor, Period or Bool)
     pub param_type: ParamType,
     /// Default value
     pub default: f64,
@@ -67,6 +71,22 @@ impl ParamMeta {
         }
     }
 
+    /// Create a new ParamMeta for a Factor parameter
+    pub const fn factor(
+        name: &'static str,
+        default: f64,
+        range: (f64, f64, f64),
+        description: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            param_type: ParamType::Factor,
+            default,
+            range,
+            description,
+        }
+    }
+
     /// Create a new ParamMeta for a Period parameter
     pub const fn period(
         name: &'static str,
@@ -83,6 +103,17 @@ impl ParamMeta {
         }
     }
 
+    /// Create a new ParamMeta for an on/off switch
+    pub const fn flag(name: &'static str, default: bool, description: &'static str) -> Self {
+        Self {
+            name,
+            param_type: ParamType::Bool,
+            default: if default { 1.0 } else { 0.0 },
+            range: (0.0, 1.0, 1.0),
+            description,
+        }
+    }
+
     /// Generate all values for grid search
     pub fn generate_grid(&self) -> Vec<f64> {
         let (min, max, step) = self.range;
@@ -107,9 +138,13 @@ impl ParamMeta {
             });
         }
         match self.param_type {
-            ParamType::Ratio => {
-                // For Ratio, value might exceed 1.0 for some parameters (e.g., shadow_ratio: 2.0)
-                // We don't strictly enforce 0-1 here, let the Ratio::new handle it
+            ParamType::Ratio => Ratio::new(value).map(|_| ()),
+            ParamType::Factor => {
+                if !(value.is_finite() && value > 0.0) {
+                    return Err(PatternError::InvalidValue(
+                        "Factor must be positive and finite",
+                    ));
+                }
                 Ok(())
             }
             ParamType::Period => {
@@ -120,6 +155,12 @@ impl ParamMeta {
                 }
                 Ok(())
             }
+            ParamType::Bool => {
+                if value != 0.0 && value != 1.0 {
+                    return Err(PatternError::InvalidValue("Flag must be 0 or 1"));
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -145,6 +186,17 @@ pub trait ParameterizedDetector: Sized {
 
     /// Returns the pattern ID string
     fn pattern_id_str() -> &'static str;
+
+    /// Parameter values that would have produced a match at `index`.
+    ///
+    /// Meant for tuning near-misses: the suggestion is the least strict value
+    /// of each threshold that still fires, leaving other parameters at their
+    /// defaults. Returns `None` when no parameter values make the detector fire
+    /// there, or when the detector does not support suggestions (the default).
+    fn suggest_params<T: OHLCV>(bars: &[T], index: usize) -> Option<HashMap<&'static str, f64>> {
+        let _ = (bars, index);
+        None
+    }
 }
 
 // ============================================================
@@ -157,6 +209,35 @@ pub fn get_ratio(params: &HashMap<&str, f64>, key: &str, default: f64) -> Result
     Ratio::new(value)
 }
 
+/// Helper to get a positive multiplier (which may exceed 1.0) from params
+/// with default fallback
+///
+/// Rejects zero, negative, NaN and infinite values.
+pub fn get_positive(params: &HashMap<&str, f64>, key: &str, default: f64) -> Result<f64> {
+    let value = params.get(key).copied().unwrap_or(default);
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(PatternError::InvalidValue(
+            "Factor must be positive and finite",
+        ))
+    }
+}
+
+/// Helper to get an on/off switch from params with default fallback
+///
+/// Switches are passed as `0.0` (off) or `1.0` (on); anything else is rejected.
+pub fn get_flag(params: &HashMap<&str, f64>, key: &str, default: bool) -> Result<bool> {
+    let Some(value) = params.get(key).copied() else {
+        return Ok(default);
+    };
+    if value == 0.0 || value == 1.0 {
+        Ok(value == 1.0)
+    } else {
+        Err(PatternError::InvalidValue("Flag must be 0 or 1"))
+    }
+}
+
 /// Helper to get a Period from params with default fallback
 pub fn get_period(params: &HashMap<&str, f64>, key: &str, default: usize) -> Result<Period> {
     let value = params.get(key).copied().unwrap_or(default as f64);
@@ -194,6 +275,30 @@ mod tests {
         assert_eq!(meta.default, 14.0);
     }
 
+    #[test]
+    fn test_param_meta_factor() {
+        let meta = ParamMeta::factor("test_factor", 2.0, (1.0, 3.0, 0.5), "Test factor");
+
+        assert_eq!(meta.param_type, ParamType::Factor);
+        assert!(meta.validate(2.5).is_ok());
+        assert!(meta.validate(3.5).is_err());
+        assert!(ParamMeta::factor("zero", 1.0, (0.0, 2.0, 0.5), "")
+            .validate(0.0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_param_meta_flag() {
+        let meta = ParamMeta::flag("test_flag", true, "Test flag parameter");
+
+        assert_eq!(meta.param_type, ParamType::Bool);
+        assert_eq!(meta.default, 1.0);
+        assert_eq!(meta.generate_grid(), vec![0.0, 1.0]);
+        assert!(meta.validate(0.0).is_ok());
+        assert!(meta.validate(1.0).is_ok());
+        assert!(meta.validate(0.5).is_err());
+    }
+
     #[test]
     fn test_generate_grid() {
         let meta = ParamMeta::ratio("test", 0.5, (0.3, 0.7, 0.2), "Test");
@@ -216,6 +321,30 @@ mod tests {
         assert!(meta.validate(0.8).is_err());
     }
 
+    #[test]
+    fn test_get_positive() {
+        let mut params = HashMap::new();
+        assert_eq!(get_positive(&params, "k", 1.5).unwrap(), 1.5);
+        params.insert("k", 2.5);
+        assert_eq!(get_positive(&params, "k", 1.0).unwrap(), 2.5);
+        for bad in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            params.insert("k", bad);
+            assert!(get_positive(&params, "k", 1.0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_get_flag() {
+        let mut params = HashMap::new();
+        assert!(get_flag(&params, "k", true).unwrap());
+        params.insert("k", 0.0);
+        assert!(!get_flag(&params, "k", true).unwrap());
+        params.insert("k", 1.0);
+        assert!(get_flag(&params, "k", false).unwrap());
+        params.insert("k", 0.5);
+        assert!(get_flag(&params, "k", false).is_err());
+    }
+
     #[test]
     fn test_validate_period() {
         let meta = ParamMeta::period("test", 14.0, (10.0, 20.0, 2.0), "Test");
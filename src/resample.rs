@@ -0,0 +1,348 @@
+//! Resampling of bar series
+//!
+//! Aggregates consecutive bars into larger candles, e.g. daily candles from
+//! intraday data, so patterns can be detected on a higher timeframe. Bars can
+//! also be collapsed onto a single price field ([`to_line_bars`]) for
+//! line-chart analysis, or snapped to a tick grid ([`snap_to_tick`]).
+//!
+//! # Example
+//!
+//! ```rust
+//! use yacpd::resample::{resample_by, OwnedBar};
+//!
+//! const DAY: i64 = 86_400;
+//!
+//! let intraday = vec![
+//!     OwnedBar::new(100.0, 102.0, 99.0, 101.0, 10.0).with_timestamp(0),
+//!     OwnedBar::new(101.0, 104.0, 100.0, 103.0, 20.0).with_timestamp(3_600),
+//!     OwnedBar::new(103.0, 103.5, 98.0, 99.0, 30.0).with_timestamp(DAY),
+//! ];
+//!
+//! let daily = resample_by(&intraday, |ts| ts.div_euclid(DAY)).unwrap();
+//! assert_eq!(daily.len(), 2);
+//! assert_eq!(daily[0].high, 104.0);
+//! ```
+
+use crate::{OHLCVExt, PatternError, Result, OHLCV};
+
+// ============================================================
+// OWNED BAR
+// ============================================================
+
+/// Plain owned OHLCV bar produced by resampling
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OwnedBar {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub timestamp: Option<i64>,
+}
+
+impl OwnedBar {
+    pub fn new(open: f64, high: f64, low: f64, close: f64, volume: f64) -> Self {
+        Self {
+            open,
+            high,
+            low,
+            close,
+            volume,
+            timestamp: None,
+        }
+    }
+
+    pub fn with_timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Copy the values of any OHLCV bar
+    pub fn from_ohlcv<T: OHLCV + ?Sized>(bar: &T) -> Self {
+        Self {
+            open: bar.open(),
+            high: bar.high(),
+            low: bar.low(),
+            close: bar.close(),
+            volume: bar.volume(),
+            timestamp: bar.timestamp(),
+        }
+    }
+
+    /// Extend this bar with a later one
+    fn merge<T: OHLCV + ?Sized>(&mut self, bar: &T) {
+        self.high = self.high.max(bar.high());
+        self.low = self.low.min(bar.low());
+        self.close = bar.close();
+        self.volume += bar.volume();
+    }
+}
+
+impl OHLCV for OwnedBar {
+    fn open(&self) -> f64 {
+        self.open
+    }
+
+    fn high(&self) -> f64 {
+        self.high
+    }
+
+    fn low(&self) -> f64 {
+        self.low
+    }
+
+    fn close(&self) -> f64 {
+        self.close
+    }
+
+    fn volume(&self) -> f64 {
+        self.volume
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+}
+
+// ============================================================
+// RESAMPLING
+// ============================================================
+
+/// Group consecutive bars sharing a bucket key into one bar each.
+///
+/// `bucket` maps a bar timestamp to its session key (e.g. `ts / 86_400` for
+/// UTC days). Each output bar takes the open and timestamp of the first bar in
+/// its group, the close of the last, the extreme high/low and summed volume.
+/// Only consecutive bars are merged; a key that reappears later starts a new
+/// group.
+///
+/// Fails with `InvalidOHLCV` on the first bar without a timestamp.
+pub fn resample_by<T: OHLCV>(bars: &[T], bucket: impl Fn(i64) -> i64) -> Result<Vec<OwnedBar>> {
+    let mut out: Vec<OwnedBar> = Vec::new();
+    let mut current_key = None;
+
+    for (index, bar) in bars.iter().enumerate() {
+        let ts = bar.timestamp().ok_or(PatternError::InvalidOHLCV {
+            index,
+            reason: "missing timestamp",
+        })?;
+        let key = bucket(ts);
+
+        match out.last_mut() {
+            Some(last) if current_key == Some(key) => last.merge(bar),
+            _ => {
+                out.push(OwnedBar::from_ohlcv(bar));
+                current_key = Some(key);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+// ============================================================
+// LINE BARS
+// ============================================================
+
+/// Price used for every OHLC value of a line bar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PriceField {
+    Open,
+    High,
+    Low,
+    #[default]
+    Close,
+    /// (high + low + close) / 3
+    Typical,
+    /// (high + low + 2 * close) / 4
+    Weighted,
+    /// (high + low) / 2
+    Median,
+}
+
+impl PriceField {
+    /// Value of this field for `bar`
+    pub fn value<T: OHLCV>(self, bar: &T) -> f64 {
+        match self {
+            Self::Open => bar.open(),
+            Self::High => bar.high(),
+            Self::Low => bar.low(),
+            Self::Close => bar.close(),
+            Self::Typical => bar.typical_price(),
+            Self::Weighted => bar.weighted_close(),
+            Self::Median => bar.median_price(),
+        }
+    }
+}
+
+/// Collapse each bar onto one price: open, high, low and close all equal
+/// `field`, volume and timestamp are kept.
+///
+/// Useful for close-only or typical-price series. Every line bar has zero
+/// body and zero range, so body- and shadow-based detectors degenerate (each
+/// bar reads as a doji, nothing as a marubozu or hammer); detectors that
+/// compare prices across bars, such as `NEW_HIGH`/`NEW_LOW` or the windows,
+/// keep their meaning.
+pub fn to_line_bars<T: OHLCV>(bars: &[T], field: PriceField) -> Vec<OwnedBar> {
+    bars.iter()
+        .map(|bar| {
+            let price = field.value(bar);
+            OwnedBar {
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: bar.volume(),
+                timestamp: bar.timestamp(),
+            }
+        })
+        .collect()
+}
+
+// ============================================================
+// TICK SNAPPING
+// ============================================================
+
+/// Round open, high, low and close to the nearest multiple of `tick`.
+///
+/// Removes floating-point noise (e.g. `99.99999998` vs `100.0`) that would
+/// otherwise break equality-based detectors such as tweezers, matching low or
+/// counterattack. Volume and timestamp are kept. A `tick` that is not a
+/// positive finite number leaves prices unchanged.
+pub fn snap_to_tick<T: OHLCV>(bars: &[T], tick: f64) -> Vec<OwnedBar> {
+    let snap = |price: f64| {
+        if tick > 0.0 && tick.is_finite() {
+            (price / tick).round() * tick
+        } else {
+            price
+        }
+    };
+    bars.iter()
+        .map(|bar| OwnedBar {
+            open: snap(bar.open()),
+            high: snap(bar.high()),
+            low: snap(bar.low()),
+            close: snap(bar.close()),
+            volume: bar.volume(),
+            timestamp: bar.timestamp(),
+        })
+        .collect()
+}
+
+// ============================================================
+// TESTS
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    #[test]
+    fn test_resample_by_day() {
+        let hour = 3_600;
+        let bars = vec![
+            OwnedBar::new(100.0, 102.0, 99.0, 101.0, 10.0).with_timestamp(9 * hour),
+            OwnedBar::new(101.0, 105.0, 100.5, 104.0, 20.0).with_timestamp(10 * hour),
+            OwnedBar::new(104.0, 104.5, 97.0, 98.0, 30.0).with_timestamp(11 * hour),
+            OwnedBar::new(98.5, 99.0, 96.0, 97.0, 5.0).with_timestamp(DAY + 9 * hour),
+            OwnedBar::new(97.0, 100.0, 96.5, 99.5, 15.0).with_timestamp(DAY + 10 * hour),
+        ];
+
+        let daily = resample_by(&bars, |ts| ts.div_euclid(DAY)).unwrap();
+        assert_eq!(daily.len(), 2);
+        assert_eq!(
+            daily[0],
+            OwnedBar::new(100.0, 105.0, 97.0, 98.0, 60.0).with_timestamp(9 * hour)
+        );
+        assert_eq!(
+            daily[1],
+            OwnedBar::new(98.5, 100.0, 96.0, 99.5, 20.0).with_timestamp(DAY + 9 * hour)
+        );
+    }
+
+    #[test]
+    fn test_resample_by_requires_timestamps() {
+        let bars = vec![
+            OwnedBar::new(100.0, 102.0, 99.0, 101.0, 10.0).with_timestamp(0),
+            OwnedBar::new(101.0, 105.0, 100.5, 104.0, 20.0),
+        ];
+
+        let err = resample_by(&bars, |ts| ts.div_euclid(DAY)).unwrap_err();
+        assert!(matches!(err, PatternError::InvalidOHLCV { index: 1, .. }));
+        assert!(resample_by::<OwnedBar>(&[], |ts| ts).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_to_line_bars_close_field() {
+        use crate::prelude::*;
+
+        let bars: Vec<OwnedBar> = (0..25)
+            .map(|i| {
+                let c = 100.0 + i as f64;
+                OwnedBar::new(c + 0.5, c + 3.0, c - 3.0, c, 10.0).with_timestamp(i)
+            })
+            .collect();
+        let lines = to_line_bars(&bars, PriceField::Close);
+        assert_eq!(lines.len(), bars.len());
+        assert_eq!(
+            lines[3],
+            OwnedBar::new(103.0, 103.0, 103.0, 103.0, 10.0).with_timestamp(3)
+        );
+        assert!((PriceField::Typical.value(&bars[3]) - 103.0).abs() < 1e-12);
+
+        // Candle highs overlap, but each close is a new line high
+        let engine = EngineBuilder::new()
+            .with_price_action_defaults()
+            .build()
+            .unwrap();
+        assert!(engine.scan(&bars).unwrap().is_empty());
+        let ends: Vec<usize> = engine
+            .scan(&lines)
+            .unwrap()
+            .iter()
+            .map(|m| m.end_index)
+            .collect();
+        assert_eq!(ends, (20..25).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_snap_to_tick_forms_tweezer_top() {
+        use crate::prelude::*;
+
+        let mut bars: Vec<OwnedBar> = (0..20)
+            .map(|i| {
+                let base = 100.0 + i as f64;
+                OwnedBar::new(base, base + 1.5, base - 0.5, base + 1.0, 10.0)
+            })
+            .collect();
+        bars.push(OwnedBar::new(120.0, 123.00000002, 119.5, 122.5, 10.0)); // White
+        bars.push(OwnedBar::new(122.5, 122.99999998, 120.0, 120.5, 10.0)); // Black, same high
+        let last = bars.len() - 1;
+
+        // Exact-match tweezer: only equal highs qualify
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::TweezerTop(TweezerTopDetector {
+                tolerance: Ratio::new(0.0).unwrap(),
+            }))
+            .build()
+            .unwrap();
+        let at_last = |bars: &[OwnedBar]| {
+            engine
+                .scan(bars)
+                .unwrap()
+                .iter()
+                .any(|m| m.end_index == last)
+        };
+        assert!(!at_last(&bars));
+
+        let snapped = snap_to_tick(&bars, 0.01);
+        assert_eq!(snapped[last].high, snapped[last - 1].high);
+        assert!((snapped[last].high - 123.0).abs() < 1e-9);
+        assert!(at_last(&snapped));
+
+        // Invalid ticks leave prices alone
+        assert_eq!(snap_to_tick(&bars, 0.0), bars);
+    }
+}
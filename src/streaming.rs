@@ -0,0 +1,380 @@
+//! Bar-by-bar detection for live feeds
+//!
+//! [`StreamingEngine`] keeps a bounded window of recent bars and reports the
+//! patterns completing on each newly pushed bar. [`scan_streaming`] does the
+//! same for an iterator, e.g. bars read lazily from a file.
+//!
+//! # Example
+//!
+//! ```rust
+//! use yacpd::prelude::*;
+//!
+//! let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+//! let mut stream = StreamingEngine::new(engine, 64);
+//!
+//! for i in 0..30 {
+//!     let base = 100.0 - i as f64;
+//!     let bar = OwnedBar::new(base + 1.0, base + 2.0, base - 1.0, base - 0.5, 1000.0);
+//!     for m in stream.push(&bar) {
+//!         assert_eq!(m.end_index, i);
+//!     }
+//! }
+//!
+//! // New session: start again from index 0
+//! stream.reset();
+//! assert_eq!(stream.bars_seen(), 0);
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::{
+    resample::OwnedBar, BarPatterns, ContextProvider, DefaultContextProvider, MarketContext,
+    PatternEngine, PatternError, PatternMatch, Result, OHLCV,
+};
+
+/// Bars before a pattern that the detectors' trailing-average helpers read
+/// (TA-Lib's `TA_CANDLEAVGPERIOD`)
+const TRAILING_AVG_BARS: usize = 10;
+
+// ============================================================
+// STREAMING ENGINE
+// ============================================================
+
+/// Incremental wrapper around a [`PatternEngine`].
+///
+/// Holds the last `capacity` bars in a ring buffer. Each [`push`] recomputes
+/// the contexts of that window, costing `O(capacity)`, and detects at the new
+/// bar, so results match a batch scan as long as `capacity` covers the longest
+/// detector lookback plus the context provider periods;
+/// [`PatternEngine::history_len`] gives that size when the providers report
+/// it. Match indices count bars pushed since creation or the last [`reset`].
+///
+/// [`push`]: StreamingEngine::push
+/// [`reset`]: StreamingEngine::reset
+pub struct StreamingEngine<C: ContextProvider = DefaultContextProvider> {
+    engine: PatternEngine<C>,
+    buffer: VecDeque<OwnedBar>,
+    capacity: usize,
+    bars_seen: usize,
+}
+
+impl<C: ContextProvider> StreamingEngine<C> {
+    /// Wrap `engine`, keeping at most `capacity` bars (at least 1)
+    pub fn new(engine: PatternEngine<C>, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            engine,
+            buffer: VecDeque::with_capacity(capacity + 1),
+            capacity,
+            bars_seen: 0,
+        }
+    }
+
+    /// Append a bar and return the patterns ending on it
+    pub fn push<T: OHLCV + ?Sized>(&mut self, bar: &T) -> Vec<PatternMatch> {
+        self.bars_seen += 1;
+        push_window(
+            &self.engine,
+            &mut self.buffer,
+            self.capacity,
+            self.bars_seen,
+            bar,
+            None,
+        )
+    }
+
+    /// Drop all buffered bars and restart indexing at 0.
+    ///
+    /// Use when a new session or instrument starts so that its first bars are
+    /// not compared against, or averaged with, the previous ones.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.bars_seen = 0;
+    }
+
+    /// Bars pushed since creation or the last reset
+    pub fn bars_seen(&self) -> usize {
+        self.bars_seen
+    }
+
+    /// The wrapped engine
+    pub fn engine(&self) -> &PatternEngine<C> {
+        &self.engine
+    }
+}
+
+/// Append `bar` to a window of at most `capacity` bars and detect at it.
+///
+/// `bars_seen` counts bars pushed so far, including this one; match indices
+/// are shifted from window positions to that count. Without a precomputed
+/// `ctx` for the bar, the contexts of the whole window are computed.
+fn push_window<C: ContextProvider, T: OHLCV + ?Sized>(
+    engine: &PatternEngine<C>,
+    buffer: &mut VecDeque<OwnedBar>,
+    capacity: usize,
+    bars_seen: usize,
+    bar: &T,
+    ctx: Option<&MarketContext>,
+) -> Vec<PatternMatch> {
+    buffer.push_back(OwnedBar::from_ohlcv(bar));
+    if buffer.len() > capacity {
+        buffer.pop_front();
+    }
+
+    let window = buffer.make_contiguous();
+    let last = window.len() - 1;
+    let offset = bars_seen - window.len();
+    let mut matches = match ctx {
+        Some(ctx) => engine.scan_at(window, last, ctx),
+        None => {
+            let contexts = engine.compute_contexts(window);
+            engine.scan_at(window, last, &contexts[last])
+        }
+    };
+    for m in &mut matches {
+        m.start_index += offset;
+        m.end_index += offset;
+    }
+    matches
+}
+
+// ============================================================
+// ITERATOR ADAPTER
+// ============================================================
+
+/// Scan bars from an iterator without materializing the series.
+///
+/// Yields one [`BarPatterns`] per input bar, like [`PatternEngine::iter`];
+/// the output equals a slice scan. Contexts are computed incrementally when
+/// the engine's context provider supports it
+/// ([`ContextProvider::incremental`], e.g. [`DefaultContextProvider`]) and no
+/// per-detector overrides are registered: each bar's context is computed once
+/// from running state, trend confirmation and Wilder smoothing included, and
+/// only the last [`PatternEngine::max_min_bars`] bars plus the 10 bars the
+/// trailing-average helpers read are held.
+///
+/// Other providers fall back to holding the last
+/// [`PatternEngine::history_len`] bars and recomputing the window's contexts
+/// for every bar, `O(history_len)` per bar. That fails with
+/// `PatternError::InvalidConfig` if the engine's context provider or a
+/// per-detector override reports no bounded
+/// [`lookback`](ContextProvider::lookback): their contexts depend on bars the
+/// window would drop or are unknown (custom providers that do not implement
+/// it). Data validation is not performed.
+pub fn scan_streaming<'a, I, T, C>(
+    engine: &'a PatternEngine<C>,
+    iter: I,
+) -> Result<impl Iterator<Item = BarPatterns> + 'a>
+where
+    I: IntoIterator<Item = T>,
+    I::IntoIter: 'a,
+    T: OHLCV,
+    C: ContextProvider,
+{
+    let mut incremental = if engine.context_overrides.is_empty() {
+        engine.context_provider.incremental()
+    } else {
+        None
+    };
+    let capacity = match incremental {
+        // The running state carries the contexts; the window only serves the
+        // detectors
+        Some(_) => engine.max_min_bars() + TRAILING_AVG_BARS,
+        None => engine.history_len().ok_or_else(|| {
+            PatternError::InvalidConfig(
+                "scan_streaming needs context providers with a bounded lookback".into(),
+            )
+        })?,
+    }
+    .max(1);
+    let mut buffer = VecDeque::with_capacity(capacity + 1);
+    Ok(iter.into_iter().enumerate().map(move |(index, bar)| {
+        let ctx = incremental.as_mut().map(|state| state.push(&bar));
+        let patterns = push_window(engine, &mut buffer, capacity, index + 1, &bar, ctx.as_ref());
+        BarPatterns { index, patterns }
+    }))
+}
+
+// ============================================================
+// TESTS
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EngineBuilder;
+
+    fn downtrend() -> Vec<OwnedBar> {
+        (0..20)
+            .map(|i| {
+                let base = 100.0 - i as f64 * 2.0;
+                OwnedBar::new(base + 1.0, base + 2.0, base - 1.0, base - 0.5, 1000.0)
+            })
+            .chain([OwnedBar::new(60.0, 65.0, 55.0, 60.0, 1000.0)])
+            .collect()
+    }
+
+    fn keys(matches: &[PatternMatch]) -> Vec<(&'static str, usize, usize)> {
+        matches
+            .iter()
+            .map(|m| (m.pattern_id.0, m.start_index, m.end_index))
+            .collect()
+    }
+
+    #[test]
+    fn test_streaming_matches_batch_scan() {
+        let bars = downtrend();
+        let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        let batch = engine.scan(&bars).unwrap();
+
+        let mut stream = StreamingEngine::new(engine, 64);
+        let streamed: Vec<PatternMatch> = bars.iter().flat_map(|b| stream.push(b)).collect();
+        assert!(!batch.is_empty());
+        assert_eq!(keys(&streamed), keys(&batch));
+    }
+
+    #[test]
+    fn test_scan_streaming_matches_slice_scan() {
+        let mut bars = downtrend();
+        bars.extend((0..40).map(|i| {
+            let base = 60.0 + i as f64 * 1.5;
+            OwnedBar::new(base - 0.5, base + 1.5, base - 1.5, base + 1.0, 1000.0)
+        }));
+        let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+        assert!(engine.history_len().unwrap() < bars.len());
+
+        let expected: Vec<_> = engine
+            .iter(&bars)
+            .map(|bp| (bp.index, keys(&bp.patterns)))
+            .collect();
+        // A consuming iterator, so no slice of the series ever exists
+        let streamed: Vec<_> = scan_streaming(&engine, bars.clone())
+            .unwrap()
+            .map(|bp| (bp.index, keys(&bp.patterns)))
+            .collect();
+        assert_eq!(streamed.len(), bars.len());
+        assert!(expected.iter().any(|(_, p)| !p.is_empty()));
+        assert_eq!(streamed, expected);
+    }
+
+    #[test]
+    fn test_scan_streaming_with_custom_providers() {
+        use crate::{AverageMethod, Trend, TrendMethod, WarmupPolicy};
+
+        /// Trend from the last three closes
+        struct ShortTrend {
+            lookback: Option<usize>,
+        }
+
+        impl ContextProvider for ShortTrend {
+            fn compute_all<T: OHLCV>(&self, bars: &[T]) -> Vec<MarketContext> {
+                (0..bars.len())
+                    .map(|i| MarketContext {
+                        trend: match i.checked_sub(2).map(|j| bars[i].close() - bars[j].close()) {
+                            Some(d) if d > 0.0 => Trend::StrongUp,
+                            Some(d) if d < 0.0 => Trend::StrongDown,
+                            _ => Trend::Sideways,
+                        },
+                        ..MarketContext::default()
+                    })
+                    .collect()
+            }
+
+            fn lookback(&self) -> Option<usize> {
+                self.lookback
+            }
+        }
+
+        let mut bars = downtrend();
+        bars.extend((0..30).map(|i| {
+            let base = 60.0 + i as f64 * 1.5;
+            OwnedBar::new(base - 0.5, base + 1.5, base - 1.5, base + 1.0, 1000.0)
+        }));
+        fn assert_matches_slice<C: ContextProvider>(engine: PatternEngine<C>, bars: &[OwnedBar]) {
+            let expected: Vec<_> = engine
+                .iter(bars)
+                .map(|bp| (bp.index, keys(&bp.patterns)))
+                .collect();
+            let streamed: Vec<_> = scan_streaming(&engine, bars.iter().copied())
+                .unwrap()
+                .map(|bp| (bp.index, keys(&bp.patterns)))
+                .collect();
+            assert!(expected.iter().any(|(_, p)| !p.is_empty()));
+            assert_eq!(streamed, expected);
+        }
+
+        assert_matches_slice(
+            EngineBuilder::new()
+                .context_provider(ShortTrend { lookback: Some(3) })
+                .with_all_defaults()
+                .build()
+                .unwrap(),
+            &bars,
+        );
+        assert_matches_slice(
+            EngineBuilder::new()
+                .context_provider(DefaultContextProvider {
+                    trend_method: TrendMethod::LinearRegression,
+                    ..DefaultContextProvider::default()
+                })
+                .with_all_defaults()
+                .build()
+                .unwrap(),
+            &bars,
+        );
+
+        // Unknown or unbounded lookback cannot be windowed
+        let unknown = EngineBuilder::new()
+            .context_provider(ShortTrend { lookback: None })
+            .with_all_defaults()
+            .build()
+            .unwrap();
+        assert!(matches!(
+            scan_streaming(&unknown, bars.iter().copied()),
+            Err(PatternError::InvalidConfig(_))
+        ));
+        let clamped = EngineBuilder::new()
+            .context_provider(DefaultContextProvider {
+                warmup_policy: WarmupPolicy::ClampToFirstFull,
+                ..DefaultContextProvider::default()
+            })
+            .with_all_defaults()
+            .build()
+            .unwrap();
+        assert!(scan_streaming(&clamped, bars.iter().copied()).is_err());
+
+        // State carried from every earlier bar streams through the running
+        // contexts, although no window would cover it
+        let confirmed = EngineBuilder::new()
+            .context_provider(DefaultContextProvider {
+                trend_confirm_bars: 3,
+                average_method: AverageMethod::Wilder,
+                ..DefaultContextProvider::default()
+            })
+            .with_all_defaults()
+            .build()
+            .unwrap();
+        assert!(confirmed.history_len().is_none());
+        assert_matches_slice(confirmed, &bars);
+    }
+
+    #[test]
+    fn test_reset_restarts_at_index_zero() {
+        let bars = downtrend();
+        let build = || EngineBuilder::new().with_all_defaults().build().unwrap();
+
+        let mut stream = StreamingEngine::new(build(), 64);
+        for bar in &bars {
+            stream.push(bar);
+        }
+        stream.reset();
+        assert_eq!(stream.bars_seen(), 0);
+
+        let doji = OwnedBar::new(60.0, 65.0, 55.0, 60.0, 1000.0);
+        let after_reset = stream.push(&doji);
+        let fresh = StreamingEngine::new(build(), 64).push(&doji);
+        assert_eq!(keys(&after_reset), keys(&fresh));
+        assert!(after_reset.iter().all(|m| m.end_index == 0));
+        assert_eq!(stream.bars_seen(), 1);
+    }
+}
@@ -1,4 +1,5 @@
-//! Comprehensive tests for all 33 extended (non-TA-Lib) candlestick patterns.
+//! Comprehensive tests for all 40 extended (non-TA-Lib) candlestick patterns
+//! and the trend-independent price action patterns.
 //!
 //! Each pattern has:
 //! - Positive test: bars that clearly match the pattern
@@ -267,6 +268,45 @@ fn test_white_marubozu_negative_has_shadows() {
     ));
 }
 
+#[test]
+fn test_white_marubozu_shadow_modes() {
+    // Wide trailing bars (range 10), then a narrow white bar with small shadows.
+    // Shadows are 0.2 of a 2.4 range: too long relative to the bar itself,
+    // but very short relative to the trailing average range.
+    let mut bars: Vec<TestBar> = (0..10)
+        .map(|_| TestBar::new(100.0, 105.0, 95.0, 101.0))
+        .collect();
+    bars.push(TestBar::new(100.2, 102.4, 100.0, 102.2));
+
+    let range_mode = WhiteMarubozuDetector::with_defaults();
+    assert_eq!(range_mode.shadow_mode, ShadowMode::Range);
+    assert!(!fires_at_last(
+        BuiltinDetector::WhiteMarubozu(range_mode),
+        &bars
+    ));
+
+    let avg_mode = WhiteMarubozuDetector {
+        shadow_mode: ShadowMode::TrailingAvg,
+        ..WhiteMarubozuDetector::with_defaults()
+    };
+    assert!(fires_at_last(
+        BuiltinDetector::WhiteMarubozu(avg_mode),
+        &bars
+    ));
+
+    // Selectable through params, and absent from older serialized configs
+    use std::collections::HashMap;
+    let params = HashMap::from([("trailing_avg_shadows", 1.0)]);
+    let from_params = WhiteMarubozuDetector::with_params(&params).unwrap();
+    assert_eq!(from_params.shadow_mode, ShadowMode::TrailingAvg);
+    assert!(WhiteMarubozuDetector::param_meta()
+        .iter()
+        .any(|p| p.name == "trailing_avg_shadows"));
+    let legacy: WhiteMarubozuDetector =
+        serde_json::from_str(r#"{"shadow_tolerance":0.01}"#).unwrap();
+    assert_eq!(legacy.shadow_mode, ShadowMode::Range);
+}
+
 // --- OpeningBlackMarubozu ---
 
 #[test]
@@ -471,6 +511,82 @@ fn test_rising_window_negative_no_gap() {
     ));
 }
 
+// --- ConfirmedWindow ---
+
+#[test]
+fn test_confirmed_window_rising_confirmed() {
+    let bars = vec![
+        TestBar::new(100.0, 102.0, 99.0, 101.5),
+        TestBar::new(103.0, 105.0, 102.5, 104.5), // rising window
+        TestBar::new(104.5, 107.0, 104.0, 106.5), // closes higher
+    ];
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::ConfirmedWindow(ConfirmedWindowDetector))
+        .build()
+        .unwrap();
+    let patterns = engine.scan(&bars).unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0].direction, Direction::Bullish);
+    assert_eq!((patterns[0].start_index, patterns[0].end_index), (0, 2));
+}
+
+#[test]
+fn test_confirmed_window_rising_faded() {
+    let bars = vec![
+        TestBar::new(100.0, 102.0, 99.0, 101.5),
+        TestBar::new(103.0, 105.0, 102.5, 104.5), // rising window
+        TestBar::new(104.5, 105.0, 102.0, 102.5), // fades back into the gap
+    ];
+    assert!(!fires_at_last(
+        BuiltinDetector::ConfirmedWindow(ConfirmedWindowDetector),
+        &bars
+    ));
+}
+
+// --- ThreeGaps ---
+
+#[test]
+fn test_three_gaps_rising_exhaustion() {
+    let bars = vec![
+        TestBar::new(100.0, 102.0, 99.0, 101.5),
+        TestBar::new(103.0, 105.0, 102.5, 104.5), // rising window
+        TestBar::new(106.0, 108.0, 105.5, 107.5), // rising window
+        TestBar::new(109.0, 111.0, 108.5, 110.5), // rising window
+    ];
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::ThreeGaps(
+            ThreeGapsDetector::with_defaults(),
+        ))
+        .build()
+        .unwrap();
+    let patterns = engine.scan(&bars).unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0].direction, Direction::Bearish);
+    assert_eq!((patterns[0].start_index, patterns[0].end_index), (0, 3));
+
+    // Gaps of 0.5 against ~2.5 ranges fall short of a 0.3 minimum
+    assert!(!fires_at_last(
+        BuiltinDetector::ThreeGaps(ThreeGapsDetector {
+            min_gap: Ratio::new(0.3).unwrap(),
+        }),
+        &bars
+    ));
+}
+
+#[test]
+fn test_three_gaps_negative_two_gaps() {
+    let bars = vec![
+        TestBar::new(100.0, 102.0, 99.0, 101.5),
+        TestBar::new(101.5, 104.0, 101.0, 103.5), // overlaps, no gap
+        TestBar::new(105.0, 107.0, 104.5, 106.5), // rising window
+        TestBar::new(108.0, 110.0, 107.5, 109.5), // rising window
+    ];
+    assert!(!fires_at_last(
+        BuiltinDetector::ThreeGaps(ThreeGapsDetector::with_defaults()),
+        &bars
+    ));
+}
+
 // --- GappingDownDoji ---
 
 #[test]
@@ -748,6 +864,113 @@ fn test_shooting_star_2_lines_negative_large_body() {
     ));
 }
 
+// --- Kicker ---
+
+fn kicker_at_last(bars: &[TestBar]) -> Option<PatternMatch> {
+    let engine = EngineBuilder::new()
+        .with_price_action_defaults()
+        .build()
+        .unwrap();
+    let last = bars.len() - 1;
+    engine
+        .scan(bars)
+        .unwrap()
+        .into_iter()
+        .find(|p| p.end_index == last && p.pattern_id == PatternId("KICKER"))
+}
+
+#[test]
+fn test_kicker_bullish_ignores_trend() {
+    // Black marubozu, then a white marubozu gapping above it - at the top of an uptrend
+    let mut bars = make_uptrend(15);
+    bars.push(TestBar::new(130.0, 130.0, 125.0, 125.0));
+    bars.push(TestBar::new(131.0, 136.0, 131.0, 136.0));
+    let m = kicker_at_last(&bars).expect("bullish kicker");
+    assert_eq!(m.direction, Direction::Bullish);
+    assert_eq!(m.start_index, bars.len() - 2);
+}
+
+#[test]
+fn test_kicker_bearish_ignores_trend() {
+    // White marubozu, then a black marubozu gapping below it - at the bottom of a downtrend
+    let mut bars = make_downtrend(15);
+    bars.push(TestBar::new(65.0, 70.0, 65.0, 70.0));
+    bars.push(TestBar::new(64.0, 64.0, 59.0, 59.0));
+    let m = kicker_at_last(&bars).expect("bearish kicker");
+    assert_eq!(m.direction, Direction::Bearish);
+}
+
+#[test]
+fn test_kicker_negative_no_gap() {
+    let mut bars = make_uptrend(15);
+    bars.push(TestBar::new(130.0, 130.0, 125.0, 125.0));
+    bars.push(TestBar::new(129.0, 134.0, 129.0, 134.0));
+    assert!(kicker_at_last(&bars).is_none());
+}
+
+// --- NewHigh / NewLow ---
+
+fn breakouts(bars: &[TestBar], id: &str) -> Vec<PatternMatch> {
+    let engine = EngineBuilder::new()
+        .with_price_action_defaults()
+        .build()
+        .unwrap();
+    engine
+        .scan(bars)
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.pattern_id.as_str() == id)
+        .collect()
+}
+
+#[test]
+fn test_new_high_on_breakout_ramp() {
+    // Range 98..102 for 20 bars, a close at the top, then a close above it
+    let mut bars = make_sideways(20);
+    bars.push(TestBar::new(101.0, 102.5, 100.5, 102.0));
+    bars.push(TestBar::new(102.0, 104.0, 101.5, 103.5));
+    bars.push(TestBar::new(103.5, 106.0, 103.0, 105.5));
+
+    let highs = breakouts(&bars, "NEW_HIGH");
+    let ends: Vec<usize> = highs.iter().map(|m| m.end_index).collect();
+    assert_eq!(ends, vec![21, 22]);
+    assert!(highs.iter().all(|m| m.direction == Direction::Bullish));
+    assert_eq!(highs[0].start_index, 1);
+    assert!(breakouts(&bars, "NEW_LOW").is_empty());
+}
+
+#[test]
+fn test_new_low_on_breakdown_ramp() {
+    let mut bars = make_sideways(20);
+    bars.push(TestBar::new(99.0, 99.5, 97.5, 98.0));
+    bars.push(TestBar::new(98.0, 98.5, 96.0, 96.5));
+
+    let lows = breakouts(&bars, "NEW_LOW");
+    assert_eq!(lows.len(), 1);
+    assert_eq!(lows[0].end_index, 21);
+    assert_eq!(lows[0].direction, Direction::Bearish);
+    assert!(breakouts(&bars, "NEW_HIGH").is_empty());
+}
+
+#[test]
+fn test_new_high_respects_period() {
+    // Every bar of a steady ramp closes above the prior highs
+    let bars = make_uptrend(25);
+    let ends: Vec<usize> = breakouts(&bars, "NEW_HIGH")
+        .iter()
+        .map(|m| m.end_index)
+        .collect();
+    assert_eq!(ends, (20..25).collect::<Vec<_>>());
+
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::NewHigh(NewHighDetector {
+            period: Period::new(5).unwrap(),
+        }))
+        .build()
+        .unwrap();
+    assert_eq!(engine.scan(&bars).unwrap().len(), 20);
+}
+
 // ============================================================
 // THREE-BAR PATTERNS
 // ============================================================
@@ -1078,6 +1301,80 @@ fn test_price_lines_bearish_positive() {
     assert_eq!(pl.unwrap().direction, Direction::Bullish);
 }
 
+#[test]
+fn test_price_lines_reports_run_length() {
+    // 10 consecutive bullish candles after a bearish one
+    let mut bars = vec![TestBar::new(101.0, 101.5, 99.5, 100.0)];
+    bars.extend((0..10).map(|i| {
+        let base = 100.0 + i as f64 * 2.0;
+        TestBar::new(base, base + 1.5, base - 0.5, base + 1.0)
+    }));
+    let last = bars.len() - 1;
+
+    let scan = |det: PriceLinesDetector| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::PriceLines(det))
+            .build()
+            .unwrap();
+        let patterns = engine.scan(&bars).unwrap();
+        *patterns.iter().find(|p| p.end_index == last).unwrap()
+    };
+
+    let m = scan(PriceLinesDetector::with_range(8, 13));
+    assert_eq!(m.metric, Some(10.0));
+    assert_eq!(m.end_index - m.start_index + 1, 10);
+    assert_eq!(m.start_index, 1);
+    assert!((m.strength - 0.7).abs() < 1e-9);
+
+    // Runs longer than max_count are capped
+    let m = scan(PriceLinesDetector::with_range(4, 6));
+    assert_eq!(m.metric, Some(6.0));
+    assert_eq!(m.end_index - m.start_index + 1, 6);
+    assert!((m.strength - 1.0).abs() < 1e-9);
+
+    // Longer runs score higher
+    let eight = {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::PriceLines(PriceLinesDetector::with_range(
+                8, 13,
+            )))
+            .build()
+            .unwrap();
+        let patterns = engine.scan(&bars).unwrap();
+        *patterns.iter().find(|p| p.end_index == 8).unwrap()
+    };
+    assert_eq!(eight.end_index - eight.start_index + 1, 8);
+    assert!(eight.strength < 0.7);
+}
+
+#[test]
+fn test_price_lines_fixed_count_strength() {
+    let bars: Vec<TestBar> = (0..13)
+        .map(|i| {
+            let base = 100.0 + i as f64 * 2.0;
+            TestBar::new(base, base + 1.5, base - 0.5, base + 1.0)
+        })
+        .collect();
+    let last = bars.len() - 1;
+
+    for (det, expected) in [
+        (PriceLinesDetector::eight(), 0.5),
+        (PriceLinesDetector::ten(), 0.6),
+        (PriceLinesDetector::twelve(), 0.7),
+        (PriceLinesDetector::thirteen(), 0.75),
+    ] {
+        let count = det.min_count;
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::PriceLines(det))
+            .build()
+            .unwrap();
+        let patterns = engine.scan(&bars).unwrap();
+        let m = patterns.iter().find(|p| p.end_index == last).unwrap();
+        assert_eq!(m.end_index - m.start_index + 1, count);
+        assert!((m.strength - expected).abs() < 1e-9, "count {count}");
+    }
+}
+
 #[test]
 fn test_price_lines_negative_mixed() {
     // Mix of bullish and bearish — should not fire
@@ -1112,6 +1409,212 @@ fn test_price_lines_negative_only_7() {
     ));
 }
 
+// --- TowerTop / TowerBottom ---
+
+#[test]
+fn test_tower_top_positive() {
+    // Long white, three small candles holding near the top, long black back down
+    let bars = vec![
+        TestBar::new(100.0, 110.5, 99.5, 110.0),
+        TestBar::new(110.0, 111.0, 108.5, 109.5),
+        TestBar::new(109.5, 110.5, 108.0, 110.0),
+        TestBar::new(110.0, 111.0, 108.5, 109.0),
+        TestBar::new(109.0, 109.5, 100.0, 100.5),
+    ];
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::TowerTop(TowerTopDetector::with_defaults()))
+        .build()
+        .unwrap();
+    let patterns = engine.scan(&bars).unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0].direction, Direction::Bearish);
+    assert_eq!((patterns[0].start_index, patterns[0].end_index), (0, 4));
+
+    // A shorter consolidation does not fit the same bars at the last index
+    assert!(!fires_at_last(
+        BuiltinDetector::TowerTop(TowerTopDetector {
+            consolidation: Period::new(2).unwrap(),
+            ..TowerTopDetector::with_defaults()
+        }),
+        &bars
+    ));
+}
+
+#[test]
+fn test_tower_top_negative_large_middle_body() {
+    let bars = vec![
+        TestBar::new(100.0, 110.5, 99.5, 110.0),
+        TestBar::new(110.0, 111.0, 108.5, 109.5),
+        TestBar::new(106.0, 114.0, 105.5, 113.5), // body 7.5 - not consolidation
+        TestBar::new(110.0, 111.0, 108.5, 109.0),
+        TestBar::new(109.0, 109.5, 100.0, 100.5),
+    ];
+    assert!(!fires_at_last(
+        BuiltinDetector::TowerTop(TowerTopDetector::with_defaults()),
+        &bars
+    ));
+}
+
+#[test]
+fn test_tower_bottom_positive() {
+    // Long black, three small candles holding near the low, long white back up
+    let bars = vec![
+        TestBar::new(110.0, 110.5, 99.5, 100.0),
+        TestBar::new(100.0, 101.5, 99.0, 100.5),
+        TestBar::new(100.5, 102.0, 99.5, 100.0),
+        TestBar::new(100.0, 101.5, 99.0, 101.0),
+        TestBar::new(101.0, 110.0, 100.5, 109.5),
+    ];
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::TowerBottom(
+            TowerBottomDetector::with_defaults(),
+        ))
+        .build()
+        .unwrap();
+    let patterns = engine.scan(&bars).unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0].direction, Direction::Bullish);
+    assert_eq!((patterns[0].start_index, patterns[0].end_index), (0, 4));
+}
+
+#[test]
+fn test_tower_bottom_negative_weak_recovery() {
+    // Final white candle stays below the midpoint of the first body
+    let bars = vec![
+        TestBar::new(110.0, 110.5, 99.5, 100.0),
+        TestBar::new(100.0, 101.5, 99.0, 100.5),
+        TestBar::new(100.5, 102.0, 99.5, 100.0),
+        TestBar::new(100.0, 101.5, 99.0, 101.0),
+        TestBar::new(101.0, 104.5, 100.5, 104.0),
+    ];
+    assert!(!fires_at_last(
+        BuiltinDetector::TowerBottom(TowerBottomDetector::with_defaults()),
+        &bars
+    ));
+}
+
+// --- FryPanBottom ---
+
+/// Twelve bars whose lows trace a U (trough at bar 6), closes mid-bar
+fn make_fry_pan() -> Vec<TestBar> {
+    [
+        104.0, 102.5, 101.2, 100.4, 99.9, 99.6, 99.5, 99.6, 99.9, 100.4, 101.2, 102.5,
+    ]
+    .iter()
+    .map(|&low| TestBar::new(low + 0.8, low + 1.5, low, low + 0.7))
+    .collect()
+}
+
+#[test]
+fn test_fry_pan_bottom_on_u_shaped_lows() {
+    let mut bars = make_fry_pan();
+    // Gap up over the last pan bar's high (104.0)
+    bars.push(TestBar::new(104.5, 106.5, 104.3, 106.0));
+
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::FryPanBottom(
+            FryPanBottomDetector::with_defaults(),
+        ))
+        .build()
+        .unwrap();
+    let patterns = engine.scan(&bars).unwrap();
+    assert_eq!(patterns.len(), 1);
+    let m = patterns[0];
+    assert_eq!(m.direction, Direction::Bullish);
+    assert_eq!((m.start_index, m.end_index), (0, 12));
+    assert!((m.strength - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_fry_pan_bottom_negative_no_breakout() {
+    // Rounded lows but the last bar stays inside the pan
+    let mut bars = make_fry_pan();
+    bars.push(TestBar::new(103.0, 103.5, 102.0, 103.2));
+    assert!(!fires_at_last(
+        BuiltinDetector::FryPanBottom(FryPanBottomDetector::with_defaults()),
+        &bars
+    ));
+}
+
+#[test]
+fn test_fry_pan_bottom_negative_v_shape_edge() {
+    // Straight decline: the trough is the last pan bar, not a rounded bottom
+    let mut bars = make_downtrend(12);
+    let prev_high = bars[11].h;
+    bars.push(TestBar::new(
+        prev_high + 1.0,
+        prev_high + 3.0,
+        prev_high + 0.5,
+        prev_high + 2.5,
+    ));
+    assert!(!fires_at_last(
+        BuiltinDetector::FryPanBottom(FryPanBottomDetector::with_defaults()),
+        &bars
+    ));
+}
+
+// --- Consolidation ---
+
+#[test]
+fn test_consolidation_on_choppy_series() {
+    // Flat, choppy market: small bodies alternating color around 100
+    let bars: Vec<TestBar> = (0..8)
+        .map(|i| {
+            if i % 2 == 0 {
+                TestBar::new(99.8, 101.0, 99.0, 100.2)
+            } else {
+                TestBar::new(100.2, 101.0, 99.0, 99.8)
+            }
+        })
+        .collect();
+
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::Consolidation(
+            ConsolidationDetector::with_defaults(),
+        ))
+        .build()
+        .unwrap();
+    let patterns = engine.scan(&bars).unwrap();
+    // One match per bar once the window fills
+    assert_eq!(patterns.len(), 3);
+    let m = patterns[2];
+    assert_eq!(m.direction, Direction::Neutral);
+    assert_eq!((m.start_index, m.end_index), (2, 7));
+    assert!((m.strength - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_consolidation_strength_scales_with_density() {
+    // Four small bodies, then two long ones: exactly `min_count` of six
+    let mut bars = make_sideways(4);
+    bars.extend(
+        make_uptrend(2)
+            .iter()
+            .map(|b| TestBar::new(b.l, b.h, b.l, b.h)),
+    );
+
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::Consolidation(
+            ConsolidationDetector::with_defaults(),
+        ))
+        .build()
+        .unwrap();
+    let patterns = engine.scan(&bars).unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert!((patterns[0].strength - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_consolidation_negative_isolated_spinning_top() {
+    // A single spinning top after a run of long bodies
+    let mut bars = make_uptrend(6);
+    bars.push(TestBar::new(112.0, 114.0, 110.0, 112.5));
+    assert!(!fires_at_last(
+        BuiltinDetector::Consolidation(ConsolidationDetector::with_defaults()),
+        &bars
+    ));
+}
+
 // ============================================================
 // DIRECTION CHECKS
 // ============================================================
@@ -1147,16 +1650,24 @@ fn test_pattern_directions() {
 
 #[test]
 fn test_doji_edge_not_black_or_white() {
-    // open == close: neither bullish nor bearish
+    // open == close: neither bullish nor bearish under the strict convention
     let bars = vec![TestBar::new(100.0, 105.0, 95.0, 100.0)];
-    assert!(!fires_at_last(
-        BuiltinDetector::BlackCandle(BlackCandleDetector::with_defaults()),
-        &bars
-    ));
-    assert!(!fires_at_last(
-        BuiltinDetector::WhiteCandle(WhiteCandleDetector::with_defaults()),
-        &bars
-    ));
+    let fires = |detector, convention| {
+        let engine = EngineBuilder::new()
+            .add(detector)
+            .color_convention(convention)
+            .build()
+            .unwrap();
+        !engine.scan(&bars).unwrap().is_empty()
+    };
+    let black = || BuiltinDetector::BlackCandle(BlackCandleDetector::with_defaults());
+    let white = || BuiltinDetector::WhiteCandle(WhiteCandleDetector::with_defaults());
+    assert!(!fires(black(), ColorConvention::Strict));
+    assert!(!fires(white(), ColorConvention::Strict));
+
+    // TA-Lib colors a flat bar white
+    assert!(!fires(black(), ColorConvention::TaLib));
+    assert!(fires(white(), ColorConvention::TaLib));
 }
 
 // ============================================================
@@ -1196,6 +1707,42 @@ fn test_deliberation_with_params() {
     assert!((det.long_body_pct.get() - 0.65).abs() < 1e-9);
 }
 
+#[test]
+fn test_consolidation_with_params_rejects_unreachable_count() {
+    use std::collections::HashMap;
+    let mut params = HashMap::new();
+    params.insert("window", 8.0);
+    params.insert("min_count", 8.0);
+    let det = ConsolidationDetector::with_params(&params).unwrap();
+    assert_eq!(det.window.get(), 8);
+    assert_eq!(det.min_count.get(), 8);
+
+    params.insert("min_count", 9.0);
+    assert!(matches!(
+        ConsolidationDetector::with_params(&params),
+        Err(PatternError::InvalidConfig(_))
+    ));
+}
+
+#[test]
+fn test_long_white_day_suggest_params() {
+    let mut bars = make_downtrend(10);
+    // 60% body: rejected by the default 70% threshold
+    bars.push(TestBar::new(100.0, 108.0, 98.0, 106.0));
+
+    let default = BuiltinDetector::LongWhiteDay(LongWhiteDayDetector::with_defaults());
+    assert!(!fires_at_last(default, &bars));
+
+    let params = LongWhiteDayDetector::suggest_params(&bars, 10).unwrap();
+    assert!((params["body_pct"] - 0.6).abs() < 1e-9);
+    let tuned = LongWhiteDayDetector::with_params(&params).unwrap();
+    assert!(fires_at_last(BuiltinDetector::LongWhiteDay(tuned), &bars));
+
+    // Black candle: no body_pct makes it a long white day
+    assert!(LongWhiteDayDetector::suggest_params(&bars, 9).is_none());
+    assert!(LongWhiteDayDetector::suggest_params(&bars, 11).is_none());
+}
+
 #[test]
 fn test_collapsing_doji_star_default_matches_meta() {
     // Bug 3 regression test: default gap_pct must match ParamMeta default (0.005)
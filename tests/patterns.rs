@@ -88,6 +88,37 @@ fn test_doji_detection() {
     assert_eq!(patterns[0].pattern_id.0, "CDL_DOJI");
 }
 
+#[test]
+fn test_doji_suppress_generic() {
+    let mut bars = make_sideways(10);
+    // Long-legged doji: tiny body, long shadows on both sides
+    bars.push(TestBar::new(100.0, 106.0, 94.0, 100.05));
+    let last = bars.len() - 1;
+
+    let ids_at_last = |suppress_generic: bool| -> Vec<&'static str> {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Doji(DojiDetector {
+                suppress_generic,
+                ..Default::default()
+            }))
+            .add(BuiltinDetector::LongLeggedDoji(
+                LongLeggedDojiDetector::with_defaults(),
+            ))
+            .build()
+            .unwrap();
+        engine
+            .scan(&bars)
+            .unwrap()
+            .iter()
+            .filter(|m| m.end_index == last)
+            .map(|m| m.pattern_id.as_str())
+            .collect()
+    };
+
+    assert_eq!(ids_at_last(false), ["CDL_DOJI", "CDL_LONGLEGGEDDOJI"]);
+    assert_eq!(ids_at_last(true), ["CDL_LONGLEGGEDDOJI"]);
+}
+
 #[test]
 fn test_dragonfly_doji_detection() {
     let mut bars = make_downtrend(10);
@@ -122,6 +153,30 @@ fn test_gravestone_doji_detection() {
     assert!(!patterns.is_empty(), "Gravestone Doji should be detected");
 }
 
+fn single_strength(detector: BuiltinDetector, bar: TestBar) -> f64 {
+    let mut bars = make_sideways(10);
+    bars.push(bar);
+    let engine = EngineBuilder::new().add(detector).build().unwrap();
+    let patterns = engine.scan(&bars).unwrap();
+    assert_eq!(patterns.len(), 1, "expected a single match on the last bar");
+    patterns[0].strength
+}
+
+#[test]
+fn test_doji_shadow_strength_gradient() {
+    let dragonfly = || BuiltinDetector::DragonflyDoji(DragonflyDojiDetector::with_defaults());
+    // Sideways bars have a range of 4.0; a 0.8 tail barely clears ShadowVeryShort
+    let marginal = single_strength(dragonfly(), TestBar::new(100.0, 100.0, 99.2, 100.0));
+    let long = single_strength(dragonfly(), TestBar::new(100.0, 100.0, 94.0, 100.0));
+    assert!(long > marginal, "{long} should exceed {marginal}");
+    assert!(long <= 1.0);
+
+    let gravestone = || BuiltinDetector::GravestoneDoji(GravestoneDojiDetector::with_defaults());
+    let marginal = single_strength(gravestone(), TestBar::new(100.0, 100.8, 100.0, 100.0));
+    let long = single_strength(gravestone(), TestBar::new(100.0, 106.0, 100.0, 100.0));
+    assert!(long > marginal, "{long} should exceed {marginal}");
+}
+
 #[test]
 fn test_marubozu_detection() {
     let mut bars = make_sideways(10);
@@ -137,6 +192,841 @@ fn test_marubozu_detection() {
     assert!(!patterns.is_empty(), "Marubozu should be detected");
 }
 
+/// Append a tiny bar and a hammer-shaped bar whose body sits on the tiny bar,
+/// so both the Hammer and Hanging Man position checks pass.
+fn push_hammer_shape(bars: &mut Vec<TestBar>, level: f64) {
+    bars.push(TestBar::new(level, level + 0.05, level - 0.05, level));
+    bars.push(TestBar::new(level, level + 0.1, level - 3.0, level + 0.05));
+}
+
+fn hammer_family_ids(bars: &[TestBar], require_trend: bool) -> Vec<&'static str> {
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::Hammer(HammerDetector {
+            require_trend,
+            ..Default::default()
+        }))
+        .add(BuiltinDetector::HangingMan(HangingManDetector {
+            require_trend,
+            ..Default::default()
+        }))
+        .build()
+        .unwrap();
+    let last = bars.len() - 1;
+    engine
+        .scan(bars)
+        .unwrap()
+        .iter()
+        .filter(|p| p.end_index == last)
+        .map(|p| p.pattern_id.as_str())
+        .collect()
+}
+
+#[test]
+fn test_hammer_hanging_man_mutually_exclusive() {
+    let mut down = make_downtrend(15);
+    push_hammer_shape(&mut down, 70.0);
+    assert_eq!(hammer_family_ids(&down, true), vec!["CDL_HAMMER"]);
+
+    let mut up = make_uptrend(15);
+    push_hammer_shape(&mut up, 130.0);
+    assert_eq!(hammer_family_ids(&up, true), vec!["CDL_HANGINGMAN"]);
+
+    // Without trend gating the shape alone matches both
+    assert_eq!(hammer_family_ids(&down, false).len(), 2);
+    assert_eq!(hammer_family_ids(&up, false).len(), 2);
+}
+
+#[test]
+fn test_trend_gating_defaults_when_deserialized() {
+    fn legacy<D>(detector: D) -> D
+    where
+        D: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let mut json = serde_json::to_value(detector).unwrap();
+        json.as_object_mut().unwrap().remove("require_trend");
+        serde_json::from_value(json).unwrap()
+    }
+
+    // Serialized with gating off, then loaded as if the field did not exist
+    assert!(
+        legacy(HammerDetector {
+            require_trend: false,
+            ..Default::default()
+        })
+        .require_trend
+    );
+    assert!(
+        legacy(HangingManDetector {
+            require_trend: false,
+            ..Default::default()
+        })
+        .require_trend
+    );
+    assert!(
+        legacy(InvertedHammerDetector {
+            require_trend: false,
+            ..Default::default()
+        })
+        .require_trend
+    );
+    assert!(
+        legacy(ShootingStarDetector {
+            require_trend: false,
+            ..Default::default()
+        })
+        .require_trend
+    );
+}
+
+/// Append a small-bodied bar with a long upper shadow whose body gaps away
+/// from the previous body, in the direction of the trend.
+fn push_inverted_shape(bars: &mut Vec<TestBar>, gap_up: bool) {
+    let prev = *bars.last().unwrap();
+    let level = if gap_up {
+        prev.o.max(prev.c) + 1.0
+    } else {
+        prev.o.min(prev.c) - 1.0
+    };
+    bars.push(TestBar::new(level, level + 2.0, level - 0.02, level + 0.2));
+}
+
+fn inverted_family_ids(bars: &[TestBar], require_trend: bool) -> Vec<&'static str> {
+    let engine = EngineBuilder::new()
+        .add(BuiltinDetector::InvertedHammer(InvertedHammerDetector {
+            require_trend,
+            ..Default::default()
+        }))
+        .add(BuiltinDetector::ShootingStar(ShootingStarDetector {
+            require_trend,
+            ..Default::default()
+        }))
+        .build()
+        .unwrap();
+    let last = bars.len() - 1;
+    engine
+        .scan(bars)
+        .unwrap()
+        .iter()
+        .filter(|p| p.end_index == last)
+        .map(|p| p.pattern_id.as_str())
+        .collect()
+}
+
+#[test]
+fn test_inverted_hammer_shooting_star_trend_gating() {
+    let mut down = make_downtrend(15);
+    push_inverted_shape(&mut down, false);
+    assert_eq!(inverted_family_ids(&down, true), vec!["CDL_INVERTEDHAMMER"]);
+
+    let mut up = make_uptrend(15);
+    push_inverted_shape(&mut up, true);
+    assert_eq!(inverted_family_ids(&up, true), vec!["CDL_SHOOTINGSTAR"]);
+
+    // Against the trend the shape is rejected when gated, accepted otherwise
+    let mut down_gap_up = make_downtrend(15);
+    push_inverted_shape(&mut down_gap_up, true);
+    assert!(inverted_family_ids(&down_gap_up, true).is_empty());
+    assert_eq!(
+        inverted_family_ids(&down_gap_up, false),
+        vec!["CDL_SHOOTINGSTAR"]
+    );
+
+    let mut up_gap_down = make_uptrend(15);
+    push_inverted_shape(&mut up_gap_down, false);
+    assert!(inverted_family_ids(&up_gap_down, true).is_empty());
+    assert_eq!(
+        inverted_family_ids(&up_gap_down, false),
+        vec!["CDL_INVERTEDHAMMER"]
+    );
+}
+
+#[test]
+fn test_shooting_star_with_params() {
+    use std::collections::HashMap;
+
+    let mut bars = make_uptrend(15);
+    // Small body gapping up above the previous body, long upper shadow (9x body)
+    bars.push(TestBar::new(130.0, 132.0, 129.98, 130.2));
+
+    let scan = |det: ShootingStarDetector| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::ShootingStar(det))
+            .build()
+            .unwrap();
+        engine.scan(&bars).unwrap()
+    };
+
+    let mut params = HashMap::new();
+    params.insert("shadow_ratio", 2.0);
+    params.insert("body_pct", 0.3);
+    let det = ShootingStarDetector::with_params(&params).unwrap();
+    assert!((det.shadow_ratio - 2.0).abs() < 1e-9);
+    assert!((det.body_pct - 0.3).abs() < 1e-9);
+    assert!(!scan(det).is_empty(), "Shooting star should be detected");
+
+    params.insert("shadow_ratio", 10.0);
+    let det = ShootingStarDetector::with_params(&params).unwrap();
+    assert!(scan(det).is_empty(), "Upper shadow too short for ratio 10");
+
+    params.insert("shadow_ratio", 2.0);
+    params.insert("body_pct", 0.05);
+    let det = ShootingStarDetector::with_params(&params).unwrap();
+    assert!(scan(det).is_empty(), "Body too large for body_pct 0.05");
+
+    params.insert("body_pct", 1.5);
+    assert!(ShootingStarDetector::with_params(&params).is_err());
+    params.insert("body_pct", 0.3);
+    params.insert("shadow_ratio", -1.0);
+    assert!(ShootingStarDetector::with_params(&params).is_err());
+
+    // Trend gating is carried through, and shadow_ratio is a factor above 1
+    params.insert("shadow_ratio", 2.0);
+    assert!(
+        ShootingStarDetector::with_params(&params)
+            .unwrap()
+            .require_trend
+    );
+    params.insert("require_trend", 0.0);
+    assert!(
+        !ShootingStarDetector::with_params(&params)
+            .unwrap()
+            .require_trend
+    );
+    let meta = ShootingStarDetector::param_meta();
+    let shadow_ratio = meta.iter().find(|p| p.name == "shadow_ratio").unwrap();
+    assert_eq!(shadow_ratio.param_type, ParamType::Factor);
+    assert!(shadow_ratio.validate(2.5).is_ok());
+
+    // Detectors serialized before the shape parameters existed keep TA-Lib's
+    let legacy: ShootingStarDetector =
+        serde_json::from_str(r#"{"body_short_factor":1.0,"shadow_veryshort_factor":0.1}"#).unwrap();
+    assert_eq!(legacy.shadow_ratio, 1.0);
+    assert_eq!(legacy.body_pct, 1.0);
+    assert!(legacy.require_trend);
+}
+
+#[test]
+fn test_rickshaw_man_center_tolerance() {
+    use std::collections::HashMap;
+
+    let mut bars = make_sideways(10);
+    // Long-legged doji with the body 1.2 above the range midpoint (100.0)
+    bars.push(TestBar::new(101.2, 105.0, 95.0, 101.2));
+
+    let scan = |det: RickshawManDetector| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::RickshawMan(det))
+            .build()
+            .unwrap();
+        engine.scan(&bars).unwrap()
+    };
+
+    assert!(
+        scan(RickshawManDetector::with_defaults()).is_empty(),
+        "Off-center body should fail the default tolerance"
+    );
+
+    let mut params = HashMap::new();
+    params.insert("center_tolerance", 0.5);
+    let det = RickshawManDetector::with_params(&params).unwrap();
+    assert_eq!(det.center_tolerance.get(), 0.5);
+    assert!(!scan(det).is_empty(), "Looser tolerance should accept it");
+
+    params.insert("center_tolerance", 1.5);
+    assert!(RickshawManDetector::with_params(&params).is_err());
+
+    let legacy = HashMap::from([("near_factor", 0.5)]);
+    let det = RickshawManDetector::with_params(&legacy).unwrap();
+    assert_eq!(det.center_tolerance.get(), 0.5);
+    let det: RickshawManDetector =
+        serde_json::from_str(r#"{"doji_factor":0.1,"near_factor":0.5}"#).unwrap();
+    assert_eq!(det.center_tolerance.get(), 0.5);
+
+    let bad = HashMap::from([("doji_factor", -0.1)]);
+    assert!(RickshawManDetector::with_params(&bad).is_err());
+}
+
+#[test]
+fn test_engulfing_min_body_requirements() {
+    use std::collections::HashMap;
+
+    let matches = |detector: EngulfingDetector, prev: TestBar, curr: TestBar| {
+        let mut bars = make_sideways(12);
+        bars.push(prev);
+        bars.push(curr);
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::Engulfing(detector))
+            .build()
+            .unwrap();
+        engine
+            .scan(&bars)
+            .unwrap()
+            .iter()
+            .any(|m| m.end_index == bars.len() - 1)
+    };
+
+    // Marginal engulf: body 1.1 over body 1.0
+    let prev = TestBar::new(101.0, 101.2, 99.8, 100.0);
+    let curr = TestBar::new(99.95, 101.1, 99.9, 101.05);
+    assert!(matches(EngulfingDetector::default(), prev, curr));
+    let strict = EngulfingDetector {
+        min_engulf_factor: 1.2,
+        ..Default::default()
+    };
+    assert!(!matches(strict, prev, curr));
+
+    // Tiny bars engulfing tinier ones, below the average body of 1.0
+    let prev = TestBar::new(100.2, 100.3, 99.9, 100.0);
+    let curr = TestBar::new(99.9, 100.4, 99.8, 100.3);
+    assert!(matches(EngulfingDetector::default(), prev, curr));
+    let params = HashMap::from([("require_above_avg_body", 1.0)]);
+    let above_avg = EngulfingDetector::with_params(&params).unwrap();
+    assert!(above_avg.require_above_avg_body);
+    assert!(!matches(above_avg, prev, curr));
+
+    // Factors above 1 are valid; non-positive ones are not
+    let params = HashMap::from([("min_engulf_factor", 1.2)]);
+    assert_eq!(
+        EngulfingDetector::with_params(&params)
+            .unwrap()
+            .min_engulf_factor,
+        1.2
+    );
+    for bad in [0.0, -1.0, f64::NAN] {
+        let params = HashMap::from([("min_engulf_factor", bad)]);
+        assert!(EngulfingDetector::with_params(&params).is_err());
+    }
+    // The ratio field keeps its 0..=1 domain
+    let params = HashMap::from([("min_engulf_ratio", 1.2)]);
+    assert!(EngulfingDetector::with_params(&params).is_err());
+
+    // Configs serialized before the new fields existed still load
+    let legacy: EngulfingDetector = serde_json::from_str(r#"{"min_engulf_ratio":1.0}"#).unwrap();
+    assert_eq!(legacy.min_engulf_factor, 1.0);
+    assert!(!legacy.require_above_avg_body);
+}
+
+#[test]
+fn test_separating_lines_requires_trend() {
+    use std::collections::HashMap;
+
+    let fires = |bars: &[TestBar], require_trend: bool| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::SeparatingLines(SeparatingLinesDetector {
+                require_trend,
+                ..Default::default()
+            }))
+            .build()
+            .unwrap();
+        let last = bars.len() - 1;
+        engine
+            .scan(bars)
+            .unwrap()
+            .iter()
+            .any(|m| m.end_index == last && m.direction == Direction::Bullish)
+    };
+
+    // Black pullback, then a long white line from the same open
+    let mut up = make_uptrend(15);
+    up.push(TestBar::new(131.0, 131.5, 127.0, 128.0));
+    up.push(TestBar::new(131.0, 137.0, 130.9, 136.8));
+    assert!(fires(&up, true));
+
+    // Same shape against a downtrend
+    let mut down = make_downtrend(15);
+    down.push(TestBar::new(72.0, 72.5, 68.0, 69.0));
+    down.push(TestBar::new(72.0, 78.0, 71.9, 77.8));
+    assert!(!fires(&down, true));
+    assert!(fires(&down, false));
+
+    let params = HashMap::from([("require_trend", 0.0)]);
+    assert!(
+        !SeparatingLinesDetector::with_params(&params)
+            .unwrap()
+            .require_trend
+    );
+    assert!(
+        SeparatingLinesDetector::with_params(&HashMap::new())
+            .unwrap()
+            .require_trend
+    );
+    let params = HashMap::from([("require_trend", 0.5)]);
+    assert!(SeparatingLinesDetector::with_params(&params).is_err());
+
+    // Snapshots from before the field existed keep the new default
+    let old: SeparatingLinesDetector = serde_json::from_str(r#"{"tolerance": 0.005}"#).unwrap();
+    assert!(old.require_trend);
+}
+
+#[test]
+fn test_harami_cross_split_directional_ids() {
+    let scan = |bars: &[TestBar], split_directional_ids: bool| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::HaramiCross(HaramiCrossDetector {
+                split_directional_ids,
+                ..Default::default()
+            }))
+            .build()
+            .unwrap();
+        let patterns = engine.scan(bars).unwrap();
+        assert_eq!(patterns.len(), 1);
+        patterns[0]
+    };
+
+    // Long black body followed by a doji inside it
+    let mut down = make_downtrend(15);
+    down.push(TestBar::new(72.0, 72.5, 66.0, 66.5));
+    down.push(TestBar::new(69.0, 70.0, 68.0, 69.0));
+    assert_eq!(scan(&down, false).pattern_id, PatternId("CDL_HARAMICROSS"));
+    let m = scan(&down, true);
+    assert_eq!(m.pattern_id, PatternId("CDL_HARAMICROSS_BULL"));
+    assert_eq!(m.pattern_id.typical_direction(), Some(Direction::Bullish));
+
+    // Long white body followed by a doji inside it
+    let mut up = make_uptrend(15);
+    up.push(TestBar::new(130.0, 136.5, 129.5, 136.0));
+    up.push(TestBar::new(133.0, 134.0, 132.0, 133.0));
+    let m = scan(&up, true);
+    assert_eq!(m.pattern_id, PatternId("CDL_HARAMICROSS_BEAR"));
+    assert_eq!(m.pattern_id.typical_direction(), Some(Direction::Bearish));
+
+    // Selectable through params, and absent from older serialized configs
+    use std::collections::HashMap;
+    let params = HashMap::from([("split_directional_ids", 1.0)]);
+    assert!(
+        HaramiCrossDetector::with_params(&params)
+            .unwrap()
+            .split_directional_ids
+    );
+    let legacy: HaramiCrossDetector =
+        serde_json::from_str(r#"{"doji_body_max_ratio":0.1}"#).unwrap();
+    assert!(!legacy.split_directional_ids);
+}
+
+#[test]
+fn test_rise_fall_three_methods_split_directional_ids() {
+    let scan = |bars: &[TestBar], split_directional_ids: bool| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::RiseFallThreeMethods(
+                RiseFallThreeMethodsDetector {
+                    split_directional_ids,
+                    ..Default::default()
+                },
+            ))
+            .build()
+            .unwrap();
+        let patterns = engine.scan(bars).unwrap();
+        assert_eq!(patterns.len(), 1);
+        patterns[0]
+    };
+
+    // Long white, three small black pulling back inside it, long white to a new close
+    let mut rising = make_sideways(10);
+    rising.push(TestBar::new(100.0, 110.5, 99.5, 110.0));
+    rising.push(TestBar::new(108.5, 109.0, 107.0, 107.5));
+    rising.push(TestBar::new(107.5, 108.0, 106.0, 106.5));
+    rising.push(TestBar::new(106.5, 107.0, 105.0, 105.5));
+    rising.push(TestBar::new(106.0, 112.5, 105.5, 112.0));
+    assert_eq!(
+        scan(&rising, false).pattern_id,
+        PatternId("CDL_RISEFALL3METHODS")
+    );
+    let m = scan(&rising, true);
+    assert_eq!(m.pattern_id, PatternId("RISING_THREE_METHODS"));
+    assert_eq!(m.direction, Direction::Bullish);
+    assert_eq!(m.pattern_id.typical_direction(), Some(Direction::Bullish));
+
+    // Mirror: long black, three small white, long black to a new low close
+    let mut falling = make_sideways(10);
+    falling.push(TestBar::new(110.0, 110.5, 99.5, 100.0));
+    falling.push(TestBar::new(101.5, 103.0, 101.0, 102.5));
+    falling.push(TestBar::new(102.5, 104.0, 102.0, 103.5));
+    falling.push(TestBar::new(103.5, 105.0, 103.0, 104.5));
+    falling.push(TestBar::new(104.0, 104.5, 97.5, 98.0));
+    let m = scan(&falling, true);
+    assert_eq!(m.pattern_id, PatternId("FALLING_THREE_METHODS"));
+    assert_eq!(m.direction, Direction::Bearish);
+    assert_eq!(m.pattern_id.typical_direction(), Some(Direction::Bearish));
+}
+
+#[test]
+fn test_hikkake_confirmed_follow_through() {
+    let mut bars = make_sideways(10);
+    bars.push(TestBar::new(100.0, 110.0, 90.0, 105.0));
+    // Inside bar, then a false break to the downside
+    bars.push(TestBar::new(104.0, 106.0, 98.0, 100.0));
+    bars.push(TestBar::new(99.0, 103.0, 95.0, 96.0));
+    // Drift back up, closing above the inside bar's high on the fourth bar
+    bars.push(TestBar::new(96.0, 101.0, 94.0, 100.0));
+    bars.push(TestBar::new(100.0, 104.0, 96.0, 103.0));
+    bars.push(TestBar::new(103.0, 105.0, 97.0, 104.0));
+    bars.push(TestBar::new(104.0, 109.0, 102.0, 108.0));
+
+    let scan = |detector: BuiltinDetector| {
+        let engine = EngineBuilder::new().add(detector).build().unwrap();
+        engine
+            .scan(&bars)
+            .unwrap()
+            .into_iter()
+            .filter(|p| p.end_index >= 10)
+            .map(|p| (p.pattern_id, p.direction, p.start_index, p.end_index))
+            .collect::<Vec<_>>()
+    };
+
+    // TA-Lib's 3-bar window misses the follow-through
+    assert_eq!(
+        scan(BuiltinDetector::Hikkake(HikkakeDetector)),
+        vec![(PatternId("CDL_HIKKAKE"), Direction::Bullish, 10, 12)]
+    );
+    assert_eq!(
+        scan(BuiltinDetector::HikkakeConfirmed(
+            HikkakeConfirmedDetector::default()
+        )),
+        vec![]
+    );
+    assert_eq!(
+        scan(BuiltinDetector::HikkakeConfirmed(
+            HikkakeConfirmedDetector { confirm_within: 4 }
+        )),
+        vec![(PatternId("HIKKAKE_CONFIRMED"), Direction::Bullish, 10, 16)]
+    );
+    assert_eq!(PatternId("HIKKAKE_CONFIRMED").typical_direction(), None);
+}
+
+#[test]
+fn test_piercing_dark_cloud_second_long_body() {
+    // Ten black candles with 5-point bodies, a long black, then a white that
+    // pierces the midpoint but whose 4.8-point body is below the average
+    let mut piercing: Vec<TestBar> = (0..10)
+        .map(|i| {
+            let base = 130.0 - (i as f64) * 2.0;
+            TestBar::new(base + 2.5, base + 3.0, base - 3.0, base - 2.5)
+        })
+        .collect();
+    piercing.push(TestBar::new(110.0, 110.5, 101.5, 102.0));
+    piercing.push(TestBar::new(101.4, 106.5, 101.0, 106.2));
+
+    let fires = |detector: BuiltinDetector, bars: &[TestBar]| {
+        let engine = EngineBuilder::new().add(detector).build().unwrap();
+        engine
+            .scan(bars)
+            .unwrap()
+            .iter()
+            .any(|p| p.end_index == bars.len() - 1)
+    };
+
+    assert!(!fires(
+        BuiltinDetector::Piercing(PiercingDetector::default()),
+        &piercing
+    ));
+    assert!(fires(
+        BuiltinDetector::Piercing(PiercingDetector {
+            require_second_long_body: false,
+            ..Default::default()
+        }),
+        &piercing
+    ));
+
+    // Mirror image: TA-Lib's dark cloud cover accepts the short black candle
+    let mut dark_cloud: Vec<TestBar> = (0..10)
+        .map(|i| {
+            let base = 80.0 + (i as f64) * 2.0;
+            TestBar::new(base - 2.5, base + 3.0, base - 3.0, base + 2.5)
+        })
+        .collect();
+    dark_cloud.push(TestBar::new(100.0, 108.5, 99.5, 108.0));
+    dark_cloud.push(TestBar::new(108.6, 109.0, 103.5, 103.8));
+
+    assert!(fires(
+        BuiltinDetector::DarkCloudCover(DarkCloudCoverDetector::default()),
+        &dark_cloud
+    ));
+    assert!(!fires(
+        BuiltinDetector::DarkCloudCover(DarkCloudCoverDetector {
+            require_second_long_body: true,
+            ..Default::default()
+        }),
+        &dark_cloud
+    ));
+
+    // Selectable through params, and absent from older serialized configs
+    use std::collections::HashMap;
+    let params = HashMap::from([("require_second_long_body", 0.0)]);
+    let textbook = PiercingDetector::with_params(&params).unwrap();
+    assert!(fires(BuiltinDetector::Piercing(textbook), &piercing));
+    let params = HashMap::from([("require_second_long_body", 1.0)]);
+    let strict = DarkCloudCoverDetector::with_params(&params).unwrap();
+    assert!(!fires(BuiltinDetector::DarkCloudCover(strict), &dark_cloud));
+
+    let legacy: PiercingDetector = serde_json::from_str(r#"{"min_pierce_ratio":0.5}"#).unwrap();
+    assert!(legacy.require_second_long_body);
+    let legacy: DarkCloudCoverDetector =
+        serde_json::from_str(r#"{"min_pierce_ratio":0.5}"#).unwrap();
+    assert!(!legacy.require_second_long_body);
+}
+
+#[test]
+fn test_three_white_soldiers_shadow_factor() {
+    let mut bars = make_sideways(10);
+    // Steady advance, but each upper shadow is a quarter of the bar's body
+    bars.push(TestBar::new(100.0, 105.0, 99.5, 104.0));
+    bars.push(TestBar::new(102.0, 107.0, 101.5, 106.0));
+    bars.push(TestBar::new(104.0, 109.0, 103.5, 108.0));
+
+    let fires = |detector: ThreeWhiteSoldiersDetector| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::ThreeWhiteSoldiers(detector))
+            .build()
+            .unwrap();
+        engine
+            .scan(&bars)
+            .unwrap()
+            .iter()
+            .any(|p| p.end_index == 12)
+    };
+
+    assert!(!fires(ThreeWhiteSoldiersDetector::default()));
+
+    let relaxed = ThreeWhiteSoldiersDetector::default()
+        .with_shadow_factor(0.5)
+        .and_then(|d| d.with_near_factor(0.3))
+        .and_then(|d| d.with_far_factor(0.6))
+        .unwrap();
+    assert!(fires(relaxed));
+
+    let defaults = ThreeWhiteSoldiersDetector::default;
+    assert!(defaults().with_shadow_factor(0.0).is_err());
+    assert!(defaults().with_near_factor(-0.2).is_err());
+    assert!(defaults().with_far_factor(f64::NAN).is_err());
+}
+
+#[test]
+fn test_neck_patterns_mutually_exclusive() {
+    use std::collections::HashMap;
+
+    let mut bars = make_sideways(10);
+    // Long black candle with a very short lower shadow
+    bars.push(TestBar::new(106.0, 106.5, 99.9, 100.0));
+    // White candle opening below the low, closing just above the prior close:
+    // within the Equal threshold of both the prior close and the prior low
+    bars.push(TestBar::new(99.5, 100.1, 99.4, 100.05));
+
+    let neck_ids = [
+        PatternId("CDL_INNECK"),
+        PatternId("CDL_ONNECK"),
+        PatternId("CDL_THRUSTING"),
+    ];
+    let fired = |on_neck: OnNeckDetector| -> Vec<PatternId> {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::OnNeck(on_neck))
+            .with_two_bar_defaults()
+            .build()
+            .unwrap();
+        let mut ids: Vec<PatternId> = engine
+            .scan(&bars)
+            .unwrap()
+            .iter()
+            .filter(|p| p.end_index == 11 && neck_ids.contains(&p.pattern_id))
+            .map(|p| p.pattern_id)
+            .collect();
+        ids.sort_by_key(|id| id.as_str());
+        ids
+    };
+    assert!(OnNeckDetector::default().exclusive);
+    assert_eq!(
+        fired(OnNeckDetector::default()),
+        vec![PatternId("CDL_INNECK")]
+    );
+
+    let ctx = MarketContext::default();
+    assert_eq!(
+        classify_neck(&bars, 11, &ctx, 1.0, 0.05),
+        Some(NeckPattern::InNeck)
+    );
+
+    // TA-Lib flags the overlapping On-Neck as well
+    let talib = OnNeckDetector::with_params(&HashMap::from([("exclusive", 0.0)])).unwrap();
+    assert!(!talib.exclusive);
+    assert_eq!(
+        fired(talib),
+        vec![PatternId("CDL_INNECK"), PatternId("CDL_ONNECK")]
+    );
+
+    // Configs serialized before the field existed load as exclusive
+    let legacy: OnNeckDetector = serde_json::from_str(r#"{"tolerance":0.01}"#).unwrap();
+    assert!(legacy.exclusive);
+}
+
+#[test]
+fn test_ladder_bottom_with_params() {
+    use std::collections::HashMap;
+
+    let mut bars = make_sideways(10);
+    // Three black candles stepping down
+    bars.push(TestBar::new(100.0, 100.2, 95.8, 96.0));
+    bars.push(TestBar::new(97.0, 97.2, 92.8, 93.0));
+    bars.push(TestBar::new(94.0, 94.2, 89.8, 90.0));
+    // Black candle with a long upper shadow, then a white candle above its high
+    bars.push(TestBar::new(91.0, 94.0, 87.8, 88.0));
+    bars.push(TestBar::new(92.0, 96.0, 91.8, 95.5));
+
+    let fires = |detector: LadderBottomDetector, bars: &[TestBar]| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::LadderBottom(detector))
+            .build()
+            .unwrap();
+        engine
+            .scan(bars)
+            .unwrap()
+            .iter()
+            .any(|p| p.end_index == bars.len() - 1)
+    };
+
+    let params = HashMap::from([("shadow_veryshort_factor", 0.1)]);
+    let detector = LadderBottomDetector::with_params(&params).unwrap();
+    assert!(fires(detector.clone(), &bars));
+
+    // Second close ticks up slightly: only the tolerant detector accepts it
+    bars[11].c = 96.2;
+    assert!(!fires(detector, &bars));
+    let params = HashMap::from([("close_tolerance", 0.1)]);
+    let tolerant = LadderBottomDetector::with_params(&params).unwrap();
+    assert!(fires(tolerant.clone(), &bars));
+
+    // The tolerance scales with the range, so it holds for negative prices
+    let shifted: Vec<TestBar> = bars
+        .iter()
+        .map(|b| TestBar::new(b.o - 200.0, b.h - 200.0, b.l - 200.0, b.c - 200.0))
+        .collect();
+    assert!(fires(tolerant, &shifted));
+    assert!(!fires(LadderBottomDetector::default(), &shifted));
+
+    let legacy: LadderBottomDetector =
+        serde_json::from_str(r#"{"shadow_veryshort_factor":0.1}"#).unwrap();
+    assert_eq!(legacy.close_tolerance.get(), 0.0);
+
+    let params = HashMap::from([("shadow_veryshort_factor", f64::NAN)]);
+    assert!(LadderBottomDetector::with_params(&params).is_err());
+}
+
+#[test]
+fn test_concealing_baby_swallow_graded_by_depth() {
+    let mut bars = make_downtrend(10);
+    // Two black marubozus, then a black gap-down candle with a long upper shadow
+    bars.push(TestBar::new(82.0, 82.0, 78.0, 78.0));
+    bars.push(TestBar::new(78.0, 78.0, 74.0, 74.0));
+    bars.push(TestBar::new(73.0, 75.0, 72.0, 72.5));
+    let setup = bars.clone();
+
+    let strength = |fourth: TestBar| {
+        let mut bars = setup.clone();
+        bars.push(fourth);
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::ConcealingBabySwallow(
+                ConcealingBabySwallowDetector::with_defaults(),
+            ))
+            .build()
+            .unwrap();
+        engine
+            .scan(&bars)
+            .unwrap()
+            .iter()
+            .find(|p| p.end_index == bars.len() - 1)
+            .map(|p| p.strength)
+            .expect("concealing baby swallow")
+    };
+
+    // Barely engulfs the third candle's range vs. swallows it with room to spare
+    let shallow = strength(TestBar::new(75.0, 75.2, 71.8, 72.0));
+    let deep = strength(TestBar::new(77.0, 77.2, 69.8, 70.0));
+    assert!(deep > shallow, "deep {deep} should beat shallow {shallow}");
+}
+
+#[test]
+fn test_morning_star_gap_tolerance_accepts_near_miss() {
+    let mut bars = make_downtrend(15);
+    bars.push(TestBar::new(70.0, 70.5, 64.5, 65.0)); // long black
+    bars.push(TestBar::new(65.1, 65.3, 63.5, 64.9)); // star overlaps the body by 0.1
+    bars.push(TestBar::new(64.5, 68.5, 64.3, 68.0)); // long white
+    let last = bars.len() - 1;
+
+    let matches_with = |tolerance: f64| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::MorningStar(MorningStarDetector {
+                gap_tolerance: Ratio::new(tolerance).unwrap(),
+                ..MorningStarDetector::with_defaults()
+            }))
+            .build()
+            .unwrap();
+        engine
+            .scan(&bars)
+            .unwrap()
+            .iter()
+            .any(|m| m.end_index == last)
+    };
+
+    assert!(!matches_with(0.0));
+    assert!(matches_with(0.1));
+}
+
+#[test]
+fn test_evening_star_gap_tolerance_limits_overlap() {
+    let mut bars = make_uptrend(15);
+    bars.push(TestBar::new(129.0, 134.5, 128.5, 134.0)); // long white
+    bars.push(TestBar::new(133.9, 135.5, 133.7, 134.1)); // star overlaps the body by 0.1
+    bars.push(TestBar::new(134.5, 134.7, 130.5, 131.0)); // long black
+    let last = bars.len() - 1;
+
+    let matches_with = |bars: &[TestBar], tolerance: f64| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::EveningStar(EveningStarDetector {
+                gap_tolerance: Ratio::new(tolerance).unwrap(),
+                ..EveningStarDetector::with_defaults()
+            }))
+            .build()
+            .unwrap();
+        engine
+            .scan(bars)
+            .unwrap()
+            .iter()
+            .any(|m| m.end_index == last)
+    };
+
+    assert!(!matches_with(&bars, 0.0));
+    assert!(matches_with(&bars, 0.1));
+    // 0.1 of a ~3.2 average range does not cover a 0.5 overlap
+    bars[last - 1] = TestBar::new(133.5, 135.5, 133.3, 133.7);
+    assert!(!matches_with(&bars, 0.1));
+}
+
+#[test]
+fn test_tasuki_gap_tolerance_accepts_near_miss() {
+    let mut bars = make_uptrend(15);
+    bars.push(TestBar::new(129.0, 131.5, 128.8, 131.0)); // white
+    bars.push(TestBar::new(130.9, 133.5, 130.8, 133.0)); // white, overlaps by 0.1
+    bars.push(TestBar::new(132.5, 132.7, 130.7, 130.8)); // black into the gap
+    let last = bars.len() - 1;
+
+    let matches_with = |tolerance: f64| {
+        let engine = EngineBuilder::new()
+            .add(BuiltinDetector::TasukiGap(TasukiGapDetector {
+                gap_tolerance: Ratio::new(tolerance).unwrap(),
+                ..TasukiGapDetector::with_defaults()
+            }))
+            .build()
+            .unwrap();
+        engine
+            .scan(&bars)
+            .unwrap()
+            .into_iter()
+            .find(|m| m.end_index == last)
+    };
+
+    assert!(matches_with(0.0).is_none());
+    let m = matches_with(0.1).expect("upside tasuki gap within tolerance");
+    assert_eq!(m.direction, Direction::Bullish);
+}
+
 // ============================================================
 // ENGINE API TESTS
 // ============================================================
@@ -151,6 +1041,60 @@ fn test_engine_with_all_defaults() {
     assert!(result.is_ok(), "Scan should not fail");
 }
 
+#[test]
+fn test_readme_pattern_counts() {
+    const README: &str = include_str!("../README.md");
+    // Number in the parentheses of the README line starting with `prefix`
+    let count = |prefix: &str| -> usize {
+        let line = README
+            .lines()
+            .find(|l| l.starts_with(prefix))
+            .unwrap_or_else(|| panic!("README has no line starting with {prefix:?}"));
+        let inner = &line[line.find('(').unwrap() + 1..];
+        let digits: String = inner.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().unwrap()
+    };
+    let registered = |builder: EngineBuilder| builder.build().unwrap().detector_ids().len();
+
+    let single = registered(EngineBuilder::new().with_single_bar_defaults());
+    let two = registered(EngineBuilder::new().with_two_bar_defaults());
+    let three = registered(EngineBuilder::new().with_three_bar_defaults());
+    let multi = registered(EngineBuilder::new().with_multi_bar_defaults());
+    let extended = registered(EngineBuilder::new().with_extended_defaults());
+    let price_action = registered(EngineBuilder::new().with_price_action_defaults());
+    let all = registered(EngineBuilder::new().with_all_defaults());
+
+    assert_eq!(count("### Single-Bar"), single);
+    assert_eq!(count("### Two-Bar"), two);
+    assert_eq!(count("### Three-Bar"), three);
+    assert_eq!(count("### Multi-Bar"), multi);
+    // The extended table also lists the opt-in HIKKAKE_CONFIRMED
+    assert_eq!(count("### Extended Patterns"), extended + 1);
+    assert_eq!(count("### Price Action"), price_action);
+    assert_eq!(count("// Only TA-Lib single-bar"), single);
+    assert_eq!(count("// Only TA-Lib two-bar"), two);
+    assert_eq!(count("// Only TA-Lib three-bar"), three);
+    assert_eq!(count("// Only TA-Lib multi-bar"), multi);
+    assert_eq!(count("// Only extended"), extended);
+    assert_eq!(count("// Only trend-independent"), price_action);
+    assert_eq!(count("// TA-Lib + Extended"), all);
+    assert_eq!(all, BUILTIN_PATTERN_COUNT - 1);
+
+    let summary = format!(
+        "- **{BUILTIN_PATTERN_COUNT} builtin patterns** (`BUILTIN_PATTERN_COUNT`): \
+         {} TA-Lib compatible",
+        single + two + three + multi
+    );
+    assert!(
+        README.contains(&summary),
+        "README summary should read {summary:?}"
+    );
+    assert!(README.contains(&format!(
+        "+ {} extended + {price_action} price action patterns",
+        extended + 1
+    )));
+}
+
 #[test]
 fn test_engine_with_single_bar_defaults() {
     let engine = EngineBuilder::new()
@@ -450,6 +1394,7 @@ impl PatternDetector for CustomDetector {
                 strength: 0.8,
                 start_index: index,
                 end_index: index,
+                metric: None,
             })
         } else {
             None
@@ -0,0 +1,115 @@
+//! Property tests feeding adversarial OHLCV data through the full engine.
+//!
+//! Scans must never panic and every emitted strength must be finite and
+//! within `0.0..=1.0`, whatever the input looks like.
+
+use proptest::prelude::*;
+use yacpd::prelude::*;
+
+#[derive(Debug, Clone, Copy)]
+struct TestBar {
+    o: f64,
+    h: f64,
+    l: f64,
+    c: f64,
+    v: f64,
+}
+
+impl OHLCV for TestBar {
+    fn open(&self) -> f64 {
+        self.o
+    }
+
+    fn high(&self) -> f64 {
+        self.h
+    }
+
+    fn low(&self) -> f64 {
+        self.l
+    }
+
+    fn close(&self) -> f64 {
+        self.c
+    }
+
+    fn volume(&self) -> f64 {
+        self.v
+    }
+}
+
+/// Prices covering zero, tiny, ordinary and extreme magnitudes
+fn price() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        Just(0.0),
+        Just(100.0),
+        1e-9..1e-3,
+        1.0..1e4,
+        1e100..1e300,
+        -1e4..0.0,
+    ]
+}
+
+/// Consistent bar: open/close inside [low, high]
+fn valid_bar() -> impl Strategy<Value = TestBar> {
+    (price(), 0.0..1.0f64, 0.0..1.0f64, 0.0..1e3f64, 0.0..1e6f64).prop_map(
+        |(low, o, c, span, v)| {
+            let high = low + span;
+            TestBar {
+                o: low + o * span,
+                h: high,
+                l: low,
+                c: low + c * span,
+                v,
+            }
+        },
+    )
+}
+
+/// Arbitrary bar with no ordering guarantees at all
+fn raw_bar() -> impl Strategy<Value = TestBar> {
+    (
+        price(),
+        price(),
+        price(),
+        price(),
+        prop_oneof![Just(0.0), 0.0..1e9],
+    )
+        .prop_map(|(o, h, l, c, v)| TestBar { o, h, l, c, v })
+}
+
+fn assert_strengths(bars: &[TestBar]) -> std::result::Result<(), TestCaseError> {
+    let engine = EngineBuilder::new().with_all_defaults().build().unwrap();
+    for m in engine.scan(bars).unwrap() {
+        prop_assert!(
+            m.strength.is_finite() && (0.0..=1.0).contains(&m.strength),
+            "{} strength {} at {}",
+            m.pattern_id.as_str(),
+            m.strength,
+            m.end_index
+        );
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn scan_valid_bars_yields_unit_strengths(bars in prop::collection::vec(valid_bar(), 0..60)) {
+        assert_strengths(&bars)?;
+    }
+
+    #[test]
+    fn scan_raw_bars_never_panics(bars in prop::collection::vec(raw_bar(), 0..60)) {
+        assert_strengths(&bars)?;
+    }
+
+    #[test]
+    fn scan_flat_bars_yields_unit_strengths(
+        price in price(),
+        n in 0usize..40,
+    ) {
+        let bars = vec![TestBar { o: price, h: price, l: price, c: price, v: 0.0 }; n];
+        assert_strengths(&bars)?;
+    }
+}
@@ -253,9 +253,38 @@ fn json_bars_to_test_bars(bars_json: &[Value]) -> Vec<TestBar> {
         .collect()
 }
 
-/// Build an engine with only the 61 standard TA-Lib patterns (no extended).
+/// Build an engine with only the 63 TA-Lib group detectors (no extended),
+/// covering TA-Lib's 61 CDL functions.
+///
+/// Trend-gated detectors are registered first with gating disabled, and
+/// On-Neck without exclusive classification, so they take precedence over the
+/// defaults and match TA-Lib's output.
 fn build_talib_engine() -> PatternEngine {
     EngineBuilder::new()
+        .add(BuiltinDetector::Hammer(HammerDetector {
+            require_trend: false,
+            ..Default::default()
+        }))
+        .add(BuiltinDetector::HangingMan(HangingManDetector {
+            require_trend: false,
+            ..Default::default()
+        }))
+        .add(BuiltinDetector::InvertedHammer(InvertedHammerDetector {
+            require_trend: false,
+            ..Default::default()
+        }))
+        .add(BuiltinDetector::ShootingStar(ShootingStarDetector {
+            require_trend: false,
+            ..Default::default()
+        }))
+        .add(BuiltinDetector::SeparatingLines(SeparatingLinesDetector {
+            require_trend: false,
+            ..Default::default()
+        }))
+        .add(BuiltinDetector::OnNeck(OnNeckDetector {
+            exclusive: false,
+            ..Default::default()
+        }))
         .with_single_bar_defaults()
         .with_two_bar_defaults()
         .with_three_bar_defaults()